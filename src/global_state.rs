@@ -25,8 +25,8 @@ pub struct GlobalState {
     pub(crate) emergency_collection: AtomicBool,
     /// Is the current GC triggered by the user?
     pub(crate) user_triggered_collection: AtomicBool,
-    /// Is the current GC triggered internally by MMTK? This is unused for now. We may have internally triggered GC
-    /// for a concurrent plan.
+    /// Is the current GC triggered internally by MMTK? A concurrent plan triggers a GC internally to
+    /// finish its concurrent work.
     pub(crate) internal_triggered_collection: AtomicBool,
     /// Is the last GC internally triggered?
     pub(crate) last_internal_triggered_collection: AtomicBool,
@@ -57,10 +57,13 @@ impl GlobalState {
 
     /// Set the collection kind for the current GC. This is called before
     /// scheduling collection to determin what kind of collection it will be.
+    /// `force_emergency` makes the collection an emergency collection regardless of the other
+    /// conditions.
     pub fn set_collection_kind(
         &self,
         last_collection_was_exhaustive: bool,
         heap_can_grow: bool,
+        force_emergency: bool,
     ) -> bool {
         self.cur_collection_attempts.store(
             if self.user_triggered_collection.load(Ordering::Relaxed) {
@@ -71,10 +74,11 @@ impl GlobalState {
             Ordering::Relaxed,
         );
 
-        let emergency_collection = !self.is_internal_triggered_collection()
-            && last_collection_was_exhaustive
-            && self.cur_collection_attempts.load(Ordering::Relaxed) > 1
-            && !heap_can_grow;
+        let emergency_collection = force_emergency
+            || (!self.is_internal_triggered_collection()
+                && last_collection_was_exhaustive
+                && self.cur_collection_attempts.load(Ordering::Relaxed) > 1
+                && !heap_can_grow);
         self.emergency_collection
             .store(emergency_collection, Ordering::Relaxed);
 
@@ -93,15 +97,16 @@ impl GlobalState {
     }
 
     fn is_internal_triggered_collection(&self) -> bool {
-        let is_internal_triggered = self
-            .last_internal_triggered_collection
-            .load(Ordering::SeqCst);
-        // Remove this assertion when we have concurrent GC.
-        assert!(
-            !is_internal_triggered,
-            "We have no concurrent GC implemented. We should not have internally triggered GC"
-        );
-        is_internal_triggered
+        self.last_internal_triggered_collection
+            .load(Ordering::SeqCst)
+    }
+
+    /// Mark the next GC as triggered internally by MMTk.
+    pub(crate) fn set_internal_triggered_collection(&self) {
+        self.last_internal_triggered_collection
+            .store(true, Ordering::Relaxed);
+        self.internal_triggered_collection
+            .store(true, Ordering::Relaxed);
     }
 
     pub fn is_emergency_collection(&self) -> bool {
@@ -567,6 +567,43 @@ pub fn total_bytes<VM: VMBinding>(mmtk: &MMTK<VM>) -> usize {
     mmtk.get_plan().get_total_pages() << LOG_BYTES_IN_PAGE
 }
 
+/// Set the heap size in bytes. This is only supported if the option `gc_trigger` is
+/// [`crate::util::options::GCTriggerSelector::Delegated`], and the size cannot be larger than the
+/// max heap size of the [`crate::util::heap::DelegatedGCTrigger`], or out of the bounds set by
+/// [`set_heap_size_bounds`]. The size is rounded up to pages.
+/// If the heap is smaller than the memory in use, the next allocation that polls the GC trigger
+/// will trigger a GC.
+///
+/// Returns whether the heap size was set.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `size`: The new heap size in bytes.
+pub fn set_heap_size<VM: VMBinding>(mmtk: &MMTK<VM>, size: usize) -> bool {
+    let pages = crate::util::conversions::bytes_to_pages_up(size);
+    mmtk.gc_trigger.policy.set_current_heap_size_in_pages(pages)
+}
+
+/// Change the min and max heap size in bytes after MMTk is initialized. The GC trigger moves the
+/// current heap size into the new bounds right away. If the memory in use is more than the new
+/// max heap size, a GC is requested, but this function does not block for it. For a fixed heap
+/// size, the heap size is set to `max`.
+///
+/// Returns an error if `min` is larger than `max`, `max` is zero or larger than the address space
+/// that MMTk reserves for the heap, or the GC trigger does not allow the bounds to be changed.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `min`: The new min heap size in bytes.
+/// * `max`: The new max heap size in bytes.
+pub fn set_heap_size_bounds<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+    min: usize,
+    max: usize,
+) -> Result<(), String> {
+    mmtk.set_heap_size_bounds(min, max)
+}
+
 /// The application code has requested a collection. This is just a GC hint, and
 /// we may ignore it.
 ///
@@ -584,6 +621,21 @@ pub fn handle_user_collection_request<VM: VMBinding>(
     mmtk.handle_user_collection_request(tls, false, false)
 }
 
+/// Tell MMTk that mutators are idle, or allocate slowly, until the deadline, e.g. between two
+/// frames of a UI. If the option `idle_gc` is set, MMTk may request a GC now if one is likely
+/// needed soon, and its first pause is expected to end before the deadline. For a concurrent plan,
+/// the rest of the GC may go on after the deadline. This function does not block for the GC. MMTk
+/// stops mutators as usual with [`crate::vm::Collection::stop_all_mutators`].
+///
+/// Returns whether a GC was requested.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `deadline`: When mutators are expected to be busy again.
+pub fn notify_idle<VM: VMBinding>(mmtk: &MMTK<VM>, deadline: std::time::Instant) -> bool {
+    mmtk.gc_trigger.notify_idle(deadline)
+}
+
 /// Is the object alive?
 ///
 /// Arguments:
@@ -753,6 +805,22 @@ pub fn harness_end<VM: VMBinding>(mmtk: &'static MMTK<VM>) {
     mmtk.harness_end();
 }
 
+/// Write the timeline of recent work packets, work buckets and GC phases in the Chrome trace event
+/// format, which can be opened with Perfetto UI or `chrome://tracing`. Only the most recent events
+/// are kept. The timeline can also be written into a file at `harness_end` with the
+/// `timeline_file` option.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `writer`: Where the timeline is written.
+#[cfg(feature = "timeline")]
+pub fn dump_timeline<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+    writer: impl std::io::Write,
+) -> std::io::Result<()> {
+    mmtk.scheduler.timeline.dump(writer)
+}
+
 /// Register a finalizable object. MMTk will retain the liveness of
 /// the object even if it is not reachable from the program.
 /// Note that finalization upon exit is not supported.
@@ -148,7 +148,13 @@ impl<VM: VMBinding> MMTK<VM> {
             *options.threads
         };
 
-        let scheduler = GCWorkScheduler::new(num_workers, (*options.thread_affinity).clone());
+        let scheduler = GCWorkScheduler::new(
+            num_workers,
+            (*options.thread_affinity).clone(),
+            *options.incremental_pause_budget,
+            *options.work_per_gc_thread,
+            *options.timeline_capacity,
+        );
 
         let state = Arc::new(GlobalState::default());
 
@@ -180,6 +186,24 @@ impl<VM: VMBinding> MMTK<VM> {
             },
         );
 
+        if options.incremental_pause_budget.is_incremental() && plan.concurrent().is_none() {
+            warn!(
+                "The plan {:?} does not do concurrent work.  The incremental pause budget is ignored.",
+                *options.plan
+            );
+        }
+
+        for budget in options.space_budgets.0.iter() {
+            let mut found = false;
+            plan.for_each_space(&mut |space| found |= space.get_name() == budget.space);
+            if !found {
+                warn!(
+                    "The plan {:?} does not have a space named {:?}.  Its budget is ignored.",
+                    *options.plan, budget.space
+                );
+            }
+        }
+
         // We haven't finished creating MMTk. No one is using the GC trigger. We cast the arc into a mutable reference.
         {
             // TODO: use Arc::get_mut_unchecked() when it is availble.
@@ -336,6 +360,23 @@ impl<VM: VMBinding> MMTK<VM> {
         self.stats.stop_all(self);
         self.inside_harness.store(false, Ordering::SeqCst);
         probe!(mmtk, harness_end);
+        #[cfg(feature = "timeline")]
+        self.dump_timeline_file();
+    }
+
+    /// Write the timeline into the file specified by the `timeline_file` option, if any.
+    #[cfg(feature = "timeline")]
+    fn dump_timeline_file(&self) {
+        let path = &*self.options.timeline_file;
+        if path.is_empty() {
+            return;
+        }
+        let result = std::fs::File::create(path)
+            .and_then(|file| self.scheduler.timeline.dump(std::io::BufWriter::new(file)));
+        match result {
+            Ok(()) => info!("Wrote the GC timeline to {}", path),
+            Err(e) => warn!("Failed to write the GC timeline to {}: {}", path, e),
+        }
     }
 
     #[cfg(feature = "sanity")]
@@ -444,16 +485,59 @@ impl<VM: VMBinding> MMTK<VM> {
         false
     }
 
+    /// Change the min and max heap size in bytes at run time. This is called by
+    /// [`crate::memory_manager::set_heap_size_bounds`].
+    pub fn set_heap_size_bounds(&self, min: usize, max: usize) -> Result<(), String> {
+        use crate::util::conversions::bytes_to_pages_up;
+        use crate::util::heap::layout::vm_layout::vm_layout;
+        use crate::vm::Collection;
+        if min > max {
+            return Err(format!(
+                "The min heap size ({} bytes) is larger than the max heap size ({} bytes)",
+                min, max
+            ));
+        }
+        if max == 0 {
+            return Err("The max heap size is zero".to_string());
+        }
+        let available = vm_layout().available_bytes();
+        if max > available {
+            return Err(format!(
+                "The max heap size ({} bytes) is larger than the address space for the heap ({} bytes)",
+                max, available
+            ));
+        }
+        if !self
+            .gc_trigger
+            .policy
+            .set_heap_size_bounds(bytes_to_pages_up(min), bytes_to_pages_up(max))
+        {
+            return Err(
+                "The GC trigger does not allow the heap size bounds to be changed".to_string(),
+            );
+        }
+        info!(
+            "Heap size bounds changed to [{}, {}] bytes. The heap size is {} pages.",
+            min,
+            max,
+            self.gc_trigger.policy.get_current_heap_size_in_pages()
+        );
+
+        // If the heap is over the new max heap size, do not wait for the next allocation to find it.
+        if self.get_plan().constraints().collects_garbage
+            && self.state.is_initialized()
+            && VM::VMCollection::is_collection_enabled()
+        {
+            self.gc_trigger.poll(false, None);
+        }
+        Ok(())
+    }
+
     /// MMTK has requested stop-the-world activity (e.g., stw within a concurrent gc).
-    // This is not used, as we do not have a concurrent plan.
+    // This is not used. Concurrent plans request their pauses from the last parked GC worker.
     #[allow(unused)]
     pub fn trigger_internal_collection_request(&self) {
-        self.state
-            .last_internal_triggered_collection
-            .store(true, Ordering::Relaxed);
-        self.state
-            .internal_triggered_collection
-            .store(true, Ordering::Relaxed);
+        self.state.set_internal_triggered_collection();
         // TODO: The current `GCRequester::request()` is probably incorrect for internally triggered GC.
         // Consider removing functions related to "internal triggered collection".
         self.gc_requester.request();
@@ -3,7 +3,10 @@
 use crate::vm::slot::{MemorySlice, Slot};
 use crate::vm::ObjectModel;
 use crate::{
-    util::{metadata::MetadataSpec, *},
+    util::{
+        metadata::{side_metadata::SideMetadataSpec, MetadataSpec},
+        *,
+    },
     vm::VMBinding,
 };
 use atomic::Ordering;
@@ -21,6 +24,10 @@ pub enum BarrierSelector {
     NoBarrier,
     /// Object remembering barrier is used.
     ObjectBarrier,
+    /// Snapshot-at-the-beginning (SATB) deletion barrier is used.
+    SATBBarrier,
+    /// Coalescing field remembering barrier is used.
+    FieldLoggingBarrier,
 }
 
 impl BarrierSelector {
@@ -171,39 +178,48 @@ impl<S: BarrierSemantics> ObjectBarrier<S> {
         Self { semantics }
     }
 
-    /// Attepmt to atomically log an object.
-    /// Returns true if the object is not logged previously.
+    /// Check if an object is unlogged.
     fn object_is_unlogged(&self, object: ObjectReference) -> bool {
-        unsafe { S::UNLOG_BIT_SPEC.load::<S::VM, u8>(object, None) != 0 }
+        object_is_unlogged::<S>(object)
     }
 
     /// Attepmt to atomically log an object.
     /// Returns true if the object is not logged previously.
     fn log_object(&self, object: ObjectReference) -> bool {
-        #[cfg(all(feature = "vo_bit", feature = "extreme_assertions"))]
-        debug_assert!(
-            crate::util::metadata::vo_bit::is_vo_bit_set(object),
-            "object bit is unset"
-        );
-        loop {
-            let old_value =
-                S::UNLOG_BIT_SPEC.load_atomic::<S::VM, u8>(object, None, Ordering::SeqCst);
-            if old_value == 0 {
-                return false;
-            }
-            if S::UNLOG_BIT_SPEC
-                .compare_exchange_metadata::<S::VM, u8>(
-                    object,
-                    1,
-                    0,
-                    None,
-                    Ordering::SeqCst,
-                    Ordering::SeqCst,
-                )
-                .is_ok()
-            {
-                return true;
-            }
+        log_object::<S>(object)
+    }
+}
+
+/// Check if an object is unlogged, using the unlog bit of the barrier semantics.
+fn object_is_unlogged<S: BarrierSemantics>(object: ObjectReference) -> bool {
+    unsafe { S::UNLOG_BIT_SPEC.load::<S::VM, u8>(object, None) != 0 }
+}
+
+/// Attepmt to atomically log an object, using the unlog bit of the barrier semantics.
+/// Returns true if the object is not logged previously.
+pub(crate) fn log_object<S: BarrierSemantics>(object: ObjectReference) -> bool {
+    #[cfg(all(feature = "vo_bit", feature = "extreme_assertions"))]
+    debug_assert!(
+        crate::util::metadata::vo_bit::is_vo_bit_set(object),
+        "object bit is unset"
+    );
+    loop {
+        let old_value = S::UNLOG_BIT_SPEC.load_atomic::<S::VM, u8>(object, None, Ordering::SeqCst);
+        if old_value == 0 {
+            return false;
+        }
+        if S::UNLOG_BIT_SPEC
+            .compare_exchange_metadata::<S::VM, u8>(
+                object,
+                1,
+                0,
+                None,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .is_ok()
+        {
+            return true;
         }
     }
 }
@@ -250,3 +266,162 @@ impl<S: BarrierSemantics> Barrier<S::VM> for ObjectBarrier<S> {
         }
     }
 }
+
+/// Barrier semantics for a snapshot-at-the-beginning (SATB) barrier.
+///
+/// In addition to the slow-path calls, the semantics tells the fast-path whether a snapshot is
+/// currently being traced.  The slow-paths are only invoked while it is.
+pub trait SATBBarrierSemantics: BarrierSemantics {
+    /// Return true if concurrent marking is in progress, and overwritten references need to be
+    /// remembered.
+    fn is_marking_active(&self) -> bool;
+}
+
+/// Generic snapshot-at-the-beginning deletion barrier with a type argument defining its
+/// slow-path behaviour.
+///
+/// This is a pre-write barrier.  While concurrent marking is active, it gives the slow-path a
+/// chance to remember the old value of a slot before the slot is overwritten, so that all objects
+/// reachable at the beginning of marking are eventually traced.
+pub struct SATBBarrier<S: SATBBarrierSemantics> {
+    semantics: S,
+}
+
+impl<S: SATBBarrierSemantics> SATBBarrier<S> {
+    pub fn new(semantics: S) -> Self {
+        Self { semantics }
+    }
+}
+
+impl<S: SATBBarrierSemantics> Barrier<S::VM> for SATBBarrier<S> {
+    fn flush(&mut self) {
+        self.semantics.flush();
+    }
+
+    fn object_reference_write_pre(
+        &mut self,
+        src: ObjectReference,
+        slot: <S::VM as VMBinding>::VMSlot,
+        target: Option<ObjectReference>,
+    ) {
+        if self.semantics.is_marking_active() {
+            self.object_reference_write_slow(src, slot, target);
+        }
+    }
+
+    fn object_reference_write_slow(
+        &mut self,
+        src: ObjectReference,
+        slot: <S::VM as VMBinding>::VMSlot,
+        target: Option<ObjectReference>,
+    ) {
+        self.semantics
+            .object_reference_write_slow(src, slot, target);
+    }
+
+    fn memory_region_copy_pre(
+        &mut self,
+        src: <S::VM as VMBinding>::VMMemorySlice,
+        dst: <S::VM as VMBinding>::VMMemorySlice,
+    ) {
+        if self.semantics.is_marking_active() {
+            self.semantics.memory_region_copy_slow(src, dst);
+        }
+    }
+
+    fn object_probable_write(&mut self, obj: ObjectReference) {
+        if self.semantics.is_marking_active() {
+            self.semantics.object_probable_write_slow(obj);
+        }
+    }
+}
+
+/// The unlog bits of the fields used by [`FieldLoggingBarrier`].  A field is unlogged if its bit
+/// is set.  The bits are indexed by the addresses of the slots (see [`Slot::to_address`]).
+pub(crate) const FIELD_UNLOG_BIT_SPEC: SideMetadataSpec =
+    crate::util::metadata::side_metadata::spec_defs::FIELD_UNLOG_BIT;
+
+/// Check if a field is unlogged.
+pub(crate) fn field_is_unlogged(slot: Address) -> bool {
+    unsafe { FIELD_UNLOG_BIT_SPEC.load::<u8>(slot) != 0 }
+}
+
+/// Attempt to atomically log a field.
+/// Returns true if the field is not logged previously.
+pub(crate) fn log_field(slot: Address) -> bool {
+    FIELD_UNLOG_BIT_SPEC.fetch_and_atomic::<u8>(slot, 0, Ordering::SeqCst) != 0
+}
+
+/// Unlog a field, so that the next modification of the field invokes the slow-path again.
+pub(crate) fn unlog_field(slot: Address) {
+    FIELD_UNLOG_BIT_SPEC.store_atomic::<u8>(slot, 1, Ordering::SeqCst);
+}
+
+/// Log all the fields of an object.  This is used when the memory of the object is reclaimed, so
+/// that the barrier ignores the fields of new objects allocated in its place.
+pub(crate) fn log_all_fields<VM: VMBinding>(object: ObjectReference) {
+    let start = VM::VMObjectModel::ref_to_object_start(object);
+    let size = VM::VMObjectModel::get_current_size(object);
+    FIELD_UNLOG_BIT_SPEC.bzero_metadata(start, size);
+}
+
+/// Generic coalescing field barrier with a type argument defining its slow-path behaviour.
+///
+/// This is a pre-write barrier.  The first time an unlogged field is about to be modified, the
+/// field is logged, and the slow-path is invoked before the write happens, so that it can
+/// remember the old value of the field.  Further writes to a logged field are coalesced, and do
+/// not invoke the slow-path until the field is unlogged again by the plan.  Fields are logged
+/// independently, so writing one field of an object does not remember the other fields.
+///
+/// Slices and probable writes do not name the fields that are modified.  The slow-path is always
+/// invoked for them, and is responsible for logging the fields with [`log_field`].
+pub struct FieldLoggingBarrier<S: BarrierSemantics> {
+    semantics: S,
+}
+
+impl<S: BarrierSemantics> FieldLoggingBarrier<S> {
+    pub fn new(semantics: S) -> Self {
+        Self { semantics }
+    }
+}
+
+impl<S: BarrierSemantics> Barrier<S::VM> for FieldLoggingBarrier<S> {
+    fn flush(&mut self) {
+        self.semantics.flush();
+    }
+
+    fn object_reference_write_pre(
+        &mut self,
+        src: ObjectReference,
+        slot: <S::VM as VMBinding>::VMSlot,
+        target: Option<ObjectReference>,
+    ) {
+        if field_is_unlogged(slot.to_address()) {
+            self.object_reference_write_slow(src, slot, target);
+        }
+    }
+
+    fn object_reference_write_slow(
+        &mut self,
+        src: ObjectReference,
+        slot: <S::VM as VMBinding>::VMSlot,
+        target: Option<ObjectReference>,
+    ) {
+        if log_field(slot.to_address()) {
+            self.semantics
+                .object_reference_write_slow(src, slot, target);
+        }
+    }
+
+    fn memory_region_copy_pre(
+        &mut self,
+        src: <S::VM as VMBinding>::VMMemorySlice,
+        dst: <S::VM as VMBinding>::VMMemorySlice,
+    ) {
+        self.semantics.memory_region_copy_slow(src, dst);
+    }
+
+    fn object_probable_write(&mut self, obj: ObjectReference) {
+        self.semantics.object_probable_write_slow(obj);
+    }
+}
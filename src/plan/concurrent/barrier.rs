@@ -0,0 +1,104 @@
+//! Read/Write barrier implementations for concurrent plans.
+
+use super::concurrent_marking_work::ConcurrentTraceObjects;
+use super::global::ConcurrentPlan;
+use crate::plan::barriers::{BarrierSemantics, SATBBarrierSemantics as SATBSemantics};
+use crate::plan::PlanTraceObject;
+use crate::plan::VectorQueue;
+use crate::scheduler::WorkBucketStage;
+use crate::util::*;
+use crate::vm::slot::{MemorySlice, Slot};
+use crate::vm::{Scanning, VMBinding};
+use crate::MMTK;
+
+/// The slow-path semantics of the snapshot-at-the-beginning deletion barrier.  It remembers the
+/// objects referred to by the slots that are about to be overwritten during concurrent marking,
+/// and hands them to GC workers to trace.
+pub struct SATBBarrierSemantics<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> {
+    /// MMTk instance
+    mmtk: &'static MMTK<VM>,
+    /// Concurrent plan
+    plan: &'static P,
+    /// SATB buffer. Contains a list of objects that were referenced by overwritten slots.
+    satb: VectorQueue<ObjectReference>,
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> SATBBarrierSemantics<VM, P> {
+    pub fn new(mmtk: &'static MMTK<VM>, plan: &'static P) -> Self {
+        Self {
+            mmtk,
+            plan,
+            satb: VectorQueue::new(),
+        }
+    }
+
+    fn enqueue(&mut self, object: ObjectReference) {
+        self.satb.push(object);
+        if self.satb.is_full() {
+            self.flush_satb();
+        }
+    }
+
+    fn enqueue_slot(&mut self, slot: VM::VMSlot) {
+        if let Some(old) = slot.load() {
+            self.enqueue(old);
+        }
+    }
+
+    fn flush_satb(&mut self) {
+        let buf = self.satb.take();
+        if !buf.is_empty() {
+            self.mmtk.scheduler.work_buckets[WorkBucketStage::Concurrent]
+                .add(ConcurrentTraceObjects::<VM, P>::new(buf, self.plan));
+        }
+    }
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> BarrierSemantics
+    for SATBBarrierSemantics<VM, P>
+{
+    type VM = VM;
+
+    fn flush(&mut self) {
+        self.flush_satb();
+    }
+
+    fn object_reference_write_slow(
+        &mut self,
+        _src: ObjectReference,
+        slot: VM::VMSlot,
+        _target: Option<ObjectReference>,
+    ) {
+        // This is called before the store.  Remember the old referent.
+        self.enqueue_slot(slot);
+    }
+
+    fn memory_region_copy_slow(&mut self, _src: VM::VMMemorySlice, dst: VM::VMMemorySlice) {
+        // All the slots in the destination are about to be overwritten.
+        for slot in dst.iter_slots() {
+            self.enqueue_slot(slot);
+        }
+    }
+
+    fn object_probable_write_slow(&mut self, obj: ObjectReference) {
+        // Any field of the object may be overwritten without a barrier.  Remember all of its
+        // current referents.
+        let tls = VMWorkerThread(VMThread::UNINITIALIZED);
+        if VM::VMScanning::support_slot_enqueuing(tls, obj) {
+            VM::VMScanning::scan_object(tls, obj, &mut |slot: VM::VMSlot| self.enqueue_slot(slot));
+        } else {
+            VM::VMScanning::scan_object_and_trace_edges(tls, obj, &mut |object| {
+                self.enqueue(object);
+                object
+            });
+        }
+    }
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> SATBSemantics
+    for SATBBarrierSemantics<VM, P>
+{
+    fn is_marking_active(&self) -> bool {
+        self.plan.concurrent_work_in_progress()
+    }
+}
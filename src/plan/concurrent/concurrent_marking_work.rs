@@ -0,0 +1,276 @@
+use super::global::ConcurrentPlan;
+use super::Pause;
+use crate::plan::ObjectQueue;
+use crate::plan::PlanTraceObject;
+use crate::plan::VectorObjectQueue;
+use crate::policy::immix::TRACE_KIND_FAST;
+use crate::scheduler::gc_work::{ProcessEdgesBase, ScanObjectsWork, SlotOf};
+use crate::scheduler::{GCWork, GCWorker, ProcessEdgesWork, WorkBucketStage};
+use crate::util::constants::LOG_BYTES_IN_ADDRESS;
+use crate::util::ObjectReference;
+use crate::vm::slot::{MemorySlice, Slot};
+use crate::vm::{Scanning, VMBinding};
+use crate::MMTK;
+use std::ops::{Deref, DerefMut};
+
+/// Trace a list of objects and compute their transitive closure while mutators are running.
+///
+/// The objects come from root scanning in an initial-mark pause, and from the buffers of the
+/// snapshot-at-the-beginning barrier.  Concurrent marking never moves objects, so the outgoing
+/// edges of the objects are loaded but never updated.
+pub struct ConcurrentTraceObjects<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> {
+    plan: &'static P,
+    /// Objects to trace.
+    objects: Option<Vec<ObjectReference>>,
+    /// Whether `objects` have already been marked by the packet that created this packet, and only
+    /// need to be scanned.
+    marked: bool,
+    /// Objects that are reached from the objects we have scanned, and are yet to be traced.
+    next_objects: VectorObjectQueue,
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>>
+    ConcurrentTraceObjects<VM, P>
+{
+    /// The number of objects a packet scans before it leaves the rest of the closure to new
+    /// packets.  This keeps each packet short, so that the budget of incremental pauses, which is
+    /// checked between packets, bounds the pause time even if the objects form a long chain.
+    const SCAN_LIMIT: usize = 1024;
+
+    pub fn new(objects: Vec<ObjectReference>, plan: &'static P) -> Self {
+        Self::with_objects(objects, false, plan)
+    }
+
+    fn with_objects(objects: Vec<ObjectReference>, marked: bool, plan: &'static P) -> Self {
+        Self {
+            plan,
+            objects: Some(objects),
+            marked,
+            next_objects: VectorObjectQueue::default(),
+        }
+    }
+
+    /// Hand the objects yet to be scanned to a new work packet so that other workers can help.
+    fn flush(&mut self, mmtk: &'static MMTK<VM>) {
+        if !self.next_objects.is_empty() {
+            let objects = self.next_objects.take();
+            mmtk.scheduler.work_buckets[WorkBucketStage::Concurrent]
+                .add(Self::with_objects(objects, true, self.plan));
+        }
+    }
+
+    fn trace_object(&mut self, object: ObjectReference, worker: &mut GCWorker<VM>) {
+        self.plan
+            .trace_object::<VectorObjectQueue, TRACE_KIND_FAST>(
+                &mut self.next_objects,
+                object,
+                worker,
+            );
+    }
+
+    fn scan_and_enqueue(&mut self, object: ObjectReference, worker: &mut GCWorker<VM>) {
+        let tls = worker.tls;
+        if VM::VMScanning::support_slot_enqueuing(tls, object) {
+            if let Some(slice) = VM::VMScanning::reference_array_slice(tls, object) {
+                // Large arrays are traced in separate packets that can be split.
+                let work = ConcurrentTraceSlice::new(slice, self.plan);
+                if work.estimated_cost() > GCWorker::<VM>::LONG_WORK_PACKET_COST {
+                    worker.mmtk.scheduler.work_buckets[WorkBucketStage::Concurrent].add(work);
+                } else {
+                    for slot in work.slice.iter_slots() {
+                        if let Some(child) = slot.load() {
+                            self.trace_object(child, worker);
+                        }
+                    }
+                }
+            } else {
+                VM::VMScanning::scan_object(tls, object, &mut |slot: VM::VMSlot| {
+                    if let Some(child) = slot.load() {
+                        self.trace_object(child, worker);
+                    }
+                });
+            }
+        } else {
+            // Concurrent marking never moves objects, so we always return the same object.
+            VM::VMScanning::scan_object_and_trace_edges(tls, object, &mut |child| {
+                self.trace_object(child, worker);
+                child
+            });
+        }
+        self.plan.post_scan_object(object);
+    }
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> GCWork<VM>
+    for ConcurrentTraceObjects<VM, P>
+{
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, mmtk: &'static MMTK<VM>) {
+        let objects = self.objects.take().unwrap();
+        if self.plan.current_pause() == Some(Pause::InitialMark) {
+            // Objects reached from roots in an initial-mark pause are traced after mutators
+            // resume.
+            mmtk.scheduler.work_buckets[WorkBucketStage::Concurrent].add(Self::with_objects(
+                objects,
+                self.marked,
+                self.plan,
+            ));
+            return;
+        }
+
+        if self.marked {
+            for object in objects {
+                self.next_objects.push(object);
+            }
+        } else {
+            for object in objects {
+                self.trace_object(object, worker);
+            }
+        }
+        // Scan the objects we have just marked, and trace their children.
+        let mut scanned = 0;
+        while !self.next_objects.is_empty() {
+            if scanned >= Self::SCAN_LIMIT {
+                // Leave the rest to another packet, which may be executed in another pause.
+                self.flush(mmtk);
+                break;
+            }
+            let objects = self.next_objects.take();
+            scanned += objects.len();
+            for object in objects {
+                self.scan_and_enqueue(object, worker);
+                if self.next_objects.is_full() {
+                    self.flush(mmtk);
+                }
+            }
+        }
+    }
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> ScanObjectsWork<VM>
+    for ConcurrentTraceObjects<VM, P>
+{
+    type E = ProcessRootSlots<VM, P>;
+
+    fn post_scan_object(&self, object: ObjectReference) {
+        self.plan.post_scan_object(object);
+    }
+
+    fn get_bucket(&self) -> WorkBucketStage {
+        WorkBucketStage::Concurrent
+    }
+}
+
+/// Trace the objects pointed by the slots of a memory slice while mutators are running, usually
+/// the elements of a large reference array reported by [`Scanning::reference_array_slice`].  Like
+/// [`crate::scheduler::gc_work::ScanSlice`], long packets are split so that different workers can
+/// trace parts of the slice, and each part is short enough for the budget of incremental pauses.
+pub struct ConcurrentTraceSlice<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> {
+    plan: &'static P,
+    slice: VM::VMMemorySlice,
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> ConcurrentTraceSlice<VM, P> {
+    fn new(slice: VM::VMMemorySlice, plan: &'static P) -> Self {
+        Self { plan, slice }
+    }
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> GCWork<VM>
+    for ConcurrentTraceSlice<VM, P>
+{
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, mmtk: &'static MMTK<VM>) {
+        // The objects marked here are scanned by `ConcurrentTraceObjects` packets.
+        let mut marked = VectorObjectQueue::default();
+        let flush = |marked: &mut VectorObjectQueue| {
+            mmtk.scheduler.work_buckets[WorkBucketStage::Concurrent].add(
+                ConcurrentTraceObjects::with_objects(marked.take(), true, self.plan),
+            );
+        };
+        for slot in self.slice.iter_slots() {
+            if let Some(child) = slot.load() {
+                self.plan
+                    .trace_object::<VectorObjectQueue, TRACE_KIND_FAST>(&mut marked, child, worker);
+                if marked.is_full() {
+                    flush(&mut marked);
+                }
+            }
+        }
+        if !marked.is_empty() {
+            flush(&mut marked);
+        }
+    }
+
+    fn estimated_cost(&self) -> usize {
+        // Assume that slots are words.
+        self.slice.bytes() >> LOG_BYTES_IN_ADDRESS
+    }
+
+    fn split_off(&mut self) -> Option<Box<dyn GCWork<VM>>> {
+        let rest = self.slice.split_off(self.slice.bytes() / 2)?;
+        Some(Box::new(Self::new(rest, self.plan)))
+    }
+}
+
+/// Process root slots in an initial-mark pause.  It does not trace the objects.  Instead, the
+/// objects pointed by the root slots are passed to [`ConcurrentTraceObjects`] which traces them
+/// concurrently with mutators.
+pub struct ProcessRootSlots<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> {
+    base: ProcessEdgesBase<VM>,
+    plan: &'static P,
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> ProcessEdgesWork
+    for ProcessRootSlots<VM, P>
+{
+    type VM = VM;
+    type ScanObjectsWorkType = ConcurrentTraceObjects<VM, P>;
+    const OVERWRITE_REFERENCE: bool = false;
+    const SCAN_OBJECTS_IMMEDIATELY: bool = true;
+
+    fn new(
+        slots: Vec<SlotOf<Self>>,
+        roots: bool,
+        mmtk: &'static MMTK<Self::VM>,
+        bucket: WorkBucketStage,
+    ) -> Self {
+        let base = ProcessEdgesBase::new(slots, roots, mmtk, bucket);
+        let plan = mmtk.get_plan().downcast_ref::<P>().unwrap();
+        Self { base, plan }
+    }
+
+    fn trace_object(&mut self, object: ObjectReference) -> ObjectReference {
+        // Only remember the object.  It will be traced by `ConcurrentTraceObjects`.
+        self.base.nodes.enqueue(object);
+        object
+    }
+
+    fn flush(&mut self) {
+        let nodes = self.pop_nodes();
+        if !nodes.is_empty() {
+            self.mmtk().scheduler.work_buckets[WorkBucketStage::Concurrent]
+                .add(ConcurrentTraceObjects::new(nodes, self.plan));
+        }
+    }
+
+    fn create_scan_work(&self, nodes: Vec<ObjectReference>) -> Self::ScanObjectsWorkType {
+        ConcurrentTraceObjects::new(nodes, self.plan)
+    }
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> Deref
+    for ProcessRootSlots<VM, P>
+{
+    type Target = ProcessEdgesBase<VM>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl<VM: VMBinding, P: ConcurrentPlan<VM = VM> + PlanTraceObject<VM>> DerefMut
+    for ProcessRootSlots<VM, P>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
@@ -0,0 +1,14 @@
+use super::Pause;
+use crate::plan::Plan;
+
+/// This trait includes methods that are specific to concurrent plans. This trait needs
+/// to be object safe.
+pub trait ConcurrentPlan: Plan {
+    /// Is there concurrent work (such as concurrent marking) in progress?  If so, GC workers will
+    /// keep executing packets in the `Concurrent` bucket after mutators resume, and the barrier
+    /// will remember the references it needs to.
+    fn concurrent_work_in_progress(&self) -> bool;
+
+    /// Return the kind of the current pause, or `None` if no pause is in progress.
+    fn current_pause(&self) -> Option<Pause>;
+}
@@ -0,0 +1,66 @@
+use super::global::ConcurrentImmix;
+use crate::plan::concurrent::concurrent_marking_work::ProcessRootSlots;
+use crate::policy::gc_work::TraceKind;
+use crate::policy::gc_work::TRACE_KIND_TRANSITIVE_PIN;
+use crate::scheduler::gc_work::PlanProcessEdges;
+use crate::scheduler::{GCWork, GCWorker};
+use crate::vm::VMBinding;
+use crate::MMTK;
+
+pub(super) struct ConcurrentImmixSTWGCWorkContext<VM: VMBinding, const KIND: TraceKind>(
+    std::marker::PhantomData<VM>,
+);
+impl<VM: VMBinding, const KIND: TraceKind> crate::scheduler::GCWorkContext
+    for ConcurrentImmixSTWGCWorkContext<VM, KIND>
+{
+    type VM = VM;
+    type PlanType = ConcurrentImmix<VM>;
+    type DefaultProcessEdges = PlanProcessEdges<VM, ConcurrentImmix<VM>, KIND>;
+    type PinningProcessEdges = PlanProcessEdges<VM, ConcurrentImmix<VM>, TRACE_KIND_TRANSITIVE_PIN>;
+}
+
+/// The work context of an initial-mark pause.  Roots are only recorded in the pause, and are
+/// traced concurrently after mutators resume.
+pub(super) struct ConcurrentImmixGCWorkContext<VM: VMBinding>(std::marker::PhantomData<VM>);
+impl<VM: VMBinding> crate::scheduler::GCWorkContext for ConcurrentImmixGCWorkContext<VM> {
+    type VM = VM;
+    type PlanType = ConcurrentImmix<VM>;
+    type DefaultProcessEdges = ProcessRootSlots<VM, ConcurrentImmix<VM>>;
+    type PinningProcessEdges = ProcessRootSlots<VM, ConcurrentImmix<VM>>;
+}
+
+/// Start concurrent marking at the end of an initial-mark pause.
+pub(super) struct StartConcurrentMarking<VM: VMBinding> {
+    plan: &'static ConcurrentImmix<VM>,
+}
+
+impl<VM: VMBinding> StartConcurrentMarking<VM> {
+    pub fn new(plan: &'static ConcurrentImmix<VM>) -> Self {
+        Self { plan }
+    }
+}
+
+impl<VM: VMBinding> GCWork<VM> for StartConcurrentMarking<VM> {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        trace!("StartConcurrentMarking");
+        self.plan.set_concurrent_marking(true);
+    }
+}
+
+/// Finish concurrent marking at the end of the transitive closure of a final-mark pause.
+pub(super) struct FinishConcurrentMarking<VM: VMBinding> {
+    plan: &'static ConcurrentImmix<VM>,
+}
+
+impl<VM: VMBinding> FinishConcurrentMarking<VM> {
+    pub fn new(plan: &'static ConcurrentImmix<VM>) -> Self {
+        Self { plan }
+    }
+}
+
+impl<VM: VMBinding> GCWork<VM> for FinishConcurrentMarking<VM> {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        trace!("FinishConcurrentMarking");
+        self.plan.set_concurrent_marking(false);
+    }
+}
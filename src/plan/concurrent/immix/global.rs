@@ -0,0 +1,280 @@
+use super::gc_work::{
+    ConcurrentImmixGCWorkContext, ConcurrentImmixSTWGCWorkContext, FinishConcurrentMarking,
+    StartConcurrentMarking,
+};
+use super::mutator::ALLOCATOR_MAPPING;
+use crate::plan::barriers::BarrierSelector;
+use crate::plan::concurrent::global::ConcurrentPlan;
+use crate::plan::concurrent::Pause;
+use crate::plan::global::BasePlan;
+use crate::plan::global::CommonPlan;
+use crate::plan::global::CreateGeneralPlanArgs;
+use crate::plan::global::CreateSpecificPlanArgs;
+use crate::plan::immix::Immix;
+use crate::plan::AllocationSemantics;
+use crate::plan::Plan;
+use crate::plan::PlanConstraints;
+use crate::policy::immix::defrag::StatsForDefrag;
+use crate::policy::immix::ImmixSpaceArgs;
+use crate::policy::immix::{TRACE_KIND_DEFRAG, TRACE_KIND_FAST};
+use crate::policy::space::Space;
+use crate::scheduler::gc_work::{Prepare, StopMutators};
+use crate::scheduler::*;
+use crate::util::alloc::allocators::AllocatorSelector;
+use crate::util::copy::*;
+use crate::util::heap::gc_trigger::SpaceStats;
+use crate::util::heap::VMRequest;
+use crate::util::metadata::side_metadata::SideMetadataContext;
+use crate::vm::VMBinding;
+use crate::{policy::immix::ImmixSpace, util::opaque_pointer::VMWorkerThread};
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::Mutex;
+
+use atomic::Ordering;
+use enum_map::EnumMap;
+
+use mmtk_macros::{HasSpaces, PlanTraceObject};
+
+/// Start concurrent marking when this percentage of the heap that was free after the last GC
+/// has been allocated.
+const CONCURRENT_MARKING_THRESHOLD: usize = 50;
+
+#[derive(HasSpaces, PlanTraceObject)]
+pub struct ConcurrentImmix<VM: VMBinding> {
+    #[post_scan]
+    #[space]
+    #[copy_semantics(CopySemantics::DefaultCopy)]
+    pub immix_space: ImmixSpace<VM>,
+    #[parent]
+    pub common: CommonPlan<VM>,
+    last_gc_was_defrag: AtomicBool,
+    /// The kind of the pause in progress.
+    current_pause: Mutex<Option<Pause>>,
+    /// The kind of the last pause.
+    previous_pause: Mutex<Option<Pause>>,
+    /// Set if the heap is full, and the next pause should collect the whole heap in one go.
+    should_do_full_gc: AtomicBool,
+    /// Is concurrent marking in progress?  The SATB barrier is active if it is.
+    concurrent_marking_active: AtomicBool,
+    /// The number of used pages at the end of the last pause that reclaimed memory.
+    used_pages_after_last_gc: AtomicUsize,
+}
+
+/// The plan constraints for the concurrent immix plan.
+pub const CONCURRENT_IMMIX_CONSTRAINTS: PlanConstraints = PlanConstraints {
+    moves_objects: crate::policy::immix::DEFRAG,
+    // Max immix object size is half of a block.
+    max_non_los_default_alloc_bytes: crate::policy::immix::MAX_IMMIX_OBJECT_SIZE,
+    needs_prepare_mutator: false,
+    barrier: BarrierSelector::SATBBarrier,
+    needs_concurrent_workers: true,
+    ..PlanConstraints::default()
+};
+
+impl<VM: VMBinding> Plan for ConcurrentImmix<VM> {
+    fn collection_required(&self, space_full: bool, _space: Option<SpaceStats<Self::VM>>) -> bool {
+        if self.base().collection_required(self, space_full) {
+            self.should_do_full_gc.store(true, Ordering::SeqCst);
+            return true;
+        }
+
+        if self.concurrent_marking_in_progress() {
+            // Let concurrent marking finish.  Workers will request the final-mark pause.
+            return false;
+        }
+
+        let used_after_gc = self.used_pages_after_last_gc.load(Ordering::Relaxed);
+        let free_after_gc = self.get_total_pages().saturating_sub(used_after_gc);
+        let allocated = self.get_used_pages().saturating_sub(used_after_gc);
+        allocated * 100 >= free_after_gc * CONCURRENT_MARKING_THRESHOLD
+    }
+
+    fn last_collection_was_exhaustive(&self) -> bool {
+        if *self.previous_pause.lock().unwrap() == Some(Pause::InitialMark) {
+            // An initial-mark pause does not reclaim any memory.
+            return false;
+        }
+        ImmixSpace::<VM>::is_last_gc_exhaustive(self.last_gc_was_defrag.load(Ordering::Relaxed))
+    }
+
+    fn constraints(&self) -> &'static PlanConstraints {
+        &CONCURRENT_IMMIX_CONSTRAINTS
+    }
+
+    fn create_copy_config(&'static self) -> CopyConfig<Self::VM> {
+        use enum_map::enum_map;
+        CopyConfig {
+            copy_mapping: enum_map! {
+                CopySemantics::DefaultCopy => CopySelector::Immix(0),
+                _ => CopySelector::Unused,
+            },
+            space_mapping: vec![(CopySelector::Immix(0), &self.immix_space)],
+            constraints: &CONCURRENT_IMMIX_CONSTRAINTS,
+        }
+    }
+
+    fn schedule_collection(&'static self, scheduler: &GCWorkScheduler<VM>) {
+        let pause = self.select_pause();
+        *self.current_pause.lock().unwrap() = Some(pause);
+        match pause {
+            Pause::Full => Immix::<VM>::schedule_immix_full_heap_collection::<
+                ConcurrentImmix<VM>,
+                ConcurrentImmixSTWGCWorkContext<VM, TRACE_KIND_FAST>,
+                ConcurrentImmixSTWGCWorkContext<VM, TRACE_KIND_DEFRAG>,
+            >(self, &self.immix_space, scheduler),
+            Pause::InitialMark => self.schedule_initial_mark(scheduler),
+            Pause::FinalMark => self.schedule_final_mark(scheduler),
+        }
+    }
+
+    fn get_allocator_mapping(&self) -> &'static EnumMap<AllocationSemantics, AllocatorSelector> {
+        &ALLOCATOR_MAPPING
+    }
+
+    fn prepare(&mut self, tls: VMWorkerThread) {
+        match self.current_pause().unwrap() {
+            Pause::Full | Pause::InitialMark => {
+                self.common.prepare(tls, true);
+                self.immix_space.prepare(true, StatsForDefrag::new(self));
+            }
+            // The spaces have been prepared in the initial-mark pause.
+            Pause::FinalMark => {}
+        }
+    }
+
+    fn release(&mut self, tls: VMWorkerThread) {
+        match self.current_pause().unwrap() {
+            Pause::Full | Pause::FinalMark => {
+                self.common.release(tls, true);
+                // release the collected region
+                self.immix_space.release(true);
+            }
+            // Nothing is released until marking finishes.
+            Pause::InitialMark => {}
+        }
+    }
+
+    fn end_of_gc(&mut self, _tls: VMWorkerThread) {
+        self.last_gc_was_defrag
+            .store(self.immix_space.end_of_gc(), Ordering::Relaxed);
+        let pause = self.current_pause.get_mut().unwrap().take();
+        if pause != Some(Pause::InitialMark) {
+            self.used_pages_after_last_gc
+                .store(self.get_used_pages(), Ordering::Relaxed);
+        }
+        *self.previous_pause.get_mut().unwrap() = pause;
+    }
+
+    fn current_gc_may_move_object(&self) -> bool {
+        self.immix_space.in_defrag()
+    }
+
+    fn get_collection_reserved_pages(&self) -> usize {
+        self.immix_space.defrag_headroom_pages()
+    }
+
+    fn get_used_pages(&self) -> usize {
+        self.immix_space.reserved_pages() + self.common.get_used_pages()
+    }
+
+    fn base(&self) -> &BasePlan<VM> {
+        &self.common.base
+    }
+
+    fn base_mut(&mut self) -> &mut BasePlan<Self::VM> {
+        &mut self.common.base
+    }
+
+    fn common(&self) -> &CommonPlan<VM> {
+        &self.common
+    }
+
+    fn concurrent(&self) -> Option<&dyn ConcurrentPlan<VM = Self::VM>> {
+        Some(self)
+    }
+}
+
+impl<VM: VMBinding> ConcurrentImmix<VM> {
+    pub fn new(args: CreateGeneralPlanArgs<VM>) -> Self {
+        let mut plan_args = CreateSpecificPlanArgs {
+            global_args: args,
+            constraints: &CONCURRENT_IMMIX_CONSTRAINTS,
+            global_side_metadata_specs: SideMetadataContext::new_global_specs(&[]),
+        };
+        let immix = ConcurrentImmix {
+            immix_space: ImmixSpace::new(
+                plan_args.get_space_args("immix", true, false, VMRequest::discontiguous()),
+                ImmixSpaceArgs {
+                    reset_log_bit_in_major_gc: false,
+                    unlog_object_when_traced: false,
+                    #[cfg(feature = "vo_bit")]
+                    mixed_age: false,
+                },
+            ),
+            common: CommonPlan::new(plan_args),
+            last_gc_was_defrag: AtomicBool::new(false),
+            current_pause: Mutex::new(None),
+            previous_pause: Mutex::new(None),
+            should_do_full_gc: AtomicBool::new(false),
+            concurrent_marking_active: AtomicBool::new(false),
+            used_pages_after_last_gc: AtomicUsize::new(0),
+        };
+
+        immix.verify_side_metadata_sanity();
+
+        immix
+    }
+
+    fn select_pause(&self) -> Pause {
+        let should_do_full_gc = self.should_do_full_gc.swap(false, Ordering::SeqCst);
+        let global_state = &self.base().global_state;
+        if self.concurrent_marking_in_progress() {
+            // Finishing marking reclaims memory, too, even if the heap is full.
+            Pause::FinalMark
+        } else if should_do_full_gc
+            || global_state.is_user_triggered_collection()
+            || global_state.is_emergency_collection()
+        {
+            Pause::Full
+        } else {
+            Pause::InitialMark
+        }
+    }
+
+    /// Scan roots, and start concurrent marking.  Roots are traced after mutators resume.
+    fn schedule_initial_mark(&'static self, scheduler: &GCWorkScheduler<VM>) {
+        type Ctx<VM> = ConcurrentImmixGCWorkContext<VM>;
+        scheduler.work_buckets[WorkBucketStage::Unconstrained].add(StopMutators::<Ctx<VM>>::new());
+        scheduler.work_buckets[WorkBucketStage::Prepare].add(Prepare::<Ctx<VM>>::new(self));
+        scheduler.work_buckets[WorkBucketStage::InitialMark].add(StartConcurrentMarking::new(self));
+    }
+
+    /// Scan roots again, finish the transitive closure, and reclaim memory.
+    fn schedule_final_mark(&'static self, scheduler: &GCWorkScheduler<VM>) {
+        scheduler
+            .schedule_common_work::<ConcurrentImmixSTWGCWorkContext<VM, TRACE_KIND_FAST>>(self);
+        scheduler.work_buckets[WorkBucketStage::FinalMark].add(FinishConcurrentMarking::new(self));
+    }
+
+    fn concurrent_marking_in_progress(&self) -> bool {
+        self.concurrent_marking_active.load(Ordering::Acquire)
+    }
+
+    pub(super) fn set_concurrent_marking(&self, active: bool) {
+        // Objects allocated during concurrent marking are not in the snapshot, and are considered
+        // live in this GC.
+        self.immix_space.set_allocate_as_live(active);
+        self.concurrent_marking_active
+            .store(active, Ordering::Release);
+    }
+}
+
+impl<VM: VMBinding> ConcurrentPlan for ConcurrentImmix<VM> {
+    fn concurrent_work_in_progress(&self) -> bool {
+        self.concurrent_marking_in_progress()
+    }
+
+    fn current_pause(&self) -> Option<Pause> {
+        *self.current_pause.lock().unwrap()
+    }
+}
@@ -0,0 +1,71 @@
+// GITHUB-CI: MMTK_PLAN=ConcurrentImmix
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::*;
+
+/// The array is long enough that the packet that traces it is split several times.
+const ARRAY_LENGTH: usize = 4 * GCWorker::<MockVM>::LONG_WORK_PACKET_COST + 1;
+/// The array has a header word followed by its elements.
+const ARRAY_SIZE: usize = DEFAULT_OBJECT_REF_OFFSET + (ARRAY_LENGTH + 1) * BYTES_IN_ADDRESS;
+
+/// The raw address of the only array.
+static ARRAY: AtomicUsize = AtomicUsize::new(0);
+
+fn is_array(object: ObjectReference) -> bool {
+    object.to_raw_address().as_usize() == ARRAY.load(Ordering::SeqCst)
+}
+
+fn setup_with_array() -> MockVM {
+    MockVM {
+        get_object_size: MockMethod::new_fixed(Box::new(|object| {
+            if is_array(object) {
+                ARRAY_SIZE
+            } else {
+                OBJECT_SIZE
+            }
+        })),
+        // Other objects are scanned with `scan_object`, which only visits the first fields.
+        reference_array_slice: MockMethod::new_fixed(Box::new(|(_, object)| {
+            is_array(object).then(|| field(object, 0)..field(object, ARRAY_LENGTH))
+        })),
+        ..setup()
+    }
+}
+
+#[test]
+pub fn concurrent_marking_traces_array_slices() {
+    with_mockvm(
+        setup_with_array,
+        || {
+            let (mmtk, mutator) = create_mmtk(PauseBudget::Unbounded, |_| {});
+
+            // root -> a large array in the LOS, whose elements are small objects.
+            let semantics = AllocationSemantics::Los;
+            let addr = memory_manager::alloc(mutator, ARRAY_SIZE, BYTES_IN_ADDRESS, 0, semantics);
+            assert!(!addr.is_zero());
+            let array = MockVM::object_start_to_ref(addr);
+            ARRAY.store(array.to_raw_address().as_usize(), Ordering::SeqCst);
+            memory_manager::post_alloc(mutator, array, ARRAY_SIZE, semantics);
+            let elements: Vec<ObjectReference> = (0..ARRAY_LENGTH)
+                .map(|i| {
+                    let element = alloc_object(mutator);
+                    Slot::store(&field(array, i), element);
+                    element
+                })
+                .collect();
+            Slot::store(&root_slot(0), array);
+
+            // Concurrent marking traces the elements in split packets, and scans each element
+            // while mutators are running.
+            mmtk.gc_requester.request();
+            wait_for_pauses(2);
+            let plan = mmtk.get_plan().concurrent().unwrap();
+            assert!(!plan.concurrent_work_in_progress());
+            assert_eq!(objects_scanned_concurrently(), ARRAY_LENGTH);
+
+            assert!(array.is_live());
+            assert!(elements.iter().all(|object| object.is_live()));
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,45 @@
+// GITHUB-CI: MMTK_PLAN=ConcurrentImmix
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use std::time::Instant;
+
+use super::*;
+use crate::util::test_util::fixtures::alloc_bytes;
+
+const MB: usize = 1024 * 1024;
+
+#[test]
+pub fn idle_gc_requests_concurrent_gc() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PauseBudget::Unbounded, |builder| {
+                builder.options.idle_gc.set(true);
+            });
+            let plan = mmtk.get_plan().concurrent().unwrap();
+            let far_deadline = || Instant::now() + Duration::from_secs(60);
+
+            // MMTk cannot tell how long a pause takes before the first GC.
+            assert!(!memory_manager::notify_idle(mmtk, far_deadline()));
+
+            // The first GC: an initial-mark pause, concurrent marking, and a final-mark pause.
+            mmtk.gc_requester.request();
+            wait_for_pauses(2);
+            assert!(!plan.concurrent_work_in_progress());
+
+            // Allocate quickly.  At this rate, the heap would be full soon.  A quarter of the heap
+            // does not start concurrent marking by itself.
+            alloc_bytes(mutator, 16 * MB);
+
+            // The first pause of a GC does not fit before a deadline that has passed.
+            assert!(!memory_manager::notify_idle(mmtk, Instant::now()));
+
+            // Otherwise, MMTk requests a GC, which starts with an initial-mark pause and finishes
+            // on its own.
+            assert!(memory_manager::notify_idle(mmtk, far_deadline()));
+            wait_for_pauses(4);
+            assert!(!plan.concurrent_work_in_progress());
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,40 @@
+// GITHUB-CI: MMTK_PLAN=ConcurrentImmix
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::*;
+
+#[test]
+pub fn incremental_pauses_do_bounded_work() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PauseBudget::WorkPackets(1), |_| {});
+
+            // root0 -> a -> b, root1 -> c -> e, and d is garbage.
+            let a = alloc_object(mutator);
+            let b = alloc_object(mutator);
+            let c = alloc_object(mutator);
+            let d = alloc_object(mutator);
+            let e = alloc_object(mutator);
+            Slot::store(&field(a, 0), b);
+            Slot::store(&field(c, 0), e);
+            Slot::store(&root_slot(0), a);
+            Slot::store(&root_slot(1), c);
+
+            // The initial-mark pause creates one concurrent work packet for each root.  Each
+            // incremental pause executes one of them, and the final-mark pause finishes marking.
+            mmtk.gc_requester.request();
+            wait_for_pauses(1 + NUM_ROOTS + 1);
+            let plan = mmtk.get_plan().concurrent().unwrap();
+            assert!(!plan.concurrent_work_in_progress());
+            assert_eq!(objects_scanned_concurrently(), 0);
+
+            assert!(a.is_live());
+            assert!(b.is_live());
+            assert!(c.is_live());
+            assert!(!d.is_live());
+            assert!(e.is_live());
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,35 @@
+// GITHUB-CI: MMTK_PLAN=ConcurrentImmix
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::*;
+
+#[test]
+pub fn incremental_pauses_bound_long_chains() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PauseBudget::WorkPackets(1), |_| {});
+
+            // A linked list that is too long to be scanned in one packet.
+            const LENGTH: usize = 1500;
+            let objects: Vec<ObjectReference> =
+                (0..LENGTH).map(|_| alloc_object(mutator)).collect();
+            for pair in objects.windows(2) {
+                Slot::store(&field(pair[0], 0), pair[1]);
+            }
+            Slot::store(&root_slot(0), objects[0]);
+
+            // A packet that reaches its scan limit leaves the rest of the list to another packet,
+            // which is executed in the next incremental pause.
+            mmtk.gc_requester.request();
+            wait_for_pauses(1 + 2 + 1);
+            let plan = mmtk.get_plan().concurrent().unwrap();
+            assert!(!plan.concurrent_work_in_progress());
+            assert_eq!(pauses(), 1 + 2 + 1);
+            assert_eq!(objects_scanned_concurrently(), 0);
+
+            assert!(objects.iter().all(|object| object.is_live()));
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,50 @@
+// GITHUB-CI: MMTK_PLAN=ConcurrentImmix
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::*;
+
+#[test]
+pub fn concurrent_marking_keeps_objects_deleted_from_the_snapshot() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PauseBudget::Unbounded, |_| {});
+
+            // root -> a -> b, and d is garbage.
+            let a = alloc_object(mutator);
+            let b = alloc_object(mutator);
+            let d = alloc_object(mutator);
+            Slot::store(&field(a, 0), b);
+            Slot::store(&root_slot(0), a);
+
+            // Stall concurrent marking until we have mutated the object graph.
+            mmtk.scheduler.work_buckets[WorkBucketStage::Concurrent].add(WaitForGate);
+
+            // Initial mark.
+            mmtk.gc_requester.request();
+            wait_for_pauses(1);
+            let plan = mmtk.get_plan().concurrent().unwrap();
+            assert!(plan.concurrent_work_in_progress());
+
+            // Move the only reference to b from a to a new object c.  c is not in the snapshot,
+            // and is already considered live, so b is only reachable via the SATB barrier.
+            let c = alloc_object(mutator);
+            memory_manager::object_reference_write_pre(mutator, c, field(c, 0), Some(b));
+            Slot::store(&field(c, 0), b);
+            Slot::store(&root_slot(1), c);
+            memory_manager::object_reference_write_pre(mutator, a, field(a, 0), None);
+            unsafe { field(a, 0).store(Address::ZERO) };
+
+            // Let concurrent marking finish.  The GC worker will request the final-mark pause.
+            open_gate();
+            wait_for_pauses(2);
+            assert!(!plan.concurrent_work_in_progress());
+
+            assert!(a.is_live());
+            assert!(b.is_live());
+            assert!(c.is_live());
+            assert!(!d.is_live());
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,95 @@
+// Mock tests for ConcurrentImmix, and the MockVM setup they share.
+//
+// They live with the plan instead of `crate::vm::tests::mock_tests` because the mocked root
+// scanning needs to name the work contexts of ConcurrentImmix, which are private to the plan.
+// Each test initializes MMTk, so each test is in its own module.  See
+// `crate::vm::tests::mock_tests`.
+
+mod mock_test_concurrent_immix_array_slice;
+mod mock_test_concurrent_immix_idle_gc;
+mod mock_test_concurrent_immix_incremental;
+mod mock_test_concurrent_immix_long_chain;
+mod mock_test_concurrent_immix_satb;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use super::gc_work::{ConcurrentImmixGCWorkContext, ConcurrentImmixSTWGCWorkContext};
+use crate::memory_manager;
+use crate::plan::Mutator;
+use crate::policy::immix::TRACE_KIND_FAST;
+use crate::scheduler::{GCWork, GCWorker, WorkBucketStage};
+use crate::util::constants::BYTES_IN_ADDRESS;
+use crate::util::options::{PauseBudget, PlanSelector};
+use crate::util::test_util::mock_method::*;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::{Address, ObjectReference};
+use crate::vm::slot::Slot;
+use crate::{AllocationSemantics, MMTKBuilder, MMTK};
+
+type InitialMarkContext = ConcurrentImmixGCWorkContext<MockVM>;
+type FinalMarkContext = ConcurrentImmixSTWGCWorkContext<MockVM, TRACE_KIND_FAST>;
+
+/// How many objects have been scanned while mutators are running.
+static OBJECTS_SCANNED_CONCURRENTLY: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether concurrent marking may proceed.
+static GATE_OPEN: Mutex<bool> = Mutex::new(false);
+static GATE_OPENED: Condvar = Condvar::new();
+
+fn objects_scanned_concurrently() -> usize {
+    OBJECTS_SCANNED_CONCURRENTLY.load(Ordering::SeqCst)
+}
+
+/// Let [`WaitForGate`] finish.
+fn open_gate() {
+    *GATE_OPEN.lock().unwrap() = true;
+    GATE_OPENED.notify_all();
+}
+
+/// Block concurrent marking until the test opens the gate.
+struct WaitForGate;
+
+impl GCWork<MockVM> for WaitForGate {
+    fn do_work(&mut self, _worker: &mut GCWorker<MockVM>, _mmtk: &'static MMTK<MockVM>) {
+        let (_open, timeout_result) = GATE_OPENED
+            .wait_timeout_while(GATE_OPEN.lock().unwrap(), TIMEOUT, |open| !*open)
+            .unwrap();
+        assert!(!timeout_result.timed_out());
+    }
+}
+
+/// The MockVM of [`mock_roots`] for ConcurrentImmix, which also counts the objects scanned while
+/// mutators are running.
+fn setup() -> MockVM {
+    OBJECTS_SCANNED_CONCURRENTLY.store(0, Ordering::SeqCst);
+    *GATE_OPEN.lock().unwrap() = false;
+
+    MockVM {
+        scan_object: MockMethod::new_fixed(Box::new(|(_, object, slot_visitor)| {
+            if !mutators_stopped() {
+                OBJECTS_SCANNED_CONCURRENTLY.fetch_add(1, Ordering::SeqCst);
+            }
+            for i in 0..NUM_FIELDS {
+                slot_visitor.visit_slot(field(object, i));
+            }
+        })),
+        ..mock_roots::setup::<InitialMarkContext, FinalMarkContext>()
+    }
+}
+
+/// Create a ConcurrentImmix instance with one GC worker, and bind the only mutator.  `configure`
+/// may change the other options.
+fn create_mmtk(
+    pause_budget: PauseBudget,
+    configure: impl FnOnce(&mut MMTKBuilder),
+) -> (&'static MMTK<MockVM>, &'static mut Mutator<MockVM>) {
+    mock_roots::create_mmtk(PlanSelector::ConcurrentImmix, |builder| {
+        // One worker, so that blocking it blocks concurrent marking.
+        builder.options.threads.set(1);
+        builder.options.incremental_pause_budget.set(pause_budget);
+        configure(builder);
+    })
+}
@@ -0,0 +1,52 @@
+use super::ConcurrentImmix;
+use crate::plan::barriers::SATBBarrier;
+use crate::plan::concurrent::barrier::SATBBarrierSemantics;
+use crate::plan::immix::mutator::immix_mutator_release;
+use crate::plan::immix::mutator::RESERVED_ALLOCATORS;
+use crate::plan::mutator_context::create_allocator_mapping;
+use crate::plan::mutator_context::create_space_mapping;
+use crate::plan::mutator_context::unreachable_prepare_func;
+use crate::plan::mutator_context::Mutator;
+use crate::plan::mutator_context::MutatorConfig;
+use crate::plan::AllocationSemantics;
+use crate::util::alloc::allocators::{AllocatorSelector, Allocators};
+use crate::util::opaque_pointer::VMMutatorThread;
+use crate::vm::VMBinding;
+use crate::MMTK;
+use enum_map::EnumMap;
+
+lazy_static! {
+    pub static ref ALLOCATOR_MAPPING: EnumMap<AllocationSemantics, AllocatorSelector> = {
+        let mut map = create_allocator_mapping(RESERVED_ALLOCATORS, true);
+        map[AllocationSemantics::Default] = AllocatorSelector::Immix(0);
+        map
+    };
+}
+
+pub fn create_concurrent_immix_mutator<VM: VMBinding>(
+    mutator_tls: VMMutatorThread,
+    mmtk: &'static MMTK<VM>,
+) -> Mutator<VM> {
+    let immix = mmtk
+        .get_plan()
+        .downcast_ref::<ConcurrentImmix<VM>>()
+        .unwrap();
+    let config = MutatorConfig {
+        allocator_mapping: &ALLOCATOR_MAPPING,
+        space_mapping: Box::new({
+            let mut vec = create_space_mapping(RESERVED_ALLOCATORS, true, immix);
+            vec.push((AllocatorSelector::Immix(0), &immix.immix_space));
+            vec
+        }),
+        prepare_func: &unreachable_prepare_func,
+        release_func: &immix_mutator_release,
+    };
+
+    Mutator {
+        allocators: Allocators::<VM>::new(mutator_tls, mmtk, &config.space_mapping),
+        barrier: Box::new(SATBBarrier::new(SATBBarrierSemantics::new(mmtk, immix))),
+        mutator_tls,
+        config,
+        plan: immix,
+    }
+}
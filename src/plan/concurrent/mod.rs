@@ -0,0 +1,24 @@
+//! Concurrent plans
+
+// Concurrent plans:
+
+/// Concurrent immix (ConcurrentImmix)
+pub mod immix;
+
+// Common concurrent code
+
+pub mod barrier;
+pub(super) mod concurrent_marking_work;
+pub(super) mod global;
+
+/// The kind of a pause of a concurrent plan.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Pause {
+    /// A stop-the-world collection that does all the work in one pause.
+    Full,
+    /// The pause that starts concurrent marking.  The roots are scanned in this pause, and the
+    /// objects reachable from the roots are traced after mutators resume.
+    InitialMark,
+    /// The pause that finishes concurrent marking, processes weak references and reclaims memory.
+    FinalMark,
+}
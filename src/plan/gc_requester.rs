@@ -34,6 +34,14 @@ impl<VM: VMBinding> GCRequester<VM> {
         }
     }
 
+    /// Set the "GC requested" flag without sending a request to GC workers.  Called by the last
+    /// parked GC worker which adds the request to the worker goals by itself, e.g. when a
+    /// concurrent plan needs a pause to finish its concurrent work.  Mutators will not send
+    /// redundant requests until the flag is cleared.
+    pub(crate) fn set_request_flag(&self) {
+        self.request_flag.store(true, Ordering::Relaxed);
+    }
+
     /// Clear the "GC requested" flag so that mutators can trigger the next GC.
     /// Called by a GC worker when all mutators have come to a stop.
     pub fn clear_request(&self) {
@@ -88,6 +88,7 @@ impl<VM: VMBinding> Plan for GenCopy<VM> {
 
     fn prepare(&mut self, tls: VMWorkerThread) {
         let full_heap = !self.gen.is_current_gc_nursery();
+        CommonGenPlan::on_nursery_gc_start(self, self.gen.nursery.reserved_pages());
         self.gen.prepare(tls);
         if full_heap {
             self.hi
@@ -115,6 +116,7 @@ impl<VM: VMBinding> Plan for GenCopy<VM> {
     }
 
     fn end_of_gc(&mut self, _tls: VMWorkerThread) {
+        CommonGenPlan::on_nursery_gc_end(self);
         self.gen
             .set_next_gc_full_heap(CommonGenPlan::should_next_gc_be_full_heap(self));
     }
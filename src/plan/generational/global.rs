@@ -273,6 +273,27 @@ impl<VM: VMBinding> CommonGenPlan<VM> {
         next_gc_full_heap
     }
 
+    /// Record the start of a nursery GC for an Adaptive nursery. `nursery_pages` is the number of
+    /// pages allocated into the nursery since the last GC. This should be called in GC prepare,
+    /// before any space is prepared.
+    pub fn on_nursery_gc_start(plan: &dyn Plan<VM = VM>, nursery_pages: usize) {
+        if is_nursery_gc(plan) {
+            plan.base()
+                .gc_trigger
+                .on_nursery_gc_start(nursery_pages, plan.get_used_pages());
+        }
+    }
+
+    /// Resize an Adaptive nursery based on the survival ratio and the pause time of a nursery GC.
+    /// This should be called at the end of GC, after all spaces have been released.
+    pub fn on_nursery_gc_end(plan: &dyn Plan<VM = VM>) {
+        if is_nursery_gc(plan) {
+            plan.base()
+                .gc_trigger
+                .on_nursery_gc_end(plan.get_used_pages());
+        }
+    }
+
     /// Set next_gc_full_heap to the given value.
     pub fn set_next_gc_full_heap(&self, next_gc_full_heap: bool) {
         self.next_gc_full_heap
@@ -0,0 +1,22 @@
+use super::global::GenMarkSweep;
+use crate::plan::generational::gc_work::GenNurseryProcessEdges;
+use crate::policy::gc_work::DEFAULT_TRACE;
+use crate::scheduler::gc_work::PlanProcessEdges;
+use crate::scheduler::gc_work::UnsupportedProcessEdges;
+use crate::vm::VMBinding;
+
+pub struct GenMarkSweepNurseryGCWorkContext<VM: VMBinding>(std::marker::PhantomData<VM>);
+impl<VM: VMBinding> crate::scheduler::GCWorkContext for GenMarkSweepNurseryGCWorkContext<VM> {
+    type VM = VM;
+    type PlanType = GenMarkSweep<VM>;
+    type DefaultProcessEdges = GenNurseryProcessEdges<VM, Self::PlanType, DEFAULT_TRACE>;
+    type PinningProcessEdges = UnsupportedProcessEdges<VM>;
+}
+
+pub(super) struct GenMarkSweepMatureGCWorkContext<VM: VMBinding>(std::marker::PhantomData<VM>);
+impl<VM: VMBinding> crate::scheduler::GCWorkContext for GenMarkSweepMatureGCWorkContext<VM> {
+    type VM = VM;
+    type PlanType = GenMarkSweep<VM>;
+    type DefaultProcessEdges = PlanProcessEdges<VM, GenMarkSweep<VM>, DEFAULT_TRACE>;
+    type PinningProcessEdges = UnsupportedProcessEdges<VM>;
+}
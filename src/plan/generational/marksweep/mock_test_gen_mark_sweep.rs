@@ -0,0 +1,82 @@
+// GITHUB-CI: MMTK_PLAN=GenMarkSweep
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::gc_work::{GenMarkSweepMatureGCWorkContext, GenMarkSweepNurseryGCWorkContext};
+use super::GenMarkSweep;
+use crate::memory_manager;
+use crate::policy::space::Space;
+use crate::util::options::PlanSelector;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::Address;
+use crate::vm::slot::Slot;
+
+fn setup() -> MockVM {
+    mock_roots::setup::<
+        GenMarkSweepNurseryGCWorkContext<MockVM>,
+        GenMarkSweepMatureGCWorkContext<MockVM>,
+    >()
+}
+
+#[test]
+pub fn promote_to_mark_sweep_space() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::GenMarkSweep, |_| {});
+            let plan = mmtk
+                .get_plan()
+                .downcast_ref::<GenMarkSweep<MockVM>>()
+                .unwrap();
+
+            // root0 -> a -> b, and root1 -> e.
+            let a = alloc_object(mutator);
+            let b = alloc_object(mutator);
+            let e = alloc_object(mutator);
+            Slot::store(&field(a, 0), b);
+            Slot::store(&root_slot(0), a);
+            Slot::store(&root_slot(1), e);
+            assert!(plan.gen.nursery.in_space(a));
+
+            // A nursery GC promotes reachable objects into the mark sweep space.
+            collect(mmtk);
+            let a = root(0);
+            let b = Slot::load(&field(a, 0)).unwrap();
+            let e = root(1);
+            for object in [a, b, e] {
+                assert!(plan.ms.in_space(object));
+                assert!(object.is_live());
+            }
+
+            // c is only reachable from a mature object, and is remembered by the barrier.
+            let c = alloc_object(mutator);
+            memory_manager::object_reference_write_pre(mutator, a, field(a, 1), Some(c));
+            Slot::store(&field(a, 1), c);
+            memory_manager::object_reference_write_post(mutator, a, field(a, 1), Some(c));
+            collect(mmtk);
+            assert_eq!(root(0), a);
+            let c = Slot::load(&field(a, 1)).unwrap();
+            assert!(plan.ms.in_space(c));
+
+            // A full heap GC does not move mature objects, and reclaims unreachable ones.
+            Slot::store(&root_slot(1), a);
+            unsafe { root_slot(0).store(Address::ZERO) };
+            plan.gen.force_full_heap_collection();
+            collect(mmtk);
+            assert!(plan.gen.last_collection_full_heap());
+            assert_eq!(root(1), a);
+            for object in [a, b, c] {
+                assert!(object.is_live());
+            }
+            assert!(!e.is_live());
+
+            // Promotion still works after the mark sweep space has been released.
+            let f = alloc_object(mutator);
+            Slot::store(&root_slot(0), f);
+            collect(mmtk);
+            assert!(plan.ms.in_space(root(0)));
+            assert!(root(0).is_live());
+        },
+        no_cleanup,
+    )
+}
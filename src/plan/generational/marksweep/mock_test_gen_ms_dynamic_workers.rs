@@ -0,0 +1,95 @@
+// GITHUB-CI: MMTK_PLAN=GenMarkSweep
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::gc_work::{GenMarkSweepMatureGCWorkContext, GenMarkSweepNurseryGCWorkContext};
+use super::GenMarkSweep;
+use crate::memory_manager;
+use crate::policy::space::Space;
+use crate::util::options::{NurserySize, PlanSelector};
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::vm::slot::Slot;
+
+const MB: usize = 1024 * 1024;
+
+fn setup() -> MockVM {
+    mock_roots::setup::<
+        GenMarkSweepNurseryGCWorkContext<MockVM>,
+        GenMarkSweepMatureGCWorkContext<MockVM>,
+    >()
+}
+
+/// Return true if the abandoned block lists of the mark sweep space hold any block.
+fn has_abandoned_blocks(plan: &GenMarkSweep<MockVM>) -> bool {
+    let lists = plan.ms.get_abandoned_block_lists().lock().unwrap();
+    lists
+        .available
+        .iter()
+        .chain(lists.unswept.iter())
+        .chain(lists.consumed.iter())
+        .any(|list| !list.is_empty())
+}
+
+#[test]
+pub fn release_with_surplus_workers() {
+    with_mockvm(
+        setup,
+        || {
+            // Each GC expects the work of a nursery GC, which is proportional to the heap size. A
+            // 64MB heap needs all four workers (or as many as the CPUs), and a 16MB heap needs one.
+            let (mmtk, mutator) = create_mmtk(PlanSelector::GenMarkSweep, |builder| {
+                builder.options.threads.set(4);
+                builder.options.work_per_gc_thread.set(16 * MB);
+                builder
+                    .options
+                    .nursery
+                    .set(NurserySize::ProportionalBounded {
+                        min: 0.25,
+                        max: 1.0,
+                    });
+            });
+            let plan = mmtk
+                .get_plan()
+                .downcast_ref::<GenMarkSweep<MockVM>>()
+                .unwrap();
+            let cpus = std::thread::available_parallelism().map_or(4, |n| n.get());
+
+            // root0 -> a -> b.
+            let a = alloc_object(mutator);
+            let b = alloc_object(mutator);
+            Slot::store(&field(a, 0), b);
+            Slot::store(&root_slot(0), a);
+
+            // A nursery GC promotes a and b.  The copy allocators of the workers give up their
+            // blocks at the end of the GC, even though the space is not released.
+            collect(mmtk);
+            assert!(!plan.gen.last_collection_full_heap());
+            assert_eq!(mmtk.scheduler.num_active_workers(), 4.min(cpus));
+            let a = root(0);
+            let b = Slot::load(&field(a, 0)).unwrap();
+            for object in [a, b] {
+                assert!(plan.ms.in_space(object));
+            }
+            assert!(has_abandoned_blocks(plan));
+
+            // Fewer workers take part in a full heap GC in a smaller heap.  The surplus workers
+            // hold no blocks, and the mark sweep space finishes releasing after the copy
+            // allocators of the active worker release their blocks.  The GC reclaims b.
+            memory_manager::set_heap_size_bounds(mmtk, 16 * MB, 16 * MB).unwrap();
+            unsafe { field(a, 0).store(crate::util::Address::ZERO) };
+            plan.gen.force_full_heap_collection();
+            collect(mmtk);
+            assert!(plan.gen.last_collection_full_heap());
+            assert_eq!(mmtk.scheduler.num_active_workers(), 1);
+            assert!(root(0).is_live());
+            assert!(!b.is_live());
+
+            // Promotion still works after the mark sweep space has been released.
+            let c = alloc_object(mutator);
+            Slot::store(&root_slot(1), c);
+            collect(mmtk);
+            assert!(plan.ms.in_space(root(1)));
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,14 @@
+//! Plan: generational mark sweep
+
+pub(in crate::plan) mod gc_work;
+pub(in crate::plan) mod global;
+pub(in crate::plan) mod mutator;
+
+pub use self::global::GenMarkSweep;
+
+pub use self::global::GENMS_CONSTRAINTS;
+
+#[cfg(all(test, feature = "mock_test_side_mark_bit"))]
+mod mock_test_gen_mark_sweep;
+#[cfg(all(test, feature = "mock_test_side_mark_bit"))]
+mod mock_test_gen_ms_dynamic_workers;
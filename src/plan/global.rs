@@ -47,6 +47,9 @@ pub fn create_mutator<VM: VMBinding>(
         PlanSelector::GenImmix => {
             crate::plan::generational::immix::mutator::create_genimmix_mutator(tls, mmtk)
         }
+        PlanSelector::GenMarkSweep => {
+            crate::plan::generational::marksweep::mutator::create_genms_mutator(tls, mmtk)
+        }
         PlanSelector::MarkSweep => crate::plan::marksweep::mutator::create_ms_mutator(tls, mmtk),
         PlanSelector::Immix => crate::plan::immix::mutator::create_immix_mutator(tls, mmtk),
         PlanSelector::PageProtect => {
@@ -58,6 +61,19 @@ pub fn create_mutator<VM: VMBinding>(
         PlanSelector::StickyImmix => {
             crate::plan::sticky::immix::mutator::create_stickyimmix_mutator(tls, mmtk)
         }
+        PlanSelector::StickyMarkSweep => {
+            crate::plan::sticky::marksweep::mutator::create_stickyms_mutator(tls, mmtk)
+        }
+        PlanSelector::ConcurrentImmix => {
+            crate::plan::concurrent::immix::mutator::create_concurrent_immix_mutator(tls, mmtk)
+        }
+        PlanSelector::Regional => {
+            crate::plan::regional::mutator::create_regional_mutator(tls, mmtk)
+        }
+        PlanSelector::RCImmix => crate::plan::rcimmix::mutator::create_rcimmix_mutator(tls, mmtk),
+        PlanSelector::SemiImmix => {
+            crate::plan::semiimmix::mutator::create_semi_immix_mutator(tls, mmtk)
+        }
     })
 }
 
@@ -76,6 +92,9 @@ pub fn create_plan<VM: VMBinding>(
             as Box<dyn Plan<VM = VM>>,
         PlanSelector::GenImmix => Box::new(crate::plan::generational::immix::GenImmix::new(args))
             as Box<dyn Plan<VM = VM>>,
+        PlanSelector::GenMarkSweep => Box::new(
+            crate::plan::generational::marksweep::GenMarkSweep::new(args),
+        ) as Box<dyn Plan<VM = VM>>,
         PlanSelector::MarkSweep => {
             Box::new(crate::plan::marksweep::MarkSweep::new(args)) as Box<dyn Plan<VM = VM>>
         }
@@ -91,6 +110,23 @@ pub fn create_plan<VM: VMBinding>(
         PlanSelector::StickyImmix => {
             Box::new(crate::plan::sticky::immix::StickyImmix::new(args)) as Box<dyn Plan<VM = VM>>
         }
+        PlanSelector::StickyMarkSweep => {
+            Box::new(crate::plan::sticky::marksweep::StickyMarkSweep::new(args))
+                as Box<dyn Plan<VM = VM>>
+        }
+        PlanSelector::ConcurrentImmix => {
+            Box::new(crate::plan::concurrent::immix::ConcurrentImmix::new(args))
+                as Box<dyn Plan<VM = VM>>
+        }
+        PlanSelector::Regional => {
+            Box::new(crate::plan::regional::Regional::new(args)) as Box<dyn Plan<VM = VM>>
+        }
+        PlanSelector::RCImmix => {
+            Box::new(crate::plan::rcimmix::RCImmix::new(args)) as Box<dyn Plan<VM = VM>>
+        }
+        PlanSelector::SemiImmix => {
+            Box::new(crate::plan::semiimmix::SemiImmix::new(args)) as Box<dyn Plan<VM = VM>>
+        }
     };
 
     // We have created Plan in the heap, and we won't explicitly move it.
@@ -173,6 +209,14 @@ pub trait Plan: 'static + HasSpaces + Sync + Downcast {
         None
     }
 
+    /// Return a reference to `ConcurrentPlan` to allow
+    /// access methods specific to concurrent plans if the plan is a concurrent plan.
+    fn concurrent(
+        &self,
+    ) -> Option<&dyn crate::plan::concurrent::global::ConcurrentPlan<VM = Self::VM>> {
+        None
+    }
+
     /// Get the current run time options.
     fn options(&self) -> &Options {
         &self.base().options
@@ -0,0 +1,133 @@
+// GITHUB-CI: MMTK_PLAN=Immix
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::gc_work::ImmixGCWorkContext;
+use super::Immix;
+use crate::memory_manager;
+use crate::policy::immix::{TRACE_KIND_DEFRAG, TRACE_KIND_FAST};
+use crate::policy::space::Space;
+use crate::util::constants::BYTES_IN_ADDRESS;
+use crate::util::options::{DecommitPolicy, PlanSelector};
+use crate::util::test_util::fixtures::{alloc_bytes, try_alloc_bytes};
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::{Address, ObjectReference};
+use crate::vm::slot::Slot;
+use crate::AllocationSemantics;
+
+const MB: usize = 1024 * 1024;
+const LARGE_OBJECT_SIZE: usize = 64 * 1024;
+/// A word near the end of the live large object, which is on a different page from its fields.
+const MARKER_OFFSET: usize = LARGE_OBJECT_SIZE - 2 * BYTES_IN_ADDRESS;
+const MARKER: usize = 0x1234_5678;
+
+fn setup() -> MockVM {
+    mock_roots::setup::<
+        ImmixGCWorkContext<MockVM, TRACE_KIND_FAST>,
+        ImmixGCWorkContext<MockVM, TRACE_KIND_DEFRAG>,
+    >()
+}
+
+fn alloc_large_object() -> ObjectReference {
+    let semantics = AllocationSemantics::Los;
+    let mutator = the_mutator();
+    let addr = memory_manager::alloc(mutator, LARGE_OBJECT_SIZE, BYTES_IN_ADDRESS, 0, semantics);
+    assert!(!addr.is_zero());
+    let object = MockVM::object_start_to_ref(addr);
+    memory_manager::post_alloc(mutator, object, LARGE_OBJECT_SIZE, semantics);
+    object
+}
+
+fn marker(object: ObjectReference) -> Address {
+    object.to_raw_address() + MARKER_OFFSET
+}
+
+/// Check that the live objects are intact.  `madvise(MADV_DONTNEED)` zeroes the pages that it is
+/// applied to, so a live page that was decommitted would read as zero.
+fn check_live_objects() {
+    // root0 -> a <-> b
+    let a = root(0);
+    let b = Slot::load(&field(a, 0)).unwrap();
+    assert_eq!(Slot::load(&field(b, 0)), Some(a));
+    // root1 -> the large object -> a
+    let large = root(1);
+    assert_eq!(Slot::load(&field(large, 0)), Some(a));
+    assert_eq!(unsafe { marker(large).load::<usize>() }, MARKER);
+}
+
+#[test]
+pub fn decommit_free_pages_after_shrinking_heap() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::Immix, |builder| {
+                builder
+                    .options
+                    .decommit_policy
+                    .set(DecommitPolicy::DontNeed);
+            });
+            let plan = mmtk.get_plan().downcast_ref::<Immix<MockVM>>().unwrap();
+            let decommitted_pages = || {
+                (
+                    plan.immix_space
+                        .get_page_resource()
+                        .common()
+                        .get_decommitted_pages(),
+                    plan.common
+                        .get_los()
+                        .get_page_resource()
+                        .common()
+                        .get_decommitted_pages(),
+                )
+            };
+
+            // Live objects in the Immix space and the LOS, and garbage in both spaces.
+            let a = alloc_object(mutator);
+            let b = alloc_object(mutator);
+            Slot::store(&field(a, 0), b);
+            Slot::store(&field(b, 0), a);
+            Slot::store(&root_slot(0), a);
+            let large = alloc_large_object();
+            Slot::store(&field(large, 0), a);
+            unsafe { marker(large).store(MARKER) };
+            Slot::store(&root_slot(1), large);
+            alloc_bytes(mutator, 8 * MB);
+            let los_garbage = 4 * MB;
+            let allocated = try_alloc_bytes(
+                mutator,
+                los_garbage,
+                LARGE_OBJECT_SIZE,
+                AllocationSemantics::Los,
+            );
+            assert_eq!(allocated, los_garbage);
+
+            // A GC that does not shrink the heap keeps the free memory committed.
+            collect(mmtk);
+            assert_eq!(decommitted_pages(), (0, 0));
+            check_live_objects();
+
+            // A GC after shrinking the heap decommits the free blocks and the free large object
+            // pages, but not the pages of live objects.
+            memory_manager::set_heap_size_bounds(mmtk, 16 * MB, 16 * MB).unwrap();
+            collect(mmtk);
+            let (immix_pages, los_pages) = decommitted_pages();
+            assert!(immix_pages > 0);
+            assert!(los_pages > 0);
+            check_live_objects();
+
+            // The heap size does not change, so another GC does not decommit more.
+            collect(mmtk);
+            assert_eq!(decommitted_pages(), (immix_pages, los_pages));
+
+            // The decommitted memory can be allocated into again, without triggering a GC in the
+            // smaller heap.
+            alloc_bytes(mutator, 4 * MB);
+            let allocated =
+                try_alloc_bytes(mutator, 2 * MB, LARGE_OBJECT_SIZE, AllocationSemantics::Los);
+            assert_eq!(allocated, 2 * MB);
+            collect(mmtk);
+            check_live_objects();
+        },
+        no_cleanup,
+    )
+}
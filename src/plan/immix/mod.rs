@@ -4,3 +4,6 @@ pub(super) mod mutator;
 
 pub use self::global::Immix;
 pub use self::global::IMMIX_CONSTRAINTS;
+
+#[cfg(all(test, feature = "mock_test_side_mark_bit"))]
+mod mock_test_immix_decommit;
@@ -12,14 +12,15 @@ use crate::vm::VMBinding;
 use crate::MMTK;
 use std::marker::PhantomData;
 
-/// iterate through the heap and calculate the new location of live objects
+/// schedule the work packets that calculate the new location of live objects in parallel, and
+/// the work packets that compact them
 pub struct CalculateForwardingAddress<VM: VMBinding> {
     mc_space: &'static MarkCompactSpace<VM>,
 }
 
 impl<VM: VMBinding> GCWork<VM> for CalculateForwardingAddress<VM> {
-    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
-        self.mc_space.calculate_forwarding_pointer();
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        self.mc_space.schedule_compaction(worker.scheduler());
     }
 }
 
@@ -75,23 +76,6 @@ impl<VM: VMBinding> UpdateReferences<VM> {
     }
 }
 
-/// compact live objects based on forwarding pointers calculated before
-pub struct Compact<VM: VMBinding> {
-    mc_space: &'static MarkCompactSpace<VM>,
-}
-
-impl<VM: VMBinding> GCWork<VM> for Compact<VM> {
-    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
-        self.mc_space.compact();
-    }
-}
-
-impl<VM: VMBinding> Compact<VM> {
-    pub fn new(mc_space: &'static MarkCompactSpace<VM>) -> Self {
-        Self { mc_space }
-    }
-}
-
 /// Marking trace
 pub type MarkingProcessEdges<VM> = PlanProcessEdges<VM, MarkCompact<VM>, TRACE_KIND_MARK>;
 /// Forwarding trace
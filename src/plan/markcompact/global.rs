@@ -1,7 +1,6 @@
 use super::gc_work::MarkCompactGCWorkContext;
 use super::gc_work::{
-    CalculateForwardingAddress, Compact, ForwardingProcessEdges, MarkingProcessEdges,
-    UpdateReferences,
+    CalculateForwardingAddress, ForwardingProcessEdges, MarkingProcessEdges, UpdateReferences,
 };
 use crate::plan::global::CommonPlan;
 use crate::plan::global::{BasePlan, CreateGeneralPlanArgs, CreateSpecificPlanArgs};
@@ -98,7 +97,7 @@ impl<VM: VMBinding> Plan for MarkCompact<VM> {
             .add(CalculateForwardingAddress::<VM>::new(&self.mc_space));
         // do another trace to update references
         scheduler.work_buckets[WorkBucketStage::SecondRoots].add(UpdateReferences::<VM>::new(self));
-        scheduler.work_buckets[WorkBucketStage::Compact].add(Compact::<VM>::new(&self.mc_space));
+        // The compaction work packets are scheduled when the forwarding pointers are calculated.
 
         // Release global/collectors/mutators
         scheduler.work_buckets[WorkBucketStage::Release]
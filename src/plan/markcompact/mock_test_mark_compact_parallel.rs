@@ -0,0 +1,98 @@
+// GITHUB-CI: MMTK_PLAN=MarkCompact
+
+use super::gc_work::{MarkCompactForwardingGCWorkContext, MarkCompactGCWorkContext};
+use super::MarkCompact;
+use crate::policy::markcompactspace::MarkCompactSpace;
+use crate::policy::space::Space;
+use crate::util::options::PlanSelector;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::ObjectReference;
+use crate::vm::slot::Slot;
+
+fn setup() -> MockVM {
+    mock_roots::setup::<MarkCompactGCWorkContext<MockVM>, MarkCompactForwardingGCWorkContext<MockVM>>(
+    )
+}
+
+/// Walk the list from root0 through the first fields, and check that every object refers to the
+/// head with its second field.
+fn walk_list() -> Vec<ObjectReference> {
+    let head = Slot::load(&root_slot(0)).unwrap();
+    let mut objects = vec![];
+    let mut next = Some(head);
+    while let Some(object) = next {
+        assert_eq!(Slot::load(&field(object, 1)), Some(head));
+        objects.push(object);
+        next = Slot::load(&field(object, 0));
+    }
+    objects
+}
+
+#[test]
+pub fn mark_compact_parallel() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::MarkCompact, |builder| {
+                builder.options.threads.set(4);
+            });
+            let plan = mmtk
+                .get_plan()
+                .downcast_ref::<MarkCompact<MockVM>>()
+                .unwrap();
+            // The memory each object takes, and the most padding each compaction region may add.
+            let cell_size = MarkCompactSpace::<MockVM>::HEADER_RESERVED_IN_BYTES + OBJECT_SIZE;
+            let padding = <MockVM as crate::vm::VMBinding>::MAX_ALIGNMENT;
+
+            // A list of every third object, spanning several compaction regions.
+            let num_objects = 5 * 256 * 1024 / cell_size;
+            let objects: Vec<ObjectReference> =
+                (0..num_objects).map(|_| alloc_object(mutator)).collect();
+            for object in &objects {
+                assert!(plan.mc_space().in_space(*object));
+            }
+            let head = objects[0];
+            let live: Vec<ObjectReference> = objects.iter().copied().step_by(3).collect();
+            for pair in live.windows(2) {
+                Slot::store(&field(pair[0], 0), pair[1]);
+            }
+            for object in &live {
+                Slot::store(&field(*object, 1), head);
+            }
+            Slot::store(&root_slot(0), head);
+            let span = objects[num_objects - 1].to_raw_address() - objects[0].to_raw_address();
+            assert!(span > 4 * 256 * 1024);
+
+            // The live objects keep their order, and slide towards the start of the space.
+            collect(mmtk);
+            let compacted = walk_list();
+            assert_eq!(compacted.len(), live.len());
+            assert_eq!(compacted[0], head);
+            for pair in compacted.windows(2) {
+                assert!(pair[0] < pair[1]);
+            }
+            let num_regions = span / (256 * 1024) + 1;
+            assert!(
+                compacted[compacted.len() - 1].to_raw_address() - head.to_raw_address()
+                    <= compacted.len() * cell_size + num_regions * padding
+            );
+
+            // The memory after the live objects can be allocated again.
+            let tail = compacted[compacted.len() - 1];
+            let more: Vec<ObjectReference> =
+                (0..num_objects).map(|_| alloc_object(mutator)).collect();
+            assert!(more[0] > tail && more[0] < objects[num_objects - 1]);
+            Slot::store(&field(tail, 0), more[0]);
+            for pair in more.windows(2) {
+                Slot::store(&field(pair[0], 0), pair[1]);
+            }
+            for object in &more {
+                Slot::store(&field(*object, 1), head);
+            }
+            collect(mmtk);
+            assert_eq!(walk_list().len(), live.len() + num_objects);
+        },
+        no_cleanup,
+    )
+}
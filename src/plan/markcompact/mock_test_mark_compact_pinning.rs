@@ -0,0 +1,102 @@
+// GITHUB-CI: MMTK_PLAN=MarkCompact
+// GITHUB-CI: FEATURES=object_pinning,mock_test_side_mark_bit
+
+use super::gc_work::{MarkCompactForwardingGCWorkContext, MarkCompactGCWorkContext};
+use super::MarkCompact;
+use crate::memory_manager;
+use crate::policy::markcompactspace::LOG_BYTES_IN_FORWARDING_BLOCK;
+use crate::policy::space::Space;
+use crate::util::options::PlanSelector;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::{Address, ObjectReference};
+use crate::vm::slot::Slot;
+
+/// The forwarding block of an object with side forwarding.  Objects in the same forwarding block
+/// are forwarded together.
+fn forwarding_block(object: ObjectReference) -> Address {
+    object
+        .to_raw_address()
+        .align_down(1 << LOG_BYTES_IN_FORWARDING_BLOCK)
+}
+
+fn setup() -> MockVM {
+    mock_roots::setup::<MarkCompactGCWorkContext<MockVM>, MarkCompactForwardingGCWorkContext<MockVM>>(
+    )
+}
+
+#[test]
+pub fn mark_compact_pinning() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::MarkCompact, |_| {});
+            let plan = mmtk
+                .get_plan()
+                .downcast_ref::<MarkCompact<MockVM>>()
+                .unwrap();
+
+            // d and the fillers are dead, p is pinned, root0 -> p <-> x -> y -> z, and root1 -> x.
+            // z is in the next forwarding block.
+            let d = alloc_object(mutator);
+            let p = alloc_object(mutator);
+            let x = alloc_object(mutator);
+            let y = alloc_object(mutator);
+            let mut z = alloc_object(mutator);
+            while forwarding_block(z) == forwarding_block(p) {
+                z = alloc_object(mutator);
+            }
+            for object in [d, p, x, y, z] {
+                assert!(plan.mc_space().in_space(object));
+            }
+            assert!(d < p && p < x && x < y && y < z);
+            assert_eq!(forwarding_block(d), forwarding_block(y));
+            Slot::store(&field(p, 0), x);
+            Slot::store(&field(x, 0), p);
+            Slot::store(&field(x, 1), y);
+            Slot::store(&field(y, 0), z);
+            Slot::store(&root_slot(0), p);
+            Slot::store(&root_slot(1), x);
+            assert!(memory_manager::pin_object(p));
+            assert!(memory_manager::is_pinned(p));
+
+            collect(mmtk);
+            assert_eq!(root(0), p);
+            assert!(memory_manager::is_pinned(p));
+            let new_x = root(1);
+            assert_eq!(Slot::load(&field(p, 0)), Some(new_x));
+            assert_eq!(Slot::load(&field(new_x, 0)), Some(p));
+            let new_y = Slot::load(&field(new_x, 1)).unwrap();
+            let new_z = Slot::load(&field(new_y, 0)).unwrap();
+            if cfg!(feature = "mark_compact_side_forwarding") {
+                // With side forwarding, the live objects in the forwarding block of the pinned
+                // object stay in place with it. z is in another block, and slides into the memory
+                // of d, before them.
+                assert_eq!(new_x, x);
+                assert_eq!(new_y, y);
+                assert_eq!(new_z, d);
+            } else {
+                // The pinned object stays in place. x slides into the memory of d, before p. y does
+                // not fit before p, and slides into the memory of x after p. z follows y.
+                assert_eq!(new_x, d);
+                assert_eq!(new_y, x);
+                assert_eq!(new_z, y);
+            }
+
+            // Once unpinned, p slides to the start of the space once x and z are dead, and y
+            // follows it into the memory of p.
+            assert!(memory_manager::unpin_object(p));
+            Slot::store(&root_slot(1), p);
+            unsafe { field(p, 0).store(Address::ZERO) };
+            unsafe { field(new_y, 0).store(Address::ZERO) };
+            Slot::store(&field(p, 1), new_y);
+            collect(mmtk);
+            let new_p = root(0);
+            assert_eq!(new_p, d);
+            assert_eq!(root(1), new_p);
+            assert!(!memory_manager::is_pinned(new_p));
+            assert_eq!(Slot::load(&field(new_p, 1)), Some(p));
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,8 @@
+// GITHUB-CI: MMTK_PLAN=MarkCompact
+// GITHUB-CI: FEATURES=object_pinning,mock_test_side_mark_bit,mark_compact_side_forwarding
+
+/// Run the pinning test with the forwarding pointers kept in side metadata.
+#[test]
+pub fn mark_compact_pinning_side_forwarding() {
+    super::mock_test_mark_compact_pinning::mark_compact_pinning()
+}
@@ -0,0 +1,98 @@
+// GITHUB-CI: MMTK_PLAN=MarkCompact
+// GITHUB-CI: FEATURES=mark_compact_side_forwarding
+
+use super::gc_work::{MarkCompactForwardingGCWorkContext, MarkCompactGCWorkContext};
+use super::MarkCompact;
+use crate::policy::markcompactspace::MarkCompactSpace;
+use crate::policy::space::Space;
+use crate::util::options::PlanSelector;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::{Address, ObjectReference};
+use crate::vm::slot::Slot;
+
+fn setup() -> MockVM {
+    mock_roots::setup::<MarkCompactGCWorkContext<MockVM>, MarkCompactForwardingGCWorkContext<MockVM>>(
+    )
+}
+
+/// Link the objects into a list from root0 through the first fields, and let every object refer
+/// to the head with its second field.
+fn make_list(objects: &[ObjectReference]) {
+    let head = objects[0];
+    for pair in objects.windows(2) {
+        Slot::store(&field(pair[0], 0), pair[1]);
+    }
+    unsafe { field(objects[objects.len() - 1], 0).store(Address::ZERO) };
+    for object in objects {
+        Slot::store(&field(*object, 1), head);
+    }
+    Slot::store(&root_slot(0), head);
+}
+
+/// Walk the list from root0, and check that the objects are next to each other.
+fn walk_list() -> Vec<ObjectReference> {
+    let head = Slot::load(&root_slot(0)).unwrap();
+    let mut objects = vec![];
+    let mut next = Some(head);
+    while let Some(object) = next {
+        assert_eq!(Slot::load(&field(object, 1)), Some(head));
+        assert_eq!(
+            object.to_raw_address(),
+            head.to_raw_address() + objects.len() * OBJECT_SIZE
+        );
+        objects.push(object);
+        next = Slot::load(&field(object, 0));
+    }
+    objects
+}
+
+#[test]
+pub fn mark_compact_side_forwarding() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::MarkCompact, |_| {});
+            let plan = mmtk
+                .get_plan()
+                .downcast_ref::<MarkCompact<MockVM>>()
+                .unwrap();
+
+            // No memory is reserved before the objects for forwarding pointers.
+            assert_eq!(MarkCompactSpace::<MockVM>::HEADER_RESERVED_IN_BYTES, 0);
+            const NUM_OBJECTS: usize = 200;
+            let objects: Vec<ObjectReference> =
+                (0..NUM_OBJECTS).map(|_| alloc_object(mutator)).collect();
+            for pair in objects.windows(2) {
+                assert!(plan.mc_space().in_space(pair[0]));
+                assert_eq!(
+                    pair[1].to_raw_address() - pair[0].to_raw_address(),
+                    OBJECT_SIZE
+                );
+            }
+
+            // Every other object is live.  Each forwarding block has several objects, and the live
+            // objects slide over the dead ones.
+            let live: Vec<ObjectReference> = objects.iter().copied().step_by(2).collect();
+            make_list(&live);
+            collect(mmtk);
+            let compacted = walk_list();
+            assert_eq!(compacted.len(), live.len());
+            assert_eq!(compacted[0], objects[0]);
+            assert_eq!(compacted[1], objects[1]);
+            // The objects are no longer forwarded after the GC.
+            for object in &compacted {
+                assert!(object.get_forwarded_object().is_none());
+            }
+
+            // The forwarding table is computed again in the next GC.
+            let live: Vec<ObjectReference> = compacted.iter().copied().skip(1).step_by(2).collect();
+            make_list(&live);
+            collect(mmtk);
+            let compacted = walk_list();
+            assert_eq!(compacted.len(), live.len());
+            assert_eq!(compacted[0], objects[0]);
+        },
+        no_cleanup,
+    )
+}
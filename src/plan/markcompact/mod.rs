@@ -4,3 +4,17 @@ pub(super) mod mutator;
 
 pub use self::global::MarkCompact;
 pub use self::global::MARKCOMPACT_CONSTRAINTS;
+
+#[cfg(all(test, feature = "mock_test"))]
+mod mock_test_mark_compact_parallel;
+#[cfg(all(test, feature = "mock_test_side_mark_bit", feature = "object_pinning"))]
+mod mock_test_mark_compact_pinning;
+#[cfg(all(
+    test,
+    feature = "mock_test_side_mark_bit",
+    feature = "object_pinning",
+    feature = "mark_compact_side_forwarding"
+))]
+mod mock_test_mark_compact_pinning_side_forwarding;
+#[cfg(all(test, feature = "mock_test", feature = "mark_compact_side_forwarding"))]
+mod mock_test_mark_compact_side_forwarding;
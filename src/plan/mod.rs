@@ -39,6 +39,8 @@ pub(crate) use plan_constraints::DEFAULT_PLAN_CONSTRAINTS;
 mod tracing;
 pub use tracing::{ObjectQueue, ObjectsClosure, VectorObjectQueue, VectorQueue};
 
+/// Concurrent plans (marking concurrently with mutators)
+mod concurrent;
 /// Generational plans (with a copying nursery)
 mod generational;
 /// Sticky plans (using sticky marks for generational behaviors without a copying nursery)
@@ -49,6 +51,12 @@ mod markcompact;
 mod marksweep;
 mod nogc;
 mod pageprotect;
+/// Reference counting plans (with backup tracing to collect cycles)
+mod rcimmix;
+/// Region-based plans (evacuating a collection set of regions in each GC)
+mod regional;
+/// Hybrid plans (copying while the heap is small, and marking in place when it grows)
+mod semiimmix;
 mod semispace;
 
 pub(crate) use generational::global::is_nursery_gc;
@@ -57,12 +65,18 @@ pub(crate) use generational::global::GenerationalPlan;
 // Expose plan constraints as public. Though a binding can get them from plan.constraints(),
 // it is possible for performance reasons that they want the constraints as constants.
 
+pub use concurrent::immix::CONCURRENT_IMMIX_CONSTRAINTS;
 pub use generational::copying::GENCOPY_CONSTRAINTS;
 pub use generational::immix::GENIMMIX_CONSTRAINTS;
+pub use generational::marksweep::GENMS_CONSTRAINTS;
 pub use immix::IMMIX_CONSTRAINTS;
 pub use markcompact::MARKCOMPACT_CONSTRAINTS;
 pub use marksweep::MS_CONSTRAINTS;
 pub use nogc::NOGC_CONSTRAINTS;
 pub use pageprotect::PP_CONSTRAINTS;
+pub use rcimmix::RCIMMIX_CONSTRAINTS;
+pub use regional::REGIONAL_CONSTRAINTS;
+pub use semiimmix::SEMI_IMMIX_CONSTRAINTS;
 pub use semispace::SS_CONSTRAINTS;
 pub use sticky::immix::STICKY_IMMIX_CONSTRAINTS;
+pub use sticky::marksweep::STICKY_MS_CONSTRAINTS;
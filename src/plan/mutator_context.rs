@@ -248,6 +248,10 @@ impl<VM: VMBinding> Mutator<VM> {
                     offset_of!(Allocators<VM>, markcompact)
                         + size_of::<MarkCompactAllocator<VM>>() * index as usize
                 }
+                AllocatorSelector::Region(index) => {
+                    offset_of!(Allocators<VM>, region)
+                        + size_of::<RegionAllocator<VM>>() * index as usize
+                }
                 AllocatorSelector::None => panic!("Expect a valid AllocatorSelector, found None"),
             }
     }
@@ -324,6 +328,7 @@ pub(crate) struct ReservedAllocators {
     pub n_immix: u8,
     pub n_mark_compact: u8,
     pub n_free_list: u8,
+    pub n_region: u8,
 }
 
 impl ReservedAllocators {
@@ -334,6 +339,7 @@ impl ReservedAllocators {
         n_immix: 0,
         n_mark_compact: 0,
         n_free_list: 0,
+        n_region: 0,
     };
     /// check if the number of each allocator is okay. Panics if any allocator exceeds the max number.
     fn validate(&self) {
@@ -362,6 +368,10 @@ impl ReservedAllocators {
             self.n_free_list as usize <= MAX_FREE_LIST_ALLOCATORS,
             "Allocator mapping declared more free list allocators than the max allowed."
         );
+        assert!(
+            self.n_region as usize <= MAX_REGION_ALLOCATORS,
+            "Allocator mapping declared more region allocators than the max allowed."
+        );
     }
 }
 
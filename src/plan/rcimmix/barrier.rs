@@ -0,0 +1,111 @@
+//! Write barrier semantics for the RCImmix plan.
+
+use super::gc_work::ProcessModBuf;
+use super::global::RCImmix;
+use crate::plan::barriers::{log_field, BarrierSemantics};
+use crate::plan::VectorQueue;
+use crate::policy::space::Space;
+use crate::scheduler::WorkBucketStage;
+use crate::util::*;
+use crate::vm::slot::{MemorySlice, Slot};
+use crate::vm::{Scanning, VMBinding};
+use crate::MMTK;
+
+/// The slow-path semantics of the field logging barrier for RCImmix.  When a field is logged
+/// before its first modification since the last GC, the barrier remembers the current referent of
+/// the field as a decrement, and remembers the slot in the modbuf.  In the next RC pause, the
+/// referents of the modbuf slots at that time are incremented.  In this way, all the modifications
+/// to a field between two GCs are coalesced into one decrement and one increment.
+pub struct RCImmixBarrierSemantics<VM: VMBinding> {
+    /// MMTk instance
+    mmtk: &'static MMTK<VM>,
+    /// RCImmix plan
+    plan: &'static RCImmix<VM>,
+    /// Slot modbuf. Contains a list of fields that are logged since the last GC.
+    modbuf: VectorQueue<VM::VMSlot>,
+    /// Decrement buffer. Contains the referents of the logged fields at the time they are logged.
+    decs: VectorQueue<ObjectReference>,
+}
+
+impl<VM: VMBinding> RCImmixBarrierSemantics<VM> {
+    pub fn new(mmtk: &'static MMTK<VM>, plan: &'static RCImmix<VM>) -> Self {
+        Self {
+            mmtk,
+            plan,
+            modbuf: VectorQueue::new(),
+            decs: VectorQueue::new(),
+        }
+    }
+
+    fn flush_modbuf(&mut self) {
+        let buf = self.modbuf.take();
+        if !buf.is_empty() {
+            self.mmtk.scheduler.work_buckets[WorkBucketStage::Closure]
+                .add(ProcessModBuf::<VM>::new(buf));
+        }
+    }
+
+    fn flush_decs(&mut self) {
+        let buf = self.decs.take();
+        if !buf.is_empty() {
+            self.plan.add_dec_buffer(buf);
+        }
+    }
+
+    /// Remember a field that has just been logged, and its current referent.
+    fn remember(&mut self, slot: VM::VMSlot) {
+        // Only objects in the Immix space are counted.
+        if let Some(old) = slot.load() {
+            if self.plan.immix_space().in_space(old) {
+                self.decs.push(old);
+                self.decs.is_full().then(|| self.flush_decs());
+            }
+        }
+        self.modbuf.push(slot);
+        self.modbuf.is_full().then(|| self.flush_modbuf());
+    }
+
+    /// Log and remember a field, unless it is logged.
+    fn log_and_remember(&mut self, slot: VM::VMSlot) {
+        if log_field(slot.to_address()) {
+            self.remember(slot);
+        }
+    }
+}
+
+impl<VM: VMBinding> BarrierSemantics for RCImmixBarrierSemantics<VM> {
+    type VM = VM;
+
+    fn flush(&mut self) {
+        self.flush_modbuf();
+        self.flush_decs();
+    }
+
+    fn object_reference_write_slow(
+        &mut self,
+        _src: ObjectReference,
+        slot: VM::VMSlot,
+        _target: Option<ObjectReference>,
+    ) {
+        // The field has been logged by the barrier.  This is called before the store, so we see
+        // the old referent of the field.
+        self.remember(slot);
+    }
+
+    fn memory_region_copy_slow(&mut self, _src: VM::VMMemorySlice, dst: VM::VMMemorySlice) {
+        // Slots outside objects are not counted.  They are reported as roots, and are counted as
+        // roots in every GC.
+        if dst.object().is_some() {
+            for slot in dst.iter_slots() {
+                self.log_and_remember(slot);
+            }
+        }
+    }
+
+    fn object_probable_write_slow(&mut self, obj: ObjectReference) {
+        let tls = VMWorkerThread(VMThread::UNINITIALIZED);
+        VM::VMScanning::scan_object(tls, obj, &mut |slot: VM::VMSlot| {
+            self.log_and_remember(slot);
+        });
+    }
+}
@@ -0,0 +1,306 @@
+use super::global::RCImmix;
+use super::rc;
+use crate::plan::barriers::{log_all_fields, unlog_field, FIELD_UNLOG_BIT_SPEC};
+use crate::plan::Plan;
+use crate::policy::immix::block::{Block, BlockState};
+use crate::policy::space::Space;
+use crate::scheduler::gc_work::{ProcessEdgesBase, ProcessEdgesWork, ScanObjects, SlotOf};
+use crate::scheduler::{GCWork, GCWorkContext, GCWorker, WorkBucketStage};
+use crate::util::heap::chunk_map::Chunk;
+use crate::util::linear_scan::Region;
+use crate::util::object_enum::ClosureObjectEnumerator;
+use crate::util::ObjectReference;
+use crate::vm::slot::Slot;
+use crate::vm::*;
+use crate::MMTK;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+pub struct RCImmixGCWorkContext<VM: VMBinding>(PhantomData<VM>);
+
+impl<VM: VMBinding> GCWorkContext for RCImmixGCWorkContext<VM> {
+    type VM = VM;
+    type PlanType = RCImmix<VM>;
+    type DefaultProcessEdges = RCImmixProcessEdges<VM>;
+    // RCImmix never moves objects.
+    type PinningProcessEdges = RCImmixProcessEdges<VM>;
+}
+
+/// Process edges for RCImmix.  Tracing an object in the Immix space increments its count, and
+/// scans it when it is reached for the first time.  See [`RCImmix::trace_object_counting`].  This
+/// is used by both RC pauses and backup traces.
+///
+/// The fields processed by this work packet are unlogged, so that the barrier remembers their next
+/// modifications.  They are the fields of the objects being scanned, and the fields in the modbufs.
+pub struct RCImmixProcessEdges<VM: VMBinding> {
+    plan: &'static RCImmix<VM>,
+    base: ProcessEdgesBase<VM>,
+}
+
+impl<VM: VMBinding> ProcessEdgesWork for RCImmixProcessEdges<VM> {
+    type VM = VM;
+    type ScanObjectsWorkType = ScanObjects<Self>;
+
+    const OVERWRITE_REFERENCE: bool = false;
+
+    fn new(
+        slots: Vec<SlotOf<Self>>,
+        roots: bool,
+        mmtk: &'static MMTK<VM>,
+        bucket: WorkBucketStage,
+    ) -> Self {
+        let base = ProcessEdgesBase::new(slots, roots, mmtk, bucket);
+        let plan = base.plan().downcast_ref().unwrap();
+        Self { plan, base }
+    }
+
+    fn trace_object(&mut self, object: ObjectReference) -> ObjectReference {
+        // We cannot borrow `self` twice in a call, so we extract `worker` as a local variable.
+        let worker = self.worker();
+        let root = self.roots;
+        self.plan
+            .trace_object_counting(&mut self.base.nodes, object, worker, root)
+    }
+
+    fn create_scan_work(&self, nodes: Vec<ObjectReference>) -> Self::ScanObjectsWorkType {
+        ScanObjects::new(nodes, false, self.bucket)
+    }
+
+    fn process_slot(&mut self, slot: SlotOf<Self>) {
+        // Root slots are not fields.
+        if !self.roots {
+            unlog_field(slot.to_address());
+        }
+        if let Some(object) = slot.load() {
+            self.trace_object(object);
+        }
+    }
+}
+
+impl<VM: VMBinding> Deref for RCImmixProcessEdges<VM> {
+    type Target = ProcessEdgesBase<VM>;
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl<VM: VMBinding> DerefMut for RCImmixProcessEdges<VM> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+/// The modbuf contains a list of fields that have been logged by the barrier since the last GC.
+/// This work packet unlogs them, and increments their current referents in an RC pause.
+pub struct ProcessModBuf<VM: VMBinding> {
+    modbuf: Vec<VM::VMSlot>,
+}
+
+impl<VM: VMBinding> ProcessModBuf<VM> {
+    pub fn new(modbuf: Vec<VM::VMSlot>) -> Self {
+        debug_assert!(!modbuf.is_empty());
+        Self { modbuf }
+    }
+}
+
+impl<VM: VMBinding> GCWork<VM> for ProcessModBuf<VM> {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, mmtk: &'static MMTK<VM>) {
+        let plan = mmtk.get_plan().downcast_ref::<RCImmix<VM>>().unwrap();
+        // In a backup trace, the fields of live objects are unlogged when the objects are
+        // scanned, and the fields of dead ones should stay logged.
+        if plan.is_current_gc_backup_trace() {
+            return;
+        }
+        // Unlog the fields and increment their referents.
+        let modbuf = std::mem::take(&mut self.modbuf);
+        GCWork::do_work(
+            &mut RCImmixProcessEdges::<VM>::new(modbuf, false, mmtk, WorkBucketStage::Closure),
+            worker,
+            mmtk,
+        )
+    }
+}
+
+/// The sentinel of the `Closure` bucket in an RC pause.  When all the increments have been
+/// processed, it schedules the decrements remembered by the barriers, and the decrements of the
+/// roots of the last GC.
+pub(super) struct ScheduleDecrements<VM: VMBinding> {
+    plan: &'static RCImmix<VM>,
+}
+
+impl<VM: VMBinding> ScheduleDecrements<VM> {
+    pub fn new(plan: &'static RCImmix<VM>) -> Self {
+        Self { plan }
+    }
+}
+
+impl<VM: VMBinding> GCWork<VM> for ScheduleDecrements<VM> {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let bucket = &worker.scheduler().work_buckets[WorkBucketStage::Closure];
+        let packets = self
+            .plan
+            .take_decrements()
+            .into_iter()
+            .map(|decs| Box::new(ProcessDecrements::<VM>::new(decs)) as Box<dyn GCWork<VM>>)
+            .collect();
+        bucket.bulk_add(packets);
+        // The objects that still have non-zero counts after the decrements are live.
+        bucket.set_sentinel(Box::new(ScheduleMarkCountedObjects::new(self.plan)));
+    }
+}
+
+/// Decrement the counts of objects.  If the count of an object drops to zero, the object is dead,
+/// and the counts of its referents are decremented recursively.
+pub(super) struct ProcessDecrements<VM: VMBinding> {
+    decs: Vec<ObjectReference>,
+    phantom: PhantomData<VM>,
+}
+
+impl<VM: VMBinding> ProcessDecrements<VM> {
+    /// The maximum number of pending decrements in a work packet.  Larger packets are split.
+    const CAPACITY: usize = RCImmixProcessEdges::<VM>::CAPACITY;
+
+    pub fn new(decs: Vec<ObjectReference>) -> Self {
+        Self {
+            decs,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<VM: VMBinding> GCWork<VM> for ProcessDecrements<VM> {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, mmtk: &'static MMTK<VM>) {
+        let plan = mmtk.get_plan().downcast_ref::<RCImmix<VM>>().unwrap();
+        let immix_space = plan.immix_space();
+        let tls = worker.tls;
+        let mut decs = std::mem::take(&mut self.decs);
+        while let Some(object) = decs.pop() {
+            debug_assert!(immix_space.in_space(object));
+            if rc::decrement(object) != 1 {
+                continue;
+            }
+            // The object is dead.  Its memory will be reclaimed when its lines are swept.  Log its
+            // fields so that the barrier ignores new objects allocated in its place.
+            log_all_fields::<VM>(object);
+            let mut enqueue = |referent: ObjectReference| {
+                if immix_space.in_space(referent) {
+                    decs.push(referent);
+                }
+            };
+            if VM::VMScanning::support_slot_enqueuing(tls, object) {
+                VM::VMScanning::scan_object(tls, object, &mut |slot: VM::VMSlot| {
+                    if let Some(referent) = slot.load() {
+                        enqueue(referent);
+                    }
+                });
+            } else {
+                VM::VMScanning::scan_object_and_trace_edges(tls, object, &mut |referent| {
+                    enqueue(referent);
+                    referent
+                });
+            }
+            if decs.len() >= Self::CAPACITY {
+                let half = decs.split_off(decs.len() / 2);
+                worker.add_work(WorkBucketStage::Closure, ProcessDecrements::<VM>::new(half));
+            }
+        }
+    }
+}
+
+/// Schedule [`MarkCountedObjects`] for all the chunks of the Immix space.
+pub(super) struct ScheduleMarkCountedObjects<VM: VMBinding> {
+    plan: &'static RCImmix<VM>,
+}
+
+impl<VM: VMBinding> ScheduleMarkCountedObjects<VM> {
+    pub fn new(plan: &'static RCImmix<VM>) -> Self {
+        Self { plan }
+    }
+}
+
+impl<VM: VMBinding> GCWork<VM> for ScheduleMarkCountedObjects<VM> {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let plan = self.plan;
+        let packets = plan.immix_space().chunk_map.generate_tasks(|chunk| {
+            Box::new(MarkCountedObjects { plan, chunk }) as Box<dyn GCWork<VM>>
+        });
+        worker.scheduler().work_buckets[WorkBucketStage::Closure].bulk_add(packets);
+    }
+}
+
+/// Mark all the objects with non-zero counts in a chunk, so that the lines they occupy are not
+/// swept.
+struct MarkCountedObjects<VM: VMBinding> {
+    plan: &'static RCImmix<VM>,
+    chunk: Chunk,
+}
+
+impl<VM: VMBinding> GCWork<VM> for MarkCountedObjects<VM> {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let immix_space = self.plan.immix_space();
+        for block in self
+            .chunk
+            .iter_region::<Block>()
+            .filter(|block| block.get_state() != BlockState::Unallocated)
+        {
+            rc::for_each_counted_object(block.start(), block.end(), |object| {
+                immix_space.mark_without_tracing(object);
+            });
+        }
+    }
+}
+
+/// Schedule [`ResetCounts`] for all the chunks of the Immix space, and [`LogLargeObjectFields`],
+/// before a backup trace.
+pub(super) struct ScheduleResetCounts<VM: VMBinding> {
+    plan: &'static RCImmix<VM>,
+}
+
+impl<VM: VMBinding> ScheduleResetCounts<VM> {
+    pub fn new(plan: &'static RCImmix<VM>) -> Self {
+        Self { plan }
+    }
+}
+
+impl<VM: VMBinding> GCWork<VM> for ScheduleResetCounts<VM> {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let packets = self.plan.immix_space().chunk_map.generate_tasks(|chunk| {
+            Box::new(ResetCounts::<VM> {
+                chunk,
+                phantom: PhantomData,
+            }) as Box<dyn GCWork<VM>>
+        });
+        let bucket = &worker.scheduler().work_buckets[WorkBucketStage::Prepare];
+        bucket.bulk_add(packets);
+        bucket.add(LogLargeObjectFields { plan: self.plan });
+    }
+}
+
+/// Clear the counts of all the objects in a chunk, and log all their fields.  A backup trace
+/// counts the references to every object it reaches, and unlogs the fields of the object.
+struct ResetCounts<VM: VMBinding> {
+    chunk: Chunk,
+    phantom: PhantomData<VM>,
+}
+
+impl<VM: VMBinding> GCWork<VM> for ResetCounts<VM> {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        rc::RC_SPEC.bzero_metadata(self.chunk.start(), Chunk::BYTES);
+        FIELD_UNLOG_BIT_SPEC.bzero_metadata(self.chunk.start(), Chunk::BYTES);
+    }
+}
+
+/// Log the fields of all the objects in the large object space before a backup trace.  Large
+/// objects that are not new are only reclaimed by backup traces, and the barrier should ignore the
+/// fields of new objects allocated in their place.  The fields of live objects are unlogged again
+/// when they are scanned.
+struct LogLargeObjectFields<VM: VMBinding> {
+    plan: &'static RCImmix<VM>,
+}
+
+impl<VM: VMBinding> GCWork<VM> for LogLargeObjectFields<VM> {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let mut enumerator = ClosureObjectEnumerator::<_, VM>::new(log_all_fields::<VM>);
+        self.plan.common().los.enumerate_objects(&mut enumerator);
+    }
+}
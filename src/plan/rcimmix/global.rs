@@ -0,0 +1,301 @@
+use super::barrier::RCImmixBarrierSemantics;
+use super::gc_work::{RCImmixGCWorkContext, ScheduleDecrements, ScheduleResetCounts};
+use super::rc;
+use crate::plan::barriers::{log_object, FIELD_UNLOG_BIT_SPEC};
+use crate::plan::global::BasePlan;
+use crate::plan::global::CommonPlan;
+use crate::plan::global::CreateGeneralPlanArgs;
+use crate::plan::global::CreateSpecificPlanArgs;
+use crate::plan::immix;
+use crate::plan::AllocationSemantics;
+use crate::plan::ObjectQueue;
+use crate::plan::Plan;
+use crate::plan::PlanConstraints;
+use crate::policy::immix::ImmixSpace;
+use crate::policy::immix::ImmixSpaceArgs;
+use crate::policy::space::Space;
+use crate::scheduler::{GCWorkScheduler, GCWorker, WorkBucketStage};
+use crate::util::alloc::allocators::AllocatorSelector;
+use crate::util::heap::gc_trigger::SpaceStats;
+use crate::util::metadata::side_metadata::SideMetadataContext;
+use crate::util::statistics::counter::EventCounter;
+use crate::util::{ObjectReference, VMWorkerThread};
+use crate::vm::{ObjectModel, VMBinding};
+
+use crossbeam::queue::SegQueue;
+use enum_map::EnumMap;
+use mmtk_macros::HasSpaces;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// RCImmix reclaims objects in an Immix space with deferred reference counting.
+///
+/// * Mutators do not count references from roots.  Instead, each GC increments the counts of the
+///   objects referenced by roots, and the next GC decrements them.
+/// * The field logging barrier logs a field before its first modification after a GC, and
+///   remembers the referent of the field at that time as a decrement.  The next GC increments the
+///   referents of the logged fields at that time, and unlogs the fields again.
+/// * New objects are allocated with zero counts, and their fields are logged.  A new object is
+///   scanned (and its referents are incremented and its fields are unlogged) when its count is
+///   first incremented.  New objects that are not incremented in the first GC after their
+///   allocation are reclaimed without being scanned.  Objects in the immortal spaces are scanned
+///   in the same way when they are first reached.
+///
+/// A GC (an RC pause) processes all the increments before all the decrements.  Objects with zero
+/// counts after that are dead, and lines without any counted objects are swept.
+///
+/// Reference counting alone cannot reclaim cyclic garbage, or objects with stuck counts.  A backup
+/// trace computes the counts from scratch with a transitive closure, reclaiming all the dead
+/// objects.  Objects in the large object space are not counted.  New large objects are reclaimed
+/// in RC pauses like new objects in the Immix space, but other dead large objects are only
+/// reclaimed by backup traces, and their referents are only decremented by backup traces.
+/// Increments made when retaining objects during weak reference processing are not decremented
+/// either until the next backup trace.
+///
+/// The barrier keeps an unlog bit for every field, and finds the fields of an object by scanning
+/// it.  So RCImmix requires the binding to enqueue slots for all objects (see
+/// [`crate::vm::Scanning::support_slot_enqueuing`]), and indexes the bits with
+/// [`crate::vm::slot::Slot::to_address`].
+#[derive(HasSpaces)]
+pub struct RCImmix<VM: VMBinding> {
+    #[parent]
+    immix: immix::Immix<VM>,
+    /// Is the current GC a backup trace?
+    backup_trace: AtomicBool,
+    /// Should the next GC be a backup trace?
+    next_gc_backup_trace: AtomicBool,
+    /// Decrement buffers flushed by the barriers since the last GC.
+    dec_buffers: Mutex<Vec<Vec<ObjectReference>>>,
+    /// The objects in the Immix space referenced by roots in the current GC.
+    roots: SegQueue<ObjectReference>,
+    /// The objects in the Immix space referenced by roots in the last GC.  Their counts are
+    /// decremented in the current GC.
+    prev_roots: Mutex<Vec<ObjectReference>>,
+    backup_trace_count: Arc<Mutex<EventCounter>>,
+}
+
+/// The plan constraints for the RC immix plan.
+pub const RCIMMIX_CONSTRAINTS: PlanConstraints = PlanConstraints {
+    moves_objects: false,
+    // The log bits mark the objects outside the Immix space that have not been scanned. See
+    // `RCImmix::trace_object_counting`.
+    needs_log_bit: true,
+    barrier: crate::plan::BarrierSelector::FieldLoggingBarrier,
+    // We may trace duplicate edges in RC immix (or any plan that uses object remembering barrier). See https://github.com/mmtk/mmtk-core/issues/743.
+    may_trace_duplicate_edges: true,
+    ..immix::IMMIX_CONSTRAINTS
+};
+
+/// Do a backup trace after an RC pause if less than this fraction of the heap is available.
+const BACKUP_TRACE_THRESHOLD: f64 = 0.2;
+
+impl<VM: VMBinding> Plan for RCImmix<VM> {
+    fn constraints(&self) -> &'static PlanConstraints {
+        &RCIMMIX_CONSTRAINTS
+    }
+
+    fn base(&self) -> &BasePlan<VM> {
+        self.immix.base()
+    }
+
+    fn base_mut(&mut self) -> &mut BasePlan<VM> {
+        self.immix.base_mut()
+    }
+
+    fn common(&self) -> &CommonPlan<VM> {
+        self.immix.common()
+    }
+
+    fn schedule_collection(&'static self, scheduler: &GCWorkScheduler<VM>) {
+        let backup_trace = self.requires_backup_trace();
+        self.backup_trace.store(backup_trace, Ordering::SeqCst);
+
+        scheduler.schedule_common_work::<RCImmixGCWorkContext<VM>>(self);
+        if backup_trace {
+            info!("Backup trace");
+            scheduler.work_buckets[WorkBucketStage::Prepare]
+                .add(ScheduleResetCounts::<VM>::new(self));
+        } else {
+            info!("RC pause");
+            scheduler.work_buckets[WorkBucketStage::Closure]
+                .set_sentinel(Box::new(ScheduleDecrements::<VM>::new(self)));
+        }
+    }
+
+    fn get_allocator_mapping(&self) -> &'static EnumMap<AllocationSemantics, AllocatorSelector> {
+        &super::mutator::ALLOCATOR_MAPPING
+    }
+
+    fn prepare(&mut self, tls: VMWorkerThread) {
+        let backup_trace = self.is_current_gc_backup_trace();
+        if backup_trace {
+            self.backup_trace_count.lock().unwrap().inc();
+        }
+        self.immix.common.prepare(tls, backup_trace);
+        // Marks are reset in every GC, and the objects with non-zero counts are marked again.
+        self.immix.immix_space.prepare(
+            true,
+            crate::policy::immix::defrag::StatsForDefrag::new(self),
+        );
+    }
+
+    fn release(&mut self, tls: VMWorkerThread) {
+        let backup_trace = self.is_current_gc_backup_trace();
+        self.immix.common.release(tls, backup_trace);
+        self.immix.immix_space.release(true);
+        if backup_trace {
+            // The backup trace has computed the counts from scratch.  The pending decrements are
+            // stale.
+            self.dec_buffers.get_mut().unwrap().clear();
+        }
+        // The counts of the objects referenced by roots in this GC are decremented in the next GC.
+        let prev_roots = self.prev_roots.get_mut().unwrap();
+        prev_roots.clear();
+        while let Some(object) = self.roots.pop() {
+            prev_roots.push(object);
+        }
+    }
+
+    fn end_of_gc(&mut self, _tls: VMWorkerThread) {
+        // If an RC pause cannot free enough memory, the heap may be filled with cyclic garbage.
+        let next_gc_backup_trace = !self.is_current_gc_backup_trace()
+            && (self.get_available_pages() as f64)
+                < self.get_total_pages() as f64 * BACKUP_TRACE_THRESHOLD;
+        self.next_gc_backup_trace
+            .store(next_gc_backup_trace, Ordering::Relaxed);
+        self.immix.immix_space.end_of_gc();
+    }
+
+    fn collection_required(&self, space_full: bool, space: Option<SpaceStats<Self::VM>>) -> bool {
+        self.immix.collection_required(space_full, space)
+    }
+
+    fn last_collection_was_exhaustive(&self) -> bool {
+        self.is_current_gc_backup_trace()
+    }
+
+    fn current_gc_may_move_object(&self) -> bool {
+        false
+    }
+
+    fn get_used_pages(&self) -> usize {
+        self.immix.get_used_pages()
+    }
+}
+
+impl<VM: VMBinding> RCImmix<VM> {
+    pub fn new(args: CreateGeneralPlanArgs<VM>) -> Self {
+        let backup_trace_count = args.stats.new_event_counter("backupTrace", true, true);
+        let mut global_specs = crate::util::metadata::extract_side_metadata(&[
+            *VM::VMObjectModel::GLOBAL_LOG_BIT_SPEC,
+        ]);
+        global_specs.push(rc::RC_SPEC);
+        global_specs.push(FIELD_UNLOG_BIT_SPEC);
+        let plan_args = CreateSpecificPlanArgs {
+            global_args: args,
+            constraints: &RCIMMIX_CONSTRAINTS,
+            global_side_metadata_specs: SideMetadataContext::new_global_specs(&global_specs),
+        };
+
+        let immix = immix::Immix::new_with_args(
+            plan_args,
+            ImmixSpaceArgs {
+                // The barrier logs fields instead of objects.  The unlog bits of the fields are
+                // maintained by the plan.
+                reset_log_bit_in_major_gc: false,
+                unlog_object_when_traced: false,
+                // New objects are allocated in the ImmixSpace among old objects.
+                #[cfg(feature = "vo_bit")]
+                mixed_age: true,
+            },
+        );
+        Self {
+            immix,
+            backup_trace: AtomicBool::new(false),
+            next_gc_backup_trace: AtomicBool::new(false),
+            dec_buffers: Mutex::new(vec![]),
+            roots: SegQueue::new(),
+            prev_roots: Mutex::new(vec![]),
+            backup_trace_count,
+        }
+    }
+
+    fn requires_backup_trace(&self) -> bool {
+        let global_state = &self.immix.common.base.global_state;
+        // Separate each condition so the code is clear
+        #[allow(clippy::if_same_then_else, clippy::needless_bool)]
+        if global_state
+            .user_triggered_collection
+            .load(Ordering::SeqCst)
+            && *self.immix.common.base.options.full_heap_system_gc
+        {
+            // User triggered collection, and we force full heap for user triggered collection
+            true
+        } else if self.next_gc_backup_trace.load(Ordering::SeqCst)
+            || global_state.is_emergency_collection()
+            || global_state.cur_collection_attempts.load(Ordering::SeqCst) > 1
+        {
+            // Forces a backup trace
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Is the current GC a backup trace?
+    pub fn is_current_gc_backup_trace(&self) -> bool {
+        self.backup_trace.load(Ordering::SeqCst)
+    }
+
+    pub fn immix_space(&self) -> &ImmixSpace<VM> {
+        &self.immix.immix_space
+    }
+
+    /// Trace an object in an RC pause or a backup trace.  The count of an object in the Immix
+    /// space is incremented, and the object is marked and scanned when its count is incremented
+    /// from zero, i.e. when it is a new object in an RC pause, or when it is first reached in a
+    /// backup trace.  Other objects are traced as in a nursery GC in an RC pause, and as in a full
+    /// heap GC in a backup trace.
+    pub(super) fn trace_object_counting<Q: ObjectQueue>(
+        &self,
+        queue: &mut Q,
+        object: ObjectReference,
+        worker: &mut GCWorker<VM>,
+        root: bool,
+    ) -> ObjectReference {
+        let immix_space = self.immix_space();
+        if immix_space.in_space(object) {
+            if root {
+                self.roots.push(object);
+            }
+            if rc::increment(object) == 0 {
+                immix_space.mark_without_tracing(object);
+                queue.enqueue(object);
+            }
+        } else if self.is_current_gc_backup_trace() {
+            self.immix.common.trace_object(queue, object, worker);
+        } else if self.immix.common.los.in_space(object) {
+            self.immix.common.los.trace_object(queue, object);
+        } else if log_object::<RCImmixBarrierSemantics<VM>>(object) {
+            // Objects in the other spaces are unlogged when they are allocated, and are logged and
+            // scanned when they are first reached, so that their fields are unlogged.
+            queue.enqueue(object);
+        }
+        object
+    }
+
+    /// Add a buffer of decrements from a barrier.
+    pub(super) fn add_dec_buffer(&self, decs: Vec<ObjectReference>) {
+        self.dec_buffers.lock().unwrap().push(decs);
+    }
+
+    /// Take all the pending decrements, including the decrements of the roots of the last GC.
+    pub(super) fn take_decrements(&self) -> Vec<Vec<ObjectReference>> {
+        let mut decs = std::mem::take(&mut *self.dec_buffers.lock().unwrap());
+        let prev_roots = std::mem::take(&mut *self.prev_roots.lock().unwrap());
+        if !prev_roots.is_empty() {
+            decs.push(prev_roots);
+        }
+        decs
+    }
+}
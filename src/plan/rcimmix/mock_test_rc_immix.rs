@@ -0,0 +1,144 @@
+// GITHUB-CI: MMTK_PLAN=RCImmix
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use std::sync::atomic::Ordering;
+
+use super::gc_work::RCImmixGCWorkContext;
+use super::rc;
+use super::RCImmix;
+use crate::memory_manager;
+use crate::plan::barriers::field_is_unlogged;
+use crate::plan::Plan;
+use crate::policy::space::Space;
+use crate::util::options::PlanSelector;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::{Address, VMMutatorThread, VMThread};
+use crate::vm::slot::Slot;
+use crate::MMTK;
+
+fn setup() -> MockVM {
+    mock_roots::setup::<RCImmixGCWorkContext<MockVM>, RCImmixGCWorkContext<MockVM>>()
+}
+
+fn collect(mmtk: &'static MMTK<MockVM>) {
+    // The GC is not requested after a failed allocation, so it should not be counted as a retry,
+    // which forces a backup trace. The Immix allocator does not always report successful
+    // allocations when it reuses recyclable lines.
+    mmtk.state.allocation_success.store(true, Ordering::SeqCst);
+    wait_for_gc(|| mmtk.gc_requester.request());
+}
+
+/// Request a GC like a user, which is a backup trace with `full_heap_system_gc`.
+fn collect_by_user(mmtk: &'static MMTK<MockVM>) {
+    wait_for_gc(|| {
+        memory_manager::handle_user_collection_request(
+            mmtk,
+            VMMutatorThread(VMThread::UNINITIALIZED),
+        );
+    });
+}
+
+#[test]
+pub fn rc_immix() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::RCImmix, |builder| {
+                builder.options.full_heap_system_gc.set(true);
+            });
+            let plan = mmtk.get_plan().downcast_ref::<RCImmix<MockVM>>().unwrap();
+
+            // root0 -> a -> b, and d is unreachable.
+            let a = alloc_object(mutator);
+            let b = alloc_object(mutator);
+            let d = alloc_object(mutator);
+            Slot::store(&field(a, 0), b);
+            Slot::store(&root_slot(0), a);
+            for object in [a, b, d] {
+                assert!(plan.immix_space().in_space(object));
+                assert_eq!(rc::count(object), 0);
+            }
+
+            // New objects are counted when they are reached in an RC pause, and new objects that
+            // are not reached are reclaimed.
+            collect(mmtk);
+            assert!(!plan.last_collection_was_exhaustive());
+            for object in [a, b] {
+                assert!(object.is_live());
+                assert_eq!(rc::count(object), 1);
+            }
+            assert!(!d.is_live());
+
+            // The barrier remembers b as a decrement and c as an increment. The root of the last
+            // GC is decremented, and incremented again as a root of this GC. Fields are logged
+            // independently.
+            let c = alloc_object(mutator);
+            memory_manager::object_reference_write_pre(mutator, a, field(a, 0), None);
+            unsafe { field(a, 0).store(Address::ZERO) };
+            memory_manager::object_reference_write_post(mutator, a, field(a, 0), None);
+            assert!(!field_is_unlogged(field(a, 0)));
+            assert!(field_is_unlogged(field(a, 1)));
+            memory_manager::object_reference_write_pre(mutator, a, field(a, 1), Some(c));
+            Slot::store(&field(a, 1), c);
+            memory_manager::object_reference_write_post(mutator, a, field(a, 1), Some(c));
+            assert!(!field_is_unlogged(field(a, 1)));
+            collect(mmtk);
+            assert!(!plan.last_collection_was_exhaustive());
+            for object in [a, c] {
+                assert!(object.is_live());
+                assert_eq!(rc::count(object), 1);
+            }
+            assert!(!b.is_live());
+            // The logged fields are unlogged by the GC, and so are the fields of new objects that
+            // are reached, while the fields of dead objects are logged.
+            for slot in [field(a, 0), field(a, 1), field(c, 0)] {
+                assert!(field_is_unlogged(slot));
+            }
+            assert!(!field_is_unlogged(field(b, 0)));
+
+            // a -> e <-> f. The cycle is not reclaimed by RC pauses after a no longer refers to e.
+            let e = alloc_object(mutator);
+            let f = alloc_object(mutator);
+            Slot::store(&field(e, 0), f);
+            Slot::store(&field(f, 0), e);
+            memory_manager::object_reference_write_pre(mutator, a, field(a, 0), Some(e));
+            Slot::store(&field(a, 0), e);
+            memory_manager::object_reference_write_post(mutator, a, field(a, 0), Some(e));
+            collect(mmtk);
+            assert_eq!(rc::count(e), 2);
+            assert_eq!(rc::count(f), 1);
+            memory_manager::object_reference_write_pre(mutator, a, field(a, 0), None);
+            unsafe { field(a, 0).store(Address::ZERO) };
+            memory_manager::object_reference_write_post(mutator, a, field(a, 0), None);
+            collect(mmtk);
+            assert!(!plan.last_collection_was_exhaustive());
+            assert_eq!(rc::count(e), 1);
+            for object in [a, c, e, f] {
+                assert!(object.is_live());
+            }
+
+            // A backup trace computes the counts from scratch, and reclaims the cycle.
+            collect_by_user(mmtk);
+            assert!(plan.last_collection_was_exhaustive());
+            for object in [a, c] {
+                assert!(object.is_live());
+                assert_eq!(rc::count(object), 1);
+            }
+            for object in [e, f] {
+                assert!(!object.is_live());
+            }
+
+            // RC pauses resume after a backup trace.
+            let g = alloc_object(mutator);
+            Slot::store(&root_slot(1), g);
+            collect(mmtk);
+            assert!(!plan.last_collection_was_exhaustive());
+            for object in [a, c, g] {
+                assert!(object.is_live());
+                assert_eq!(rc::count(object), 1);
+            }
+        },
+        no_cleanup,
+    )
+}
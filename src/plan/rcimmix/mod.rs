@@ -0,0 +1,13 @@
+//! Plan: RC Immix (deferred reference counting over Immix, with backup tracing)
+
+pub(in crate::plan) mod barrier;
+pub(in crate::plan) mod gc_work;
+pub(in crate::plan) mod global;
+pub(in crate::plan) mod mutator;
+mod rc;
+
+pub use global::RCImmix;
+pub use global::RCIMMIX_CONSTRAINTS;
+
+#[cfg(all(test, feature = "mock_test_side_mark_bit"))]
+mod mock_test_rc_immix;
@@ -0,0 +1,45 @@
+use super::barrier::RCImmixBarrierSemantics;
+use super::global::RCImmix;
+use crate::plan::barriers::FieldLoggingBarrier;
+use crate::plan::immix;
+use crate::plan::mutator_context::{create_space_mapping, unreachable_prepare_func, MutatorConfig};
+use crate::util::alloc::allocators::Allocators;
+use crate::util::alloc::AllocatorSelector;
+use crate::util::opaque_pointer::VMWorkerThread;
+use crate::util::VMMutatorThread;
+use crate::vm::VMBinding;
+use crate::{Mutator, MMTK};
+
+pub fn rcimmix_mutator_release<VM: VMBinding>(mutator: &mut Mutator<VM>, tls: VMWorkerThread) {
+    immix::mutator::immix_mutator_release(mutator, tls)
+}
+
+pub use immix::mutator::ALLOCATOR_MAPPING;
+
+pub fn create_rcimmix_mutator<VM: VMBinding>(
+    mutator_tls: VMMutatorThread,
+    mmtk: &'static MMTK<VM>,
+) -> Mutator<VM> {
+    let rcimmix = mmtk.get_plan().downcast_ref::<RCImmix<VM>>().unwrap();
+    let config = MutatorConfig {
+        allocator_mapping: &ALLOCATOR_MAPPING,
+        space_mapping: Box::new({
+            let mut vec =
+                create_space_mapping(immix::mutator::RESERVED_ALLOCATORS, true, mmtk.get_plan());
+            vec.push((AllocatorSelector::Immix(0), rcimmix.immix_space()));
+            vec
+        }),
+        prepare_func: &unreachable_prepare_func,
+        release_func: &rcimmix_mutator_release,
+    };
+
+    Mutator {
+        allocators: Allocators::<VM>::new(mutator_tls, mmtk, &config.space_mapping),
+        barrier: Box::new(FieldLoggingBarrier::new(RCImmixBarrierSemantics::new(
+            mmtk, rcimmix,
+        ))),
+        mutator_tls,
+        config,
+        plan: mmtk.get_plan(),
+    }
+}
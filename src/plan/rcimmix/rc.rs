@@ -0,0 +1,78 @@
+//! Reference counts of the objects in the Immix space of RCImmix.
+//!
+//! Each object has a small saturating count in the side metadata, indexed by the address of its
+//! object reference.  Once a count reaches [`MAX_COUNT`], it is stuck, and is neither incremented
+//! nor decremented.  Objects with stuck counts are only reclaimed by backup traces, which compute
+//! the counts from scratch.
+
+use crate::util::constants::{BITS_IN_WORD, MIN_OBJECT_SIZE};
+use crate::util::metadata::side_metadata::SideMetadataSpec;
+use crate::util::{Address, ObjectReference};
+use std::sync::atomic::Ordering;
+
+/// The side metadata spec for reference counts.
+pub(super) const RC_SPEC: SideMetadataSpec =
+    crate::util::metadata::side_metadata::spec_defs::RC_COUNT;
+
+/// The largest count.  A count is stuck once it reaches this value.
+pub(super) const MAX_COUNT: u8 = (1 << (1 << RC_SPEC.log_num_of_bits)) - 1;
+
+/// Get the count of an object.
+pub(super) fn count(object: ObjectReference) -> u8 {
+    RC_SPEC.load_atomic::<u8>(object.to_raw_address(), Ordering::SeqCst)
+}
+
+/// Increment the count of an object, and return the count before the increment.
+pub(super) fn increment(object: ObjectReference) -> u8 {
+    let (Ok(old) | Err(old)) = RC_SPEC.fetch_update_atomic::<u8, _>(
+        object.to_raw_address(),
+        Ordering::SeqCst,
+        Ordering::SeqCst,
+        |c| (c < MAX_COUNT).then_some(c + 1),
+    );
+    old
+}
+
+/// Decrement the count of an object, and return the count before the decrement.  A count of zero
+/// is left unchanged.
+pub(super) fn decrement(object: ObjectReference) -> u8 {
+    let (Ok(old) | Err(old)) = RC_SPEC.fetch_update_atomic::<u8, _>(
+        object.to_raw_address(),
+        Ordering::SeqCst,
+        Ordering::SeqCst,
+        |c| (c != 0 && c < MAX_COUNT).then(|| c - 1),
+    );
+    old
+}
+
+/// Call `f` for each object with a non-zero count whose object reference is in the address range
+/// `[start, end)`.  The range must be aligned to the bytes covered by a word of the count table.
+///
+/// This loads the count table without synchronization, so the counts must not be modified
+/// concurrently.
+pub(super) fn for_each_counted_object(
+    start: Address,
+    end: Address,
+    mut f: impl FnMut(ObjectReference),
+) {
+    // The number of bytes whose counts are held in a word of the count table.
+    const BYTES_PER_WORD: usize =
+        (BITS_IN_WORD >> RC_SPEC.log_num_of_bits) << RC_SPEC.log_bytes_in_region;
+    debug_assert!(start.is_aligned_to(BYTES_PER_WORD));
+    let mut cursor = start;
+    while cursor < end {
+        // Skip the whole word if no object in it has a count.
+        if unsafe { RC_SPEC.load_raw_word(cursor) } != 0 {
+            let mut addr = cursor;
+            while addr < cursor + BYTES_PER_WORD {
+                // Only object references are counted, and they are all aligned.
+                let object = unsafe { ObjectReference::from_raw_address_unchecked(addr) };
+                if count(object) != 0 {
+                    f(object);
+                }
+                addr += MIN_OBJECT_SIZE;
+            }
+        }
+        cursor += BYTES_PER_WORD;
+    }
+}
@@ -0,0 +1,66 @@
+//! Write barrier semantics for the Regional plan.
+
+use super::global::Regional;
+use crate::plan::barriers::BarrierSemantics;
+use crate::plan::generational::barrier::GenObjectBarrierSemantics;
+use crate::plan::generational::global::GenerationalPlan;
+use crate::util::*;
+use crate::vm::slot::MemorySlice;
+use crate::vm::VMBinding;
+use crate::MMTK;
+
+/// The slow-path semantics of the object remembering barrier for the Regional plan. Like the
+/// generational barrier, it remembers the modified objects so they can be scanned in young GCs. It
+/// also hands the modified objects to the region space, so their references are recorded in the
+/// remembered sets at the end of the next GC.
+pub struct RegionalBarrierSemantics<VM: VMBinding> {
+    /// Regional plan
+    plan: &'static Regional<VM>,
+    /// Generational barrier semantics that maintains the modbuf
+    gen: GenObjectBarrierSemantics<VM, Regional<VM>>,
+}
+
+impl<VM: VMBinding> RegionalBarrierSemantics<VM> {
+    pub fn new(mmtk: &'static MMTK<VM>, plan: &'static Regional<VM>) -> Self {
+        Self {
+            plan,
+            gen: GenObjectBarrierSemantics::new(mmtk, plan),
+        }
+    }
+}
+
+impl<VM: VMBinding> BarrierSemantics for RegionalBarrierSemantics<VM> {
+    type VM = VM;
+
+    fn flush(&mut self) {
+        self.gen.flush();
+    }
+
+    fn object_reference_write_slow(
+        &mut self,
+        src: ObjectReference,
+        slot: VM::VMSlot,
+        target: Option<ObjectReference>,
+    ) {
+        self.plan.region_space().add_to_refinement_queue(src);
+        self.gen.object_reference_write_slow(src, slot, target);
+    }
+
+    fn memory_region_copy_slow(&mut self, src: VM::VMMemorySlice, dst: VM::VMMemorySlice) {
+        match dst.object() {
+            Some(object) => self.plan.region_space().add_to_refinement_queue(object),
+            // Slices in young regions are scanned with the objects that contain them.
+            None if !self.plan.is_address_in_nursery(dst.start()) => self
+                .plan
+                .region_space()
+                .add_slice_to_refinement_queue(dst.clone()),
+            None => {}
+        }
+        self.gen.memory_region_copy_slow(src, dst);
+    }
+
+    fn object_probable_write_slow(&mut self, obj: ObjectReference) {
+        self.plan.region_space().add_to_refinement_queue(obj);
+        self.gen.object_probable_write_slow(obj);
+    }
+}
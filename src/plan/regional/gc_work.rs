@@ -0,0 +1,25 @@
+use crate::plan::generational::gc_work::GenNurseryProcessEdges;
+use crate::policy::gc_work::DEFAULT_TRACE;
+use crate::scheduler::gc_work::PlanProcessEdges;
+use crate::scheduler::gc_work::UnsupportedProcessEdges;
+use crate::vm::VMBinding;
+
+use super::global::Regional;
+
+pub struct RegionalYoungGCWorkContext<VM: VMBinding>(std::marker::PhantomData<VM>);
+
+impl<VM: VMBinding> crate::scheduler::GCWorkContext for RegionalYoungGCWorkContext<VM> {
+    type VM = VM;
+    type PlanType = Regional<VM>;
+    type DefaultProcessEdges = GenNurseryProcessEdges<VM, Self::PlanType, DEFAULT_TRACE>;
+    type PinningProcessEdges = UnsupportedProcessEdges<VM>;
+}
+
+pub struct RegionalFullGCWorkContext<VM: VMBinding>(std::marker::PhantomData<VM>);
+
+impl<VM: VMBinding> crate::scheduler::GCWorkContext for RegionalFullGCWorkContext<VM> {
+    type VM = VM;
+    type PlanType = Regional<VM>;
+    type DefaultProcessEdges = PlanProcessEdges<VM, Self::PlanType, DEFAULT_TRACE>;
+    type PinningProcessEdges = UnsupportedProcessEdges<VM>;
+}
@@ -0,0 +1,413 @@
+use super::gc_work::{RegionalFullGCWorkContext, RegionalYoungGCWorkContext};
+use super::mutator::ALLOCATOR_MAPPING;
+use crate::plan::generational::gc_work::GenNurseryProcessEdges;
+use crate::plan::generational::global::{CommonGenPlan, GenerationalPlan, GenerationalPlanExt};
+use crate::plan::global::BasePlan;
+use crate::plan::global::CommonPlan;
+use crate::plan::global::CreateGeneralPlanArgs;
+use crate::plan::global::CreateSpecificPlanArgs;
+use crate::plan::AllocationSemantics;
+use crate::plan::Plan;
+use crate::plan::PlanConstraints;
+use crate::policy::gc_work::{TraceKind, DEFAULT_TRACE};
+use crate::policy::region::REMSET_ENTRY_COST_BYTES;
+use crate::policy::region::{HeapRegion, RegionSpace, RegionState, MAX_REGION_OBJECT_SIZE};
+use crate::policy::sft::SFT;
+use crate::policy::space::Space;
+use crate::scheduler::GCWorkScheduler;
+use crate::scheduler::GCWorker;
+use crate::util::alloc::allocators::AllocatorSelector;
+use crate::util::constants::LOG_BYTES_IN_PAGE;
+use crate::util::copy::*;
+use crate::util::heap::gc_trigger::SpaceStats;
+use crate::util::heap::VMRequest;
+use crate::util::linear_scan::Region;
+use crate::util::object_enum::ClosureObjectEnumerator;
+use crate::util::statistics::counter::EventCounter;
+use crate::util::Address;
+use crate::util::ObjectReference;
+use crate::util::VMWorkerThread;
+use crate::vm::VMBinding;
+use crate::ObjectQueue;
+
+use atomic::Ordering;
+use enum_map::EnumMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+use mmtk_macros::{HasSpaces, PlanTraceObject};
+
+/// A G1-style region-based collector. The heap is divided into regions. Mutators allocate into
+/// young regions, and every GC evacuates a collection set of regions into fresh old regions.
+///
+/// A young GC evacuates all the young regions, plus as many old regions with the most garbage as
+/// the pause time goal ([`crate::util::options::Options::pause_time_goal`]) allows. The pause time
+/// is predicted from the copying throughput and the survival rate of previous young GCs. Old
+/// regions have remembered sets, so a young GC does not need to trace the old regions that are
+/// not evacuated. A full heap GC evacuates the young regions, marks the old regions in place,
+/// releases old regions without live objects, and picks the old regions with the most garbage as
+/// the candidates for later young GCs.
+#[derive(HasSpaces, PlanTraceObject)]
+pub struct Regional<VM: VMBinding> {
+    #[parent]
+    common: CommonPlan<VM>,
+    #[space]
+    #[copy_semantics(CopySemantics::Mature)]
+    region: RegionSpace<VM>,
+    gc_full_heap: AtomicBool,
+    next_gc_full_heap: AtomicBool,
+    full_heap_gc_count: Arc<Mutex<EventCounter>>,
+    /// Bytes in young regions at the start of the current GC
+    young_bytes: usize,
+    /// Predicts the pause time of young GCs
+    pause_predictor: PausePredictor,
+}
+
+/// The plan constraints for the regional plan.
+pub const REGIONAL_CONSTRAINTS: PlanConstraints = PlanConstraints {
+    moves_objects: true,
+    max_non_los_default_alloc_bytes: MAX_REGION_OBJECT_SIZE,
+    needs_log_bit: true,
+    barrier: crate::plan::BarrierSelector::ObjectBarrier,
+    // We may trace duplicate edges with an object remembering barrier. See https://github.com/mmtk/mmtk-core/issues/743.
+    may_trace_duplicate_edges: true,
+    needs_prepare_mutator: false,
+    ..PlanConstraints::default()
+};
+
+impl<VM: VMBinding> Plan for Regional<VM> {
+    fn constraints(&self) -> &'static PlanConstraints {
+        &REGIONAL_CONSTRAINTS
+    }
+
+    fn create_copy_config(&'static self) -> CopyConfig<Self::VM> {
+        use enum_map::enum_map;
+        CopyConfig {
+            copy_mapping: enum_map! {
+                CopySemantics::Mature => CopySelector::Region(0),
+                CopySemantics::PromoteToMature => CopySelector::Region(0),
+                _ => CopySelector::Unused,
+            },
+            space_mapping: vec![(CopySelector::Region(0), &self.region)],
+            constraints: &REGIONAL_CONSTRAINTS,
+        }
+    }
+
+    fn base(&self) -> &BasePlan<VM> {
+        &self.common.base
+    }
+
+    fn base_mut(&mut self) -> &mut BasePlan<Self::VM> {
+        &mut self.common.base
+    }
+
+    fn common(&self) -> &CommonPlan<VM> {
+        &self.common
+    }
+
+    fn generational(&self) -> Option<&dyn GenerationalPlan<VM = VM>> {
+        Some(self)
+    }
+
+    fn schedule_collection(&'static self, scheduler: &GCWorkScheduler<VM>) {
+        let is_full_heap = self.requires_full_heap_collection();
+        self.gc_full_heap.store(is_full_heap, Ordering::SeqCst);
+        probe!(mmtk, gen_full_heap, is_full_heap);
+
+        if !is_full_heap {
+            info!("Young GC");
+            scheduler.schedule_common_work::<RegionalYoungGCWorkContext<VM>>(self);
+        } else {
+            info!("Full heap GC");
+            scheduler.schedule_common_work::<RegionalFullGCWorkContext<VM>>(self);
+        }
+    }
+
+    fn get_allocator_mapping(&self) -> &'static EnumMap<AllocationSemantics, AllocatorSelector> {
+        &ALLOCATOR_MAPPING
+    }
+
+    fn prepare(&mut self, tls: VMWorkerThread) {
+        let full_heap = !self.is_current_gc_nursery();
+        self.young_bytes = self.region.young_pages() << LOG_BYTES_IN_PAGE;
+        self.remember_new_large_objects();
+        if full_heap {
+            self.full_heap_gc_count.lock().unwrap().inc();
+            self.common.prepare(tls, true);
+            self.region.prepare(true, 0);
+        } else {
+            self.common.prepare(tls, false);
+            let pause_time_goal = *self.common.base.options.pause_time_goal;
+            let budget = usize::min(
+                self.pause_predictor
+                    .old_region_budget(pause_time_goal as f64, self.young_bytes),
+                self.get_available_pages() << LOG_BYTES_IN_PAGE,
+            );
+            self.region.prepare(false, budget);
+            self.region
+                .scan_remembered_sets::<GenNurseryProcessEdges<VM, Self, DEFAULT_TRACE>>();
+        }
+    }
+
+    fn release(&mut self, tls: VMWorkerThread) {
+        let full_heap = !self.is_current_gc_nursery();
+        // The region space needs to know which objects in other spaces survived, so it is
+        // released before other spaces release dead objects.
+        let los = &self.common.los;
+        self.region.release(|object| {
+            if los.in_space(object) {
+                // New large objects that are not traced are not released yet in a young GC.
+                los.is_live(object) && (full_heap || !los.is_in_nursery(object))
+            } else {
+                object.is_live()
+            }
+        });
+        self.common.release(tls, full_heap);
+    }
+
+    fn end_of_gc(&mut self, _tls: VMWorkerThread) {
+        if self.is_current_gc_nursery() {
+            let pause_time = self
+                .common
+                .base
+                .global_state
+                .gc_start_time
+                .borrow()
+                .map(|start| start.elapsed());
+            if let Some(pause_time) = pause_time {
+                let (copied_bytes, young_copied_bytes) = self.region.copied_bytes();
+                self.pause_predictor.update(
+                    copied_bytes + self.region.remset_entries_scanned() * REMSET_ENTRY_COST_BYTES,
+                    pause_time.as_secs_f64() * 1000.0,
+                    self.young_bytes,
+                    young_copied_bytes,
+                );
+            }
+        }
+        let next_gc_full_heap = CommonGenPlan::should_next_gc_be_full_heap(self);
+        self.next_gc_full_heap
+            .store(next_gc_full_heap, Ordering::Relaxed);
+    }
+
+    fn collection_required(&self, space_full: bool, space: Option<SpaceStats<Self::VM>>) -> bool {
+        let young_full = self.region.young_pages() > self.base().gc_trigger.get_max_nursery_pages();
+        if space_full && space.is_some() && space.as_ref().unwrap().0.name() != self.region.name() {
+            self.next_gc_full_heap.store(true, Ordering::SeqCst);
+        }
+        self.base().collection_required(self, space_full) || young_full
+    }
+
+    fn current_gc_may_move_object(&self) -> bool {
+        true
+    }
+
+    fn last_collection_was_exhaustive(&self) -> bool {
+        self.gc_full_heap.load(Ordering::Relaxed)
+    }
+
+    fn get_collection_reserved_pages(&self) -> usize {
+        self.region.young_pages()
+    }
+
+    fn get_used_pages(&self) -> usize {
+        self.common.get_used_pages() + self.region.reserved_pages()
+    }
+}
+
+impl<VM: VMBinding> GenerationalPlan for Regional<VM> {
+    fn is_current_gc_nursery(&self) -> bool {
+        !self.gc_full_heap.load(Ordering::SeqCst)
+    }
+
+    fn is_object_in_nursery(&self, object: ObjectReference) -> bool {
+        self.region.in_space(object)
+            && HeapRegion::containing(object).get_state() == RegionState::Young
+    }
+
+    fn is_address_in_nursery(&self, addr: Address) -> bool {
+        self.region.address_in_space(addr)
+            && HeapRegion::from_unaligned_address(addr).get_state() == RegionState::Young
+    }
+
+    fn get_mature_physical_pages_available(&self) -> usize {
+        self.region.available_physical_pages()
+    }
+
+    fn get_mature_reserved_pages(&self) -> usize {
+        self.region.reserved_pages() - self.region.young_pages()
+    }
+
+    fn force_full_heap_collection(&self) {
+        self.next_gc_full_heap.store(true, Ordering::SeqCst);
+    }
+
+    fn last_collection_full_heap(&self) -> bool {
+        self.gc_full_heap.load(Ordering::SeqCst)
+    }
+}
+
+impl<VM: VMBinding> GenerationalPlanExt<VM> for Regional<VM> {
+    fn trace_object_nursery<Q: ObjectQueue, const KIND: TraceKind>(
+        &self,
+        queue: &mut Q,
+        object: ObjectReference,
+        worker: &mut GCWorker<VM>,
+    ) -> ObjectReference {
+        if self.region.in_space(object) {
+            // Only objects in the collection set are traced. They are evacuated.
+            return self.region.trace_object::<Q>(
+                queue,
+                object,
+                Some(CopySemantics::PromoteToMature),
+                worker,
+            );
+        }
+
+        if self.common.get_los().in_space(object) {
+            return self.common.get_los().trace_object::<Q>(queue, object);
+        }
+
+        object
+    }
+}
+
+impl<VM: VMBinding> Regional<VM> {
+    pub fn new(args: CreateGeneralPlanArgs<VM>) -> Self {
+        let full_heap_gc_count = args.stats.new_event_counter("majorGC", true, true);
+        let mut plan_args = CreateSpecificPlanArgs {
+            global_args: args,
+            constraints: &REGIONAL_CONSTRAINTS,
+            global_side_metadata_specs:
+                crate::plan::generational::new_generational_global_metadata_specs::<VM>(),
+        };
+
+        let res = Regional {
+            region: RegionSpace::new(plan_args.get_space_args(
+                "region",
+                true,
+                false,
+                VMRequest::discontiguous(),
+            )),
+            common: CommonPlan::new(plan_args),
+            gc_full_heap: AtomicBool::new(false),
+            next_gc_full_heap: AtomicBool::new(false),
+            full_heap_gc_count,
+            young_bytes: 0,
+            pause_predictor: PausePredictor::default(),
+        };
+
+        res.verify_side_metadata_sanity();
+
+        res
+    }
+
+    fn requires_full_heap_collection(&self) -> bool {
+        // Separate each condition so the code is clear
+        #[allow(clippy::if_same_then_else, clippy::needless_bool)]
+        if crate::plan::generational::FULL_NURSERY_GC {
+            trace!("full heap: forced full heap");
+            // For barrier overhead measurements, we always do full gc in nursery collections.
+            true
+        } else if self
+            .common
+            .base
+            .global_state
+            .user_triggered_collection
+            .load(Ordering::SeqCst)
+            && *self.common.base.options.full_heap_system_gc
+        {
+            // User triggered collection, and we force full heap for user triggered collection
+            true
+        } else if self.next_gc_full_heap.load(Ordering::SeqCst)
+            || self
+                .common
+                .base
+                .global_state
+                .cur_collection_attempts
+                .load(Ordering::SeqCst)
+                > 1
+        {
+            // Forces full heap collection
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Large objects allocated since the last GC are not in any remembered set. Hand them to the
+    /// region space, so their references are recorded if they survive the current GC.
+    fn remember_new_large_objects(&self) {
+        let los = self.common.get_los();
+        let mut enumerator = ClosureObjectEnumerator::<_, VM>::new(|object| {
+            if los.is_in_nursery(object) {
+                self.region.add_to_refinement_queue(object);
+            }
+        });
+        los.enumerate_objects(&mut enumerator);
+    }
+
+    pub fn region_space(&self) -> &RegionSpace<VM> {
+        &self.region
+    }
+}
+
+/// Predicts the pause time of young GCs from the previous young GCs, and decides how many old
+/// regions a young GC can evacuate within the pause time goal.
+#[derive(Default)]
+struct PausePredictor {
+    /// Evacuation work done per millisecond, in bytes. This is `None` before the first young GC.
+    bytes_per_ms: Option<f64>,
+    /// The fraction of the bytes in young regions that survive a young GC
+    survival_rate: f64,
+}
+
+impl PausePredictor {
+    /// The weight of the latest young GC in the decaying averages
+    const WEIGHT: f64 = 0.3;
+
+    /// Update the predictor with the work done in a young GC and its pause time.
+    fn update(
+        &mut self,
+        work_bytes: usize,
+        pause_ms: f64,
+        young_bytes: usize,
+        young_copied: usize,
+    ) {
+        if pause_ms <= 0.0 || work_bytes == 0 {
+            return;
+        }
+        let bytes_per_ms = work_bytes as f64 / pause_ms;
+        let survival_rate = if young_bytes == 0 {
+            self.survival_rate
+        } else {
+            f64::min(young_copied as f64 / young_bytes as f64, 1.0)
+        };
+        match self.bytes_per_ms {
+            None => {
+                self.bytes_per_ms = Some(bytes_per_ms);
+                self.survival_rate = survival_rate;
+            }
+            Some(prev) => {
+                self.bytes_per_ms = Some(prev + (bytes_per_ms - prev) * Self::WEIGHT);
+                self.survival_rate += (survival_rate - self.survival_rate) * Self::WEIGHT;
+            }
+        }
+        trace!(
+            "Pause predictor: {:.1} bytes/ms, survival rate {:.3}",
+            self.bytes_per_ms.unwrap(),
+            self.survival_rate
+        );
+    }
+
+    /// The budget for evacuating old regions in a young GC, measured in bytes of evacuation work.
+    /// Without any previous young GC, we allow evacuating about one region.
+    fn old_region_budget(&self, pause_time_goal_ms: f64, young_bytes: usize) -> usize {
+        match self.bytes_per_ms {
+            None => HeapRegion::BYTES,
+            Some(bytes_per_ms) => {
+                let young_work = young_bytes as f64 * self.survival_rate;
+                f64::max(pause_time_goal_ms * bytes_per_ms - young_work, 0.0) as usize
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+// GITHUB-CI: MMTK_PLAN=Regional
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::gc_work::{RegionalFullGCWorkContext, RegionalYoungGCWorkContext};
+use super::Regional;
+use crate::memory_manager;
+use crate::plan::generational::global::GenerationalPlan;
+use crate::policy::space::Space;
+use crate::util::constants::BYTES_IN_ADDRESS;
+use crate::util::options::PlanSelector;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::Address;
+use crate::vm::slot::Slot;
+
+fn setup() -> MockVM {
+    mock_roots::setup::<RegionalYoungGCWorkContext<MockVM>, RegionalFullGCWorkContext<MockVM>>()
+}
+
+#[test]
+pub fn regional() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::Regional, |_| {});
+            let plan = mmtk.get_plan().downcast_ref::<Regional<MockVM>>().unwrap();
+
+            // root0 -> a -> b, and d is unreachable.
+            let a = alloc_object(mutator);
+            let b = alloc_object(mutator);
+            let _d = alloc_object(mutator);
+            Slot::store(&field(a, 0), b);
+            Slot::store(&root_slot(0), a);
+            for object in [a, b] {
+                assert!(plan.region_space().in_space(object));
+                assert!(plan.is_object_in_nursery(object));
+            }
+
+            // A young GC evacuates the young regions. Survivors are copied into old regions.
+            collect(mmtk);
+            assert!(!plan.last_collection_full_heap());
+            let a = root(0);
+            let b = Slot::load(&field(a, 0)).unwrap();
+            for object in [a, b] {
+                assert!(plan.region_space().in_space(object));
+                assert!(!plan.is_object_in_nursery(object));
+            }
+
+            // c is only reachable from an old object, and is remembered by the barrier.
+            let c = alloc_object(mutator);
+            memory_manager::object_reference_write_pre(mutator, a, field(a, 1), Some(c));
+            Slot::store(&field(a, 1), c);
+            memory_manager::object_reference_write_post(mutator, a, field(a, 1), Some(c));
+            collect(mmtk);
+            assert!(!plan.last_collection_full_heap());
+            let a = root(0);
+            let c = Slot::load(&field(a, 1)).unwrap();
+            assert!(plan.region_space().in_space(c));
+            assert!(!plan.is_object_in_nursery(c));
+            assert_eq!(Slot::load(&field(a, 0)), Some(b));
+
+            // g is only reachable from an old object through a copied slice, which does not name
+            // the object that contains it.
+            let g = alloc_object(mutator);
+            let h = alloc_object(mutator);
+            Slot::store(&field(h, 0), g);
+            let src = field(h, 0)..field(h, 1);
+            let dst = field(b, 1)..field(b, 1) + BYTES_IN_ADDRESS;
+            memory_manager::memory_region_copy_pre(the_mutator(), src.clone(), dst.clone());
+            Slot::store(&field(b, 1), g);
+            memory_manager::memory_region_copy_post(the_mutator(), src, dst);
+            collect(mmtk);
+            assert!(!plan.last_collection_full_heap());
+            let g = Slot::load(&field(b, 1)).unwrap();
+            assert!(plan.region_space().in_space(g));
+            assert!(!plan.is_object_in_nursery(g));
+            assert!(g.is_live());
+
+            // A full heap GC evacuates young objects, and marks old objects in place. The mock VM
+            // keeps the log bit and the forwarding bits in the same header bits, so this test does
+            // not run a young GC after a full heap GC, which may evacuate old regions.
+            unsafe { field(a, 0).store(Address::ZERO) };
+            let e = alloc_object(mutator);
+            assert!(plan.is_object_in_nursery(e));
+            Slot::store(&root_slot(1), e);
+            plan.force_full_heap_collection();
+            collect(mmtk);
+            assert!(plan.last_collection_full_heap());
+            assert_eq!(root(0), a);
+            assert_eq!(Slot::load(&field(a, 1)), Some(c));
+            for object in [a, c] {
+                assert!(object.is_live());
+            }
+            let e = root(1);
+            assert!(plan.region_space().in_space(e));
+            assert!(!plan.is_object_in_nursery(e));
+        },
+        no_cleanup,
+    )
+}
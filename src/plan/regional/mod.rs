@@ -0,0 +1,12 @@
+//! Plan: regional (a G1-style region-based collector)
+
+pub(in crate::plan) mod barrier;
+pub(in crate::plan) mod gc_work;
+pub(in crate::plan) mod global;
+pub(in crate::plan) mod mutator;
+
+pub use global::Regional;
+pub use global::REGIONAL_CONSTRAINTS;
+
+#[cfg(all(test, feature = "mock_test_side_mark_bit"))]
+mod mock_test_regional;
@@ -0,0 +1,66 @@
+use super::barrier::RegionalBarrierSemantics;
+use super::Regional;
+use crate::plan::barriers::ObjectBarrier;
+use crate::plan::mutator_context::create_allocator_mapping;
+use crate::plan::mutator_context::unreachable_prepare_func;
+use crate::plan::mutator_context::{create_space_mapping, MutatorConfig, ReservedAllocators};
+use crate::plan::AllocationSemantics;
+use crate::util::alloc::allocators::{AllocatorSelector, Allocators};
+use crate::util::alloc::RegionAllocator;
+use crate::util::{VMMutatorThread, VMWorkerThread};
+use crate::vm::VMBinding;
+use crate::{Mutator, MMTK};
+
+use enum_map::EnumMap;
+
+pub fn regional_mutator_release<VM: VMBinding>(mutator: &mut Mutator<VM>, _tls: VMWorkerThread) {
+    // The young region of the allocator has been evacuated and released.
+    let region_allocator = unsafe {
+        mutator
+            .allocators
+            .get_allocator_mut(mutator.config.allocator_mapping[AllocationSemantics::Default])
+    }
+    .downcast_mut::<RegionAllocator<VM>>()
+    .unwrap();
+    region_allocator.reset();
+}
+
+const RESERVED_ALLOCATORS: ReservedAllocators = ReservedAllocators {
+    n_region: 1,
+    ..ReservedAllocators::DEFAULT
+};
+
+lazy_static! {
+    pub static ref ALLOCATOR_MAPPING: EnumMap<AllocationSemantics, AllocatorSelector> = {
+        let mut map = create_allocator_mapping(RESERVED_ALLOCATORS, true);
+        map[AllocationSemantics::Default] = AllocatorSelector::Region(0);
+        map
+    };
+}
+
+pub fn create_regional_mutator<VM: VMBinding>(
+    mutator_tls: VMMutatorThread,
+    mmtk: &'static MMTK<VM>,
+) -> Mutator<VM> {
+    let regional = mmtk.get_plan().downcast_ref::<Regional<VM>>().unwrap();
+    let config = MutatorConfig {
+        allocator_mapping: &ALLOCATOR_MAPPING,
+        space_mapping: Box::new({
+            let mut vec = create_space_mapping(RESERVED_ALLOCATORS, true, mmtk.get_plan());
+            vec.push((AllocatorSelector::Region(0), regional.region_space()));
+            vec
+        }),
+        prepare_func: &unreachable_prepare_func,
+        release_func: &regional_mutator_release,
+    };
+
+    Mutator {
+        allocators: Allocators::<VM>::new(mutator_tls, mmtk, &config.space_mapping),
+        barrier: Box::new(ObjectBarrier::new(RegionalBarrierSemantics::new(
+            mmtk, regional,
+        ))),
+        mutator_tls,
+        config,
+        plan: regional,
+    }
+}
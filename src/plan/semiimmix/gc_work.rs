@@ -0,0 +1,19 @@
+use super::global::SemiImmix;
+use crate::policy::gc_work::TraceKind;
+use crate::scheduler::gc_work::{PlanProcessEdges, UnsupportedProcessEdges};
+use crate::vm::VMBinding;
+
+/// The work context of SemiImmix.  Copying GCs always use `TRACE_KIND_FAST`.  GCs after the switch
+/// to Immix use `TRACE_KIND_FAST` or `TRACE_KIND_DEFRAG` like Immix.
+pub struct SemiImmixGCWorkContext<VM: VMBinding, const KIND: TraceKind>(
+    std::marker::PhantomData<VM>,
+);
+impl<VM: VMBinding, const KIND: TraceKind> crate::scheduler::GCWorkContext
+    for SemiImmixGCWorkContext<VM, KIND>
+{
+    type VM = VM;
+    type PlanType = SemiImmix<VM>;
+    type DefaultProcessEdges = PlanProcessEdges<VM, SemiImmix<VM>, KIND>;
+    // Objects in the copy spaces cannot be pinned.
+    type PinningProcessEdges = UnsupportedProcessEdges<VM>;
+}
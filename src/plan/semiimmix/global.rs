@@ -0,0 +1,369 @@
+use super::gc_work::SemiImmixGCWorkContext;
+use super::mutator::{ALLOCATOR_MAPPING, IMMIX_ALLOCATOR_MAPPING};
+use crate::plan::global::BasePlan;
+use crate::plan::global::CommonPlan;
+use crate::plan::global::CreateGeneralPlanArgs;
+use crate::plan::global::CreateSpecificPlanArgs;
+use crate::plan::immix::Immix;
+use crate::plan::AllocationSemantics;
+use crate::plan::ObjectQueue;
+use crate::plan::Plan;
+use crate::plan::PlanConstraints;
+use crate::plan::PlanTraceObject;
+use crate::policy::copyspace::CopySpace;
+use crate::policy::gc_work::{PolicyTraceObject, TraceKind};
+use crate::policy::immix::{ImmixSpace, ImmixSpaceArgs, TRACE_KIND_DEFRAG, TRACE_KIND_FAST};
+use crate::policy::space::Space;
+use crate::scheduler::*;
+use crate::util::alloc::allocators::AllocatorSelector;
+use crate::util::conversions;
+use crate::util::copy::*;
+use crate::util::heap::gc_trigger::SpaceStats;
+use crate::util::heap::VMRequest;
+use crate::util::metadata::side_metadata::SideMetadataContext;
+use crate::util::opaque_pointer::VMWorkerThread;
+use crate::util::ObjectReference;
+use crate::vm::VMBinding;
+use atomic::Atomic;
+use bytemuck::NoUninit;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use mmtk_macros::HasSpaces;
+
+use enum_map::EnumMap;
+
+/// SemiImmix copies live objects between two semispaces like SemiSpace while the live data is
+/// small, and switches to Immix once the live data grows past the `semi_immix_threshold` option,
+/// so that large heaps do not need the copy reserve of SemiSpace.
+///
+/// * In the copying mode, mutators allocate into the to-space with a bump pointer allocator, and
+///   each GC copies the live objects from the from-space to the to-space.
+/// * If the live bytes in the to-space exceed the threshold after a GC, the next GC copies the
+///   live objects from the from-space into the Immix space instead.
+/// * From then on, mutators allocate into the Immix space with an Immix allocator, and GCs are
+///   Immix GCs, with opportunistic defragmentation.  The plan never switches back.
+///
+/// The allocator that a mutator uses for [`AllocationSemantics::Default`] changes at the switch.
+/// A binding should not cache the allocator selector of a mutator across GCs.
+#[derive(HasSpaces)]
+pub struct SemiImmix<VM: VMBinding> {
+    pub hi: AtomicBool,
+    #[space]
+    pub copyspace0: CopySpace<VM>,
+    #[space]
+    pub copyspace1: CopySpace<VM>,
+    #[space]
+    pub immix_space: ImmixSpace<VM>,
+    #[parent]
+    pub common: CommonPlan<VM>,
+    mode: Atomic<SemiImmixMode>,
+    /// Whether the current GC copies objects out of the from-space.  This is decided when the GC is
+    /// scheduled.
+    copying_gc: AtomicBool,
+    last_gc_was_exhaustive: AtomicBool,
+}
+
+/// Where SemiImmix allocates objects, and where GCs move them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, NoUninit)]
+#[repr(u8)]
+enum SemiImmixMode {
+    /// Objects are allocated into the to-space, and copied to the other semispace by GCs.
+    Copying,
+    /// Objects are allocated into the to-space, and the next GC copies them into the Immix space.
+    Switching,
+    /// Objects are allocated into the Immix space, and the copy spaces are empty.
+    Immix,
+}
+
+/// The plan constraints for the SemiImmix plan.
+pub const SEMI_IMMIX_CONSTRAINTS: PlanConstraints = PlanConstraints {
+    moves_objects: true,
+    // Objects in the copy spaces are copied into the Immix space at the switch, so they cannot be
+    // larger than the max immix object size.
+    max_non_los_default_alloc_bytes: crate::util::rust_util::min_of_usize(
+        crate::policy::immix::MAX_IMMIX_OBJECT_SIZE,
+        crate::plan::plan_constraints::MAX_NON_LOS_ALLOC_BYTES_COPYING_PLAN,
+    ),
+    needs_prepare_mutator: false,
+    ..PlanConstraints::default()
+};
+
+impl<VM: VMBinding> Plan for SemiImmix<VM> {
+    fn constraints(&self) -> &'static PlanConstraints {
+        &SEMI_IMMIX_CONSTRAINTS
+    }
+
+    fn last_collection_was_exhaustive(&self) -> bool {
+        self.last_gc_was_exhaustive.load(Ordering::Relaxed)
+    }
+
+    fn create_copy_config(&'static self) -> CopyConfig<Self::VM> {
+        use enum_map::enum_map;
+        CopyConfig {
+            copy_mapping: enum_map! {
+                CopySemantics::DefaultCopy => CopySelector::CopySpace(0),
+                CopySemantics::Mature => CopySelector::Immix(0),
+                _ => CopySelector::Unused,
+            },
+            space_mapping: vec![
+                // The tospace argument doesn't matter, we will rebind before a GC anyway.
+                (CopySelector::CopySpace(0), &self.copyspace0),
+                (CopySelector::Immix(0), &self.immix_space),
+            ],
+            constraints: &SEMI_IMMIX_CONSTRAINTS,
+        }
+    }
+
+    fn schedule_collection(&'static self, scheduler: &GCWorkScheduler<VM>) {
+        let copying_gc = self.mode() != SemiImmixMode::Immix;
+        self.copying_gc.store(copying_gc, Ordering::SeqCst);
+        if copying_gc {
+            scheduler.schedule_common_work::<SemiImmixGCWorkContext<VM, TRACE_KIND_FAST>>(self);
+        } else {
+            Immix::schedule_immix_full_heap_collection::<
+                SemiImmix<VM>,
+                SemiImmixGCWorkContext<VM, TRACE_KIND_FAST>,
+                SemiImmixGCWorkContext<VM, TRACE_KIND_DEFRAG>,
+            >(self, &self.immix_space, scheduler)
+        }
+    }
+
+    fn get_allocator_mapping(&self) -> &'static EnumMap<AllocationSemantics, AllocatorSelector> {
+        if self.uses_immix() {
+            &IMMIX_ALLOCATOR_MAPPING
+        } else {
+            &ALLOCATOR_MAPPING
+        }
+    }
+
+    fn prepare(&mut self, tls: VMWorkerThread) {
+        self.common.prepare(tls, true);
+        if self.copying_gc.load(Ordering::SeqCst) {
+            // flip the semi-spaces
+            self.hi
+                .store(!self.hi.load(Ordering::SeqCst), Ordering::SeqCst);
+            let hi = self.hi.load(Ordering::SeqCst);
+            self.copyspace0.prepare(hi);
+            self.copyspace1.prepare(!hi);
+            let semantics = self.fromspace_copy_semantics();
+            self.fromspace_mut().set_copy_for_sft_trace(Some(semantics));
+            self.tospace_mut().set_copy_for_sft_trace(None);
+        }
+        self.immix_space.prepare(
+            true,
+            crate::policy::immix::defrag::StatsForDefrag::new(self),
+        );
+    }
+
+    fn prepare_worker(&self, worker: &mut GCWorker<VM>) {
+        unsafe { worker.get_copy_context_mut().copy[0].assume_init_mut() }.rebind(self.tospace());
+    }
+
+    fn release(&mut self, tls: VMWorkerThread) {
+        self.common.release(tls, true);
+        if self.copying_gc.load(Ordering::SeqCst) {
+            // release the collected region
+            self.fromspace().release();
+            let next_mode = if self.mode() == SemiImmixMode::Switching {
+                SemiImmixMode::Immix
+            } else {
+                let live_bytes = conversions::pages_to_bytes(self.tospace().reserved_pages());
+                if live_bytes > *self.base().options.semi_immix_threshold {
+                    info!(
+                        "SemiImmix: {} live bytes exceed the threshold. Switch to Immix in the next GC.",
+                        live_bytes
+                    );
+                    SemiImmixMode::Switching
+                } else {
+                    SemiImmixMode::Copying
+                }
+            };
+            self.mode.store(next_mode, Ordering::SeqCst);
+        }
+        self.immix_space.release(true);
+    }
+
+    fn end_of_gc(&mut self, _tls: VMWorkerThread) {
+        let did_defrag = self.immix_space.end_of_gc();
+        // A copying GC moves all the live objects, like a defrag GC.
+        self.last_gc_was_exhaustive.store(
+            self.copying_gc.load(Ordering::SeqCst)
+                || ImmixSpace::<VM>::is_last_gc_exhaustive(did_defrag),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn collection_required(&self, space_full: bool, _space: Option<SpaceStats<Self::VM>>) -> bool {
+        self.base().collection_required(self, space_full)
+    }
+
+    fn current_gc_may_move_object(&self) -> bool {
+        self.copying_gc.load(Ordering::SeqCst) || self.immix_space.in_defrag()
+    }
+
+    fn get_collection_reserved_pages(&self) -> usize {
+        if self.uses_immix() {
+            self.immix_space.defrag_headroom_pages()
+        } else {
+            self.tospace().reserved_pages()
+        }
+    }
+
+    fn get_used_pages(&self) -> usize {
+        self.tospace().reserved_pages()
+            + self.immix_space.reserved_pages()
+            + self.common.get_used_pages()
+    }
+
+    fn get_available_pages(&self) -> usize {
+        let available = self
+            .get_total_pages()
+            .saturating_sub(self.get_reserved_pages());
+        if self.uses_immix() {
+            available
+        } else {
+            // Half of the memory is reserved for copying.
+            available >> 1
+        }
+    }
+
+    fn base(&self) -> &BasePlan<VM> {
+        &self.common.base
+    }
+
+    fn base_mut(&mut self) -> &mut BasePlan<Self::VM> {
+        &mut self.common.base
+    }
+
+    fn common(&self) -> &CommonPlan<VM> {
+        &self.common
+    }
+}
+
+impl<VM: VMBinding> PlanTraceObject<VM> for SemiImmix<VM> {
+    fn trace_object<Q: ObjectQueue, const KIND: TraceKind>(
+        &self,
+        queue: &mut Q,
+        object: ObjectReference,
+        worker: &mut GCWorker<VM>,
+    ) -> ObjectReference {
+        for copyspace in [&self.copyspace0, &self.copyspace1] {
+            if copyspace.in_space(object) {
+                return copyspace.trace_object::<Q>(
+                    queue,
+                    object,
+                    Some(self.fromspace_copy_semantics()),
+                    worker,
+                );
+            }
+        }
+        if self.immix_space.in_space(object) {
+            return self.immix_space.trace_object::<Q, KIND>(
+                queue,
+                object,
+                Some(CopySemantics::Mature),
+                worker,
+            );
+        }
+        self.common.trace_object::<Q>(queue, object, worker)
+    }
+
+    fn post_scan_object(&self, object: ObjectReference) {
+        if self.immix_space.in_space(object) {
+            self.immix_space.post_scan_object(object);
+        }
+    }
+
+    fn may_move_objects<const KIND: TraceKind>() -> bool {
+        true
+    }
+}
+
+impl<VM: VMBinding> SemiImmix<VM> {
+    pub fn new(args: CreateGeneralPlanArgs<VM>) -> Self {
+        let mut plan_args = CreateSpecificPlanArgs {
+            global_args: args,
+            constraints: &SEMI_IMMIX_CONSTRAINTS,
+            global_side_metadata_specs: SideMetadataContext::new_global_specs(&[]),
+        };
+
+        let res = SemiImmix {
+            hi: AtomicBool::new(false),
+            copyspace0: CopySpace::new(
+                plan_args.get_space_args("copyspace0", true, false, VMRequest::discontiguous()),
+                false,
+            ),
+            copyspace1: CopySpace::new(
+                plan_args.get_space_args("copyspace1", true, false, VMRequest::discontiguous()),
+                true,
+            ),
+            immix_space: ImmixSpace::new(
+                plan_args.get_space_args("immix", true, false, VMRequest::discontiguous()),
+                ImmixSpaceArgs {
+                    reset_log_bit_in_major_gc: false,
+                    unlog_object_when_traced: false,
+                    #[cfg(feature = "vo_bit")]
+                    mixed_age: false,
+                },
+            ),
+            common: CommonPlan::new(plan_args),
+            mode: Atomic::new(SemiImmixMode::Copying),
+            copying_gc: AtomicBool::new(false),
+            last_gc_was_exhaustive: AtomicBool::new(true),
+        };
+
+        res.verify_side_metadata_sanity();
+
+        res
+    }
+
+    fn mode(&self) -> SemiImmixMode {
+        self.mode.load(Ordering::SeqCst)
+    }
+
+    /// Have mutators switched to allocating into the Immix space?
+    pub fn uses_immix(&self) -> bool {
+        self.mode() == SemiImmixMode::Immix
+    }
+
+    /// Where the live objects in the from-space are copied to in the current GC.
+    fn fromspace_copy_semantics(&self) -> CopySemantics {
+        if self.mode() == SemiImmixMode::Switching {
+            CopySemantics::Mature
+        } else {
+            CopySemantics::DefaultCopy
+        }
+    }
+
+    pub fn tospace(&self) -> &CopySpace<VM> {
+        if self.hi.load(Ordering::SeqCst) {
+            &self.copyspace1
+        } else {
+            &self.copyspace0
+        }
+    }
+
+    pub fn tospace_mut(&mut self) -> &mut CopySpace<VM> {
+        if self.hi.load(Ordering::SeqCst) {
+            &mut self.copyspace1
+        } else {
+            &mut self.copyspace0
+        }
+    }
+
+    pub fn fromspace(&self) -> &CopySpace<VM> {
+        if self.hi.load(Ordering::SeqCst) {
+            &self.copyspace0
+        } else {
+            &self.copyspace1
+        }
+    }
+
+    pub fn fromspace_mut(&mut self) -> &mut CopySpace<VM> {
+        if self.hi.load(Ordering::SeqCst) {
+            &mut self.copyspace0
+        } else {
+            &mut self.copyspace1
+        }
+    }
+}
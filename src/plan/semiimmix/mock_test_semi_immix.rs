@@ -0,0 +1,103 @@
+// GITHUB-CI: MMTK_PLAN=SemiImmix
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::gc_work::SemiImmixGCWorkContext;
+use super::SemiImmix;
+use crate::plan::Plan;
+use crate::policy::immix::{TRACE_KIND_DEFRAG, TRACE_KIND_FAST};
+use crate::policy::space::Space;
+use crate::util::options::PlanSelector;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::vm::slot::Slot;
+
+/// A small threshold so that the test does not need a large heap.
+const THRESHOLD: usize = 256 * 1024;
+/// A list of this many objects exceeds the threshold.
+const LIST_LENGTH: usize = 2 * THRESHOLD / OBJECT_SIZE;
+
+fn setup() -> MockVM {
+    mock_roots::setup::<
+        SemiImmixGCWorkContext<MockVM, TRACE_KIND_FAST>,
+        SemiImmixGCWorkContext<MockVM, TRACE_KIND_DEFRAG>,
+    >()
+}
+
+#[test]
+pub fn semi_immix() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::SemiImmix, |builder| {
+                builder.options.semi_immix_threshold.set(THRESHOLD);
+            });
+            let plan = mmtk.get_plan().downcast_ref::<SemiImmix<MockVM>>().unwrap();
+
+            // root0 -> a -> b. A copying GC copies them to the other semispace.
+            let a = alloc_object(mutator);
+            let b = alloc_object(mutator);
+            Slot::store(&field(a, 0), b);
+            Slot::store(&root_slot(0), a);
+            assert!(plan.tospace().in_space(a));
+            collect(mmtk);
+            assert!(plan.last_collection_was_exhaustive());
+            assert!(!plan.uses_immix());
+            let a2 = root(0);
+            assert_ne!(a2, a);
+            assert!(plan.tospace().in_space(a2));
+            assert!(plan.tospace().in_space(Slot::load(&field(a2, 0)).unwrap()));
+
+            // root1 -> a list that is larger than the threshold. The plan keeps copying in the
+            // first GC after the live data grows past the threshold.
+            let mut head = alloc_object(mutator);
+            for _ in 1..LIST_LENGTH {
+                let object = alloc_object(mutator);
+                Slot::store(&field(object, 0), head);
+                head = object;
+            }
+            Slot::store(&root_slot(1), head);
+            collect(mmtk);
+            assert!(!plan.uses_immix());
+            assert!(plan.tospace().in_space(root(1)));
+
+            // The next GC copies all the live objects into the Immix space, and mutators allocate
+            // into the Immix space from then on.
+            collect(mmtk);
+            assert!(plan.last_collection_was_exhaustive());
+            assert!(plan.uses_immix());
+            assert_eq!(plan.tospace().reserved_pages(), 0);
+            check_list(plan);
+            let a = root(0);
+            assert!(plan.immix_space.in_space(a));
+            assert!(plan.immix_space.in_space(Slot::load(&field(a, 0)).unwrap()));
+            let c = alloc_object(mutator);
+            assert!(plan.immix_space.in_space(c));
+            Slot::store(&field(a, 1), c);
+
+            // Later GCs are Immix GCs.
+            collect(mmtk);
+            assert!(plan.uses_immix());
+            assert_eq!(plan.tospace().reserved_pages(), 0);
+            check_list(plan);
+            let a = root(0);
+            let c = Slot::load(&field(a, 1)).unwrap();
+            for object in [a, c] {
+                assert!(plan.immix_space.in_space(object));
+                assert!(object.is_live());
+            }
+        },
+        no_cleanup,
+    )
+}
+
+/// Check that the list from root1 is intact and has been copied into the Immix space.
+fn check_list(plan: &SemiImmix<MockVM>) {
+    let mut length = 0;
+    let mut next = Slot::load(&root_slot(1));
+    while let Some(object) = next {
+        assert!(plan.immix_space.in_space(object));
+        length += 1;
+        next = Slot::load(&field(object, 0));
+    }
+    assert_eq!(length, LIST_LENGTH);
+}
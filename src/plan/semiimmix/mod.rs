@@ -0,0 +1,11 @@
+//! Plan: SemiImmix (semi-space copying that switches to Immix as the live data grows)
+
+pub(in crate::plan) mod gc_work;
+pub(in crate::plan) mod global;
+pub(in crate::plan) mod mutator;
+
+pub use global::SemiImmix;
+pub use global::SEMI_IMMIX_CONSTRAINTS;
+
+#[cfg(all(test, feature = "mock_test_side_mark_bit"))]
+mod mock_test_semi_immix;
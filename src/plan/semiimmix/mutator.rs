@@ -0,0 +1,88 @@
+use super::SemiImmix;
+use crate::plan::barriers::NoBarrier;
+use crate::plan::mutator_context::unreachable_prepare_func;
+use crate::plan::mutator_context::Mutator;
+use crate::plan::mutator_context::MutatorConfig;
+use crate::plan::mutator_context::{
+    create_allocator_mapping, create_space_mapping, ReservedAllocators,
+};
+use crate::plan::AllocationSemantics;
+use crate::plan::Plan;
+use crate::util::alloc::allocators::{AllocatorSelector, Allocators};
+use crate::util::alloc::{BumpAllocator, ImmixAllocator};
+use crate::util::{VMMutatorThread, VMWorkerThread};
+use crate::vm::VMBinding;
+use crate::MMTK;
+use enum_map::EnumMap;
+
+pub fn semi_immix_mutator_release<VM: VMBinding>(mutator: &mut Mutator<VM>, _tls: VMWorkerThread) {
+    let plan = mutator.plan.downcast_ref::<SemiImmix<VM>>().unwrap();
+    if plan.uses_immix() {
+        // Allocate into the Immix space from now on.
+        mutator.config.allocator_mapping = &IMMIX_ALLOCATOR_MAPPING;
+        let immix_allocator = unsafe {
+            mutator
+                .allocators
+                .get_allocator_mut(AllocatorSelector::Immix(0))
+        }
+        .downcast_mut::<ImmixAllocator<VM>>()
+        .unwrap();
+        immix_allocator.reset();
+    }
+    // rebind the allocation bump pointer to the appropriate semispace
+    let bump_allocator = unsafe {
+        mutator
+            .allocators
+            .get_allocator_mut(AllocatorSelector::BumpPointer(0))
+    }
+    .downcast_mut::<BumpAllocator<VM>>()
+    .unwrap();
+    bump_allocator.rebind(plan.tospace());
+}
+
+const RESERVED_ALLOCATORS: ReservedAllocators = ReservedAllocators {
+    n_bump_pointer: 1,
+    n_immix: 1,
+    ..ReservedAllocators::DEFAULT
+};
+
+lazy_static! {
+    /// The allocator mapping before the plan switches to Immix.
+    pub static ref ALLOCATOR_MAPPING: EnumMap<AllocationSemantics, AllocatorSelector> = {
+        let mut map = create_allocator_mapping(RESERVED_ALLOCATORS, true);
+        map[AllocationSemantics::Default] = AllocatorSelector::BumpPointer(0);
+        map
+    };
+    /// The allocator mapping after the plan switches to Immix.
+    pub static ref IMMIX_ALLOCATOR_MAPPING: EnumMap<AllocationSemantics, AllocatorSelector> = {
+        let mut map = create_allocator_mapping(RESERVED_ALLOCATORS, true);
+        map[AllocationSemantics::Default] = AllocatorSelector::Immix(0);
+        map
+    };
+}
+
+pub fn create_semi_immix_mutator<VM: VMBinding>(
+    mutator_tls: VMMutatorThread,
+    mmtk: &'static MMTK<VM>,
+) -> Mutator<VM> {
+    let plan = mmtk.get_plan().downcast_ref::<SemiImmix<VM>>().unwrap();
+    let config = MutatorConfig {
+        allocator_mapping: plan.get_allocator_mapping(),
+        space_mapping: Box::new({
+            let mut vec = create_space_mapping(RESERVED_ALLOCATORS, true, plan);
+            vec.push((AllocatorSelector::BumpPointer(0), plan.tospace()));
+            vec.push((AllocatorSelector::Immix(0), &plan.immix_space));
+            vec
+        }),
+        prepare_func: &unreachable_prepare_func,
+        release_func: &semi_immix_mutator_release,
+    };
+
+    Mutator {
+        allocators: Allocators::<VM>::new(mutator_tls, mmtk, &config.space_mapping),
+        barrier: Box::new(NoBarrier),
+        mutator_tls,
+        config,
+        plan,
+    }
+}
@@ -0,0 +1,85 @@
+// GITHUB-CI: MMTK_PLAN=SemiSpace
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::gc_work::SSGCWorkContext;
+use super::SemiSpace;
+use crate::memory_manager;
+use crate::policy::space::Space;
+use crate::scheduler::GCWorker;
+use crate::util::constants::BYTES_IN_ADDRESS;
+use crate::util::options::PlanSelector;
+use crate::util::test_util::mock_method::*;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::ObjectReference;
+use crate::vm::slot::Slot;
+use crate::AllocationSemantics;
+
+/// The array is long enough that the packet that scans it is split several times.
+const ARRAY_LENGTH: usize = 4 * GCWorker::<MockVM>::LONG_WORK_PACKET_COST + 1;
+/// The array has a header word followed by its elements.
+const ARRAY_SIZE: usize = DEFAULT_OBJECT_REF_OFFSET + (ARRAY_LENGTH + 1) * BYTES_IN_ADDRESS;
+
+/// The raw address of the only array.
+static ARRAY: AtomicUsize = AtomicUsize::new(0);
+
+fn is_array(object: ObjectReference) -> bool {
+    object.to_raw_address().as_usize() == ARRAY.load(Ordering::SeqCst)
+}
+
+fn setup() -> MockVM {
+    MockVM {
+        get_object_size: MockMethod::new_fixed(Box::new(|object| {
+            if is_array(object) {
+                ARRAY_SIZE
+            } else {
+                OBJECT_SIZE
+            }
+        })),
+        // Other objects are scanned with `scan_object`, which only visits the first fields.
+        reference_array_slice: MockMethod::new_fixed(Box::new(|(_, object)| {
+            is_array(object).then(|| field(object, 0)..field(object, ARRAY_LENGTH))
+        })),
+        ..mock_roots::setup::<SSGCWorkContext<MockVM>, SSGCWorkContext<MockVM>>()
+    }
+}
+
+#[test]
+pub fn reference_array_slice() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::SemiSpace, |_| {});
+            let plan = mmtk.get_plan().downcast_ref::<SemiSpace<MockVM>>().unwrap();
+
+            // root0 -> a large array in the LOS, whose elements are small objects in the copy
+            // space.
+            let semantics = AllocationSemantics::Los;
+            let addr = memory_manager::alloc(mutator, ARRAY_SIZE, BYTES_IN_ADDRESS, 0, semantics);
+            assert!(!addr.is_zero());
+            let array = MockVM::object_start_to_ref(addr);
+            ARRAY.store(array.to_raw_address().as_usize(), Ordering::SeqCst);
+            memory_manager::post_alloc(mutator, array, ARRAY_SIZE, semantics);
+            let elements: Vec<ObjectReference> = (0..ARRAY_LENGTH)
+                .map(|i| {
+                    let element = alloc_object(mutator);
+                    Slot::store(&field(array, i), element);
+                    element
+                })
+                .collect();
+            Slot::store(&root_slot(0), array);
+
+            // The array is scanned in parallel packets.  Every element is copied, and every slot
+            // of the array is updated.
+            collect(mmtk);
+            assert_eq!(root(0), array);
+            for (i, old) in elements.into_iter().enumerate() {
+                let new = Slot::load(&field(array, i)).unwrap();
+                assert_ne!(new, old);
+                assert!(plan.tospace().in_space(new));
+            }
+        },
+        no_cleanup,
+    )
+}
@@ -6,3 +6,6 @@ pub(super) mod mutator;
 
 pub use self::global::SemiSpace;
 pub use self::global::SS_CONSTRAINTS;
+
+#[cfg(all(test, feature = "mock_test"))]
+mod mock_test_reference_array_slice;
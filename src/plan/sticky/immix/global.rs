@@ -113,6 +113,10 @@ impl<VM: VMBinding> Plan for StickyImmix<VM> {
     }
 
     fn prepare(&mut self, tls: crate::util::VMWorkerThread) {
+        crate::plan::generational::global::CommonGenPlan::on_nursery_gc_start(
+            self,
+            self.immix.immix_space.get_pages_allocated(),
+        );
         if self.is_current_gc_nursery() {
             // Prepare both large object space and immix space
             self.immix.immix_space.prepare(
@@ -136,6 +140,7 @@ impl<VM: VMBinding> Plan for StickyImmix<VM> {
     }
 
     fn end_of_gc(&mut self, _tls: crate::util::opaque_pointer::VMWorkerThread) {
+        crate::plan::generational::global::CommonGenPlan::on_nursery_gc_end(self);
         let next_gc_full_heap =
             crate::plan::generational::global::CommonGenPlan::should_next_gc_be_full_heap(self);
         self.next_gc_full_heap
@@ -0,0 +1,25 @@
+use crate::policy::gc_work::DEFAULT_TRACE;
+use crate::policy::gc_work::TRACE_KIND_TRANSITIVE_PIN;
+use crate::scheduler::gc_work::PlanProcessEdges;
+use crate::{plan::generational::gc_work::GenNurseryProcessEdges, vm::VMBinding};
+
+use super::global::StickyMarkSweep;
+
+pub struct StickyMarkSweepNurseryGCWorkContext<VM: VMBinding>(std::marker::PhantomData<VM>);
+
+impl<VM: VMBinding> crate::scheduler::GCWorkContext for StickyMarkSweepNurseryGCWorkContext<VM> {
+    type VM = VM;
+    type PlanType = StickyMarkSweep<VM>;
+    type DefaultProcessEdges = GenNurseryProcessEdges<VM, Self::PlanType, DEFAULT_TRACE>;
+    type PinningProcessEdges =
+        GenNurseryProcessEdges<VM, Self::PlanType, TRACE_KIND_TRANSITIVE_PIN>;
+}
+
+pub struct StickyMarkSweepMatureGCWorkContext<VM: VMBinding>(std::marker::PhantomData<VM>);
+
+impl<VM: VMBinding> crate::scheduler::GCWorkContext for StickyMarkSweepMatureGCWorkContext<VM> {
+    type VM = VM;
+    type PlanType = StickyMarkSweep<VM>;
+    type DefaultProcessEdges = PlanProcessEdges<VM, Self::PlanType, DEFAULT_TRACE>;
+    type PinningProcessEdges = PlanProcessEdges<VM, Self::PlanType, TRACE_KIND_TRANSITIVE_PIN>;
+}
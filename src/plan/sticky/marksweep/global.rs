@@ -0,0 +1,318 @@
+use crate::plan::generational::global::GenerationalPlan;
+use crate::plan::global::BasePlan;
+use crate::plan::global::CommonPlan;
+use crate::plan::global::CreateGeneralPlanArgs;
+use crate::plan::global::CreateSpecificPlanArgs;
+use crate::plan::AllocationSemantics;
+use crate::plan::Plan;
+use crate::plan::PlanConstraints;
+use crate::policy::gc_work::TraceKind;
+use crate::policy::marksweepspace::native_ms::MarkSweepSpace;
+use crate::policy::marksweepspace::native_ms::MarkSweepSpaceArgs;
+use crate::policy::marksweepspace::native_ms::MAX_OBJECT_SIZE;
+use crate::policy::sft::SFT;
+use crate::policy::space::Space;
+use crate::scheduler::GCWorkScheduler;
+use crate::scheduler::GCWorker;
+use crate::util::alloc::allocators::AllocatorSelector;
+use crate::util::heap::gc_trigger::SpaceStats;
+use crate::util::heap::VMRequest;
+use crate::util::statistics::counter::EventCounter;
+use crate::util::Address;
+use crate::util::ObjectReference;
+use crate::util::VMWorkerThread;
+use crate::vm::ObjectModel;
+use crate::vm::VMBinding;
+use crate::ObjectQueue;
+
+use atomic::Ordering;
+use enum_map::EnumMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+use mmtk_macros::{HasSpaces, PlanTraceObject};
+
+use super::gc_work::StickyMarkSweepMatureGCWorkContext;
+use super::gc_work::StickyMarkSweepNurseryGCWorkContext;
+
+/// Sticky mark sweep. Like sticky immix, this plan gets generational behaviors without a copying
+/// nursery. Mark bits are not cleared in nursery GCs, so objects that survived a GC stay marked
+/// and are treated as mature objects. Nursery GCs only trace unmarked objects, and use the log bit
+/// and the modified object buffer to find the nursery objects that are reachable from mature
+/// objects.
+///
+/// This plan always uses the native mark sweep space, regardless of the `malloc_mark_sweep`
+/// feature, as the malloc mark sweep space does not keep mark bits across GCs.
+#[derive(HasSpaces, PlanTraceObject)]
+pub struct StickyMarkSweep<VM: VMBinding> {
+    #[parent]
+    common: CommonPlan<VM>,
+    #[space]
+    ms: MarkSweepSpace<VM>,
+    gc_full_heap: AtomicBool,
+    next_gc_full_heap: AtomicBool,
+    full_heap_gc_count: Arc<Mutex<EventCounter>>,
+}
+
+/// The plan constraints for the sticky mark sweep plan.
+pub const STICKY_MS_CONSTRAINTS: PlanConstraints = PlanConstraints {
+    moves_objects: false,
+    max_non_los_default_alloc_bytes: MAX_OBJECT_SIZE,
+    needs_log_bit: true,
+    barrier: crate::plan::BarrierSelector::ObjectBarrier,
+    // We may trace duplicate edges in sticky mark sweep (or any plan that uses object remembering barrier). See https://github.com/mmtk/mmtk-core/issues/743.
+    may_trace_duplicate_edges: true,
+    needs_prepare_mutator: !cfg!(feature = "eager_sweeping"),
+    ..PlanConstraints::default()
+};
+
+impl<VM: VMBinding> Plan for StickyMarkSweep<VM> {
+    fn constraints(&self) -> &'static PlanConstraints {
+        &STICKY_MS_CONSTRAINTS
+    }
+
+    fn base(&self) -> &BasePlan<VM> {
+        &self.common.base
+    }
+
+    fn base_mut(&mut self) -> &mut BasePlan<Self::VM> {
+        &mut self.common.base
+    }
+
+    fn common(&self) -> &CommonPlan<VM> {
+        &self.common
+    }
+
+    fn generational(&self) -> Option<&dyn GenerationalPlan<VM = VM>> {
+        Some(self)
+    }
+
+    fn schedule_collection(&'static self, scheduler: &GCWorkScheduler<VM>) {
+        let is_full_heap = self.requires_full_heap_collection();
+        self.gc_full_heap.store(is_full_heap, Ordering::SeqCst);
+        probe!(mmtk, gen_full_heap, is_full_heap);
+
+        if !is_full_heap {
+            info!("Nursery GC");
+            scheduler.schedule_common_work::<StickyMarkSweepNurseryGCWorkContext<VM>>(self);
+        } else {
+            info!("Full heap GC");
+            scheduler.schedule_common_work::<StickyMarkSweepMatureGCWorkContext<VM>>(self);
+        }
+    }
+
+    fn get_allocator_mapping(&self) -> &'static EnumMap<AllocationSemantics, AllocatorSelector> {
+        &super::mutator::ALLOCATOR_MAPPING
+    }
+
+    fn prepare(&mut self, tls: VMWorkerThread) {
+        if self.is_current_gc_nursery() {
+            // Keep the mark bits of mature objects.
+            self.ms.prepare(false);
+            self.common.los.prepare(false);
+        } else {
+            self.full_heap_gc_count.lock().unwrap().inc();
+            self.common.prepare(tls, true);
+            self.ms.prepare(true);
+        }
+    }
+
+    fn release(&mut self, tls: VMWorkerThread) {
+        self.ms.release();
+        if self.is_current_gc_nursery() {
+            self.common.los.release(false);
+        } else {
+            self.common.release(tls, true);
+        }
+    }
+
+    fn end_of_gc(&mut self, _tls: VMWorkerThread) {
+        let next_gc_full_heap =
+            crate::plan::generational::global::CommonGenPlan::should_next_gc_be_full_heap(self);
+        self.next_gc_full_heap
+            .store(next_gc_full_heap, Ordering::Relaxed);
+        self.ms.end_of_gc();
+    }
+
+    fn collection_required(&self, space_full: bool, space: Option<SpaceStats<Self::VM>>) -> bool {
+        let nursery_full =
+            self.ms.get_pages_allocated() > self.base().gc_trigger.get_max_nursery_pages();
+        if space_full && space.is_some() && space.as_ref().unwrap().0.name() != self.ms.name() {
+            self.next_gc_full_heap.store(true, Ordering::SeqCst);
+        }
+        self.base().collection_required(self, space_full) || nursery_full
+    }
+
+    fn last_collection_was_exhaustive(&self) -> bool {
+        self.gc_full_heap.load(Ordering::Relaxed)
+    }
+
+    fn current_gc_may_move_object(&self) -> bool {
+        false
+    }
+
+    fn get_used_pages(&self) -> usize {
+        self.common.get_used_pages() + self.ms.reserved_pages()
+    }
+
+    fn sanity_check_object(&self, object: ObjectReference) -> bool {
+        if self.is_current_gc_nursery() {
+            // Every reachable object should be logged
+            if !VM::VMObjectModel::GLOBAL_LOG_BIT_SPEC.is_unlogged::<VM>(object, Ordering::SeqCst) {
+                error!("Object {} is not unlogged (all objects that have been traced should be unlogged/mature)", object);
+                return false;
+            }
+
+            // Every reachable object should be marked
+            if self.ms.in_space(object) && !self.ms.is_marked(object) {
+                error!(
+                    "Object {} is not marked (all objects that have been traced should be marked)",
+                    object
+                );
+                return false;
+            } else if self.common.los.in_space(object) && !self.common.los.is_live(object) {
+                error!("LOS Object {} is not marked", object);
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl<VM: VMBinding> GenerationalPlan for StickyMarkSweep<VM> {
+    fn is_current_gc_nursery(&self) -> bool {
+        !self.gc_full_heap.load(Ordering::SeqCst)
+    }
+
+    fn is_object_in_nursery(&self, object: ObjectReference) -> bool {
+        self.ms.in_space(object) && !self.ms.is_marked(object)
+    }
+
+    // Like sticky immix, we need object metadata to tell whether an object is in the nursery, so
+    // we conservatively treat every address as in the mature space.
+    fn is_address_in_nursery(&self, _addr: Address) -> bool {
+        false
+    }
+
+    fn get_mature_physical_pages_available(&self) -> usize {
+        self.ms.available_physical_pages()
+    }
+
+    fn get_mature_reserved_pages(&self) -> usize {
+        self.ms.reserved_pages()
+    }
+
+    fn force_full_heap_collection(&self) {
+        self.next_gc_full_heap.store(true, Ordering::SeqCst);
+    }
+
+    fn last_collection_full_heap(&self) -> bool {
+        self.gc_full_heap.load(Ordering::SeqCst)
+    }
+}
+
+impl<VM: VMBinding> crate::plan::generational::global::GenerationalPlanExt<VM>
+    for StickyMarkSweep<VM>
+{
+    fn trace_object_nursery<Q: ObjectQueue, const KIND: TraceKind>(
+        &self,
+        queue: &mut Q,
+        object: ObjectReference,
+        worker: &mut GCWorker<VM>,
+    ) -> ObjectReference {
+        if self.ms.in_space(object) {
+            if !self.is_object_in_nursery(object) {
+                trace!("Mark sweep mature object {}, skip", object);
+                return object;
+            }
+            trace!("Mark sweep nursery object {} is being traced", object);
+            // The space marks the object and unlogs it, which makes it a mature object.
+            return <MarkSweepSpace<VM> as crate::policy::gc_work::PolicyTraceObject<VM>>::trace_object::<Q, KIND>(
+                &self.ms, queue, object, None, worker,
+            );
+        }
+
+        if self.common.get_los().in_space(object) {
+            return self.common.get_los().trace_object::<Q>(queue, object);
+        }
+
+        object
+    }
+}
+
+impl<VM: VMBinding> StickyMarkSweep<VM> {
+    pub fn new(args: CreateGeneralPlanArgs<VM>) -> Self {
+        let full_heap_gc_count = args.stats.new_event_counter("majorGC", true, true);
+        let mut global_side_metadata_specs =
+            crate::plan::generational::new_generational_global_metadata_specs::<VM>();
+        MarkSweepSpace::<VM>::extend_global_side_metadata_specs(&mut global_side_metadata_specs);
+
+        let mut plan_args = CreateSpecificPlanArgs {
+            global_args: args,
+            constraints: &STICKY_MS_CONSTRAINTS,
+            global_side_metadata_specs,
+        };
+
+        let ms = MarkSweepSpace::new_with_args(
+            plan_args.get_space_args("ms", true, false, VMRequest::discontiguous()),
+            MarkSweepSpaceArgs {
+                // Every object we trace in nursery GC becomes a mature object.
+                // Every object we trace in full heap GC is a mature object. Thus in both cases,
+                // they should be unlogged.
+                unlog_object_when_traced: true,
+                // In full heap GC, mature objects may die, and their unlogged bit needs to be reset.
+                // Along with the option above, we unlog them again during tracing.
+                reset_log_bit_in_major_gc: true,
+            },
+        );
+
+        let res = StickyMarkSweep {
+            ms,
+            common: CommonPlan::new(plan_args),
+            gc_full_heap: AtomicBool::new(false),
+            next_gc_full_heap: AtomicBool::new(false),
+            full_heap_gc_count,
+        };
+
+        res.verify_side_metadata_sanity();
+
+        res
+    }
+
+    fn requires_full_heap_collection(&self) -> bool {
+        // Separate each condition so the code is clear
+        #[allow(clippy::if_same_then_else, clippy::needless_bool)]
+        if crate::plan::generational::FULL_NURSERY_GC {
+            trace!("full heap: forced full heap");
+            // For barrier overhead measurements, we always do full gc in nursery collections.
+            true
+        } else if self
+            .common
+            .base
+            .global_state
+            .user_triggered_collection
+            .load(Ordering::SeqCst)
+            && *self.common.base.options.full_heap_system_gc
+        {
+            // User triggered collection, and we force full heap for user triggered collection
+            true
+        } else if self.next_gc_full_heap.load(Ordering::SeqCst)
+            || self
+                .common
+                .base
+                .global_state
+                .cur_collection_attempts
+                .load(Ordering::SeqCst)
+                > 1
+        {
+            // Forces full heap collection
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn ms_space(&self) -> &MarkSweepSpace<VM> {
+        &self.ms
+    }
+}
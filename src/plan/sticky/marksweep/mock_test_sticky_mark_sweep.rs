@@ -0,0 +1,90 @@
+// GITHUB-CI: MMTK_PLAN=StickyMarkSweep
+// GITHUB-CI: FEATURES=mock_test_side_mark_bit
+
+use super::gc_work::{StickyMarkSweepMatureGCWorkContext, StickyMarkSweepNurseryGCWorkContext};
+use super::StickyMarkSweep;
+use crate::memory_manager;
+use crate::plan::generational::global::GenerationalPlan;
+use crate::policy::space::Space;
+use crate::util::options::PlanSelector;
+use crate::util::test_util::mock_roots::{self, *};
+use crate::util::test_util::mock_vm::*;
+use crate::util::Address;
+use crate::vm::slot::Slot;
+
+fn setup() -> MockVM {
+    mock_roots::setup::<
+        StickyMarkSweepNurseryGCWorkContext<MockVM>,
+        StickyMarkSweepMatureGCWorkContext<MockVM>,
+    >()
+}
+
+#[test]
+pub fn sticky_mark_sweep() {
+    with_mockvm(
+        setup,
+        || {
+            let (mmtk, mutator) = create_mmtk(PlanSelector::StickyMarkSweep, |_| {});
+            let plan = mmtk
+                .get_plan()
+                .downcast_ref::<StickyMarkSweep<MockVM>>()
+                .unwrap();
+
+            // root0 -> a -> b, and d is unreachable.
+            let a = alloc_object(mutator);
+            let b = alloc_object(mutator);
+            let d = alloc_object(mutator);
+            Slot::store(&field(a, 0), b);
+            Slot::store(&root_slot(0), a);
+            for object in [a, b, d] {
+                assert!(plan.ms_space().in_space(object));
+                assert!(plan.is_object_in_nursery(object));
+            }
+
+            // A nursery GC does not move objects. Survivors become mature, and dead young objects
+            // are reclaimed.
+            collect(mmtk);
+            assert!(!plan.last_collection_full_heap());
+            assert_eq!(root(0), a);
+            assert_eq!(Slot::load(&field(a, 0)), Some(b));
+            for object in [a, b] {
+                assert!(object.is_live());
+                assert!(!plan.is_object_in_nursery(object));
+            }
+            assert!(!d.is_live());
+
+            // c is only reachable from a mature object, and is remembered by the barrier. b is an
+            // unreachable mature object, which a nursery GC does not reclaim.
+            unsafe { field(a, 0).store(Address::ZERO) };
+            let c = alloc_object(mutator);
+            memory_manager::object_reference_write_pre(mutator, a, field(a, 1), Some(c));
+            Slot::store(&field(a, 1), c);
+            memory_manager::object_reference_write_post(mutator, a, field(a, 1), Some(c));
+            collect(mmtk);
+            assert!(!plan.last_collection_full_heap());
+            assert_eq!(Slot::load(&field(a, 1)), Some(c));
+            assert!(c.is_live());
+            assert!(!plan.is_object_in_nursery(c));
+            assert!(b.is_live());
+
+            // A full heap GC reclaims unreachable mature objects.
+            plan.force_full_heap_collection();
+            collect(mmtk);
+            assert!(plan.last_collection_full_heap());
+            for object in [a, c] {
+                assert!(object.is_live());
+            }
+            assert!(!b.is_live());
+
+            // Objects allocated after a full heap GC are in the nursery again.
+            let e = alloc_object(mutator);
+            assert!(plan.is_object_in_nursery(e));
+            Slot::store(&root_slot(1), e);
+            collect(mmtk);
+            assert!(!plan.last_collection_full_heap());
+            assert_eq!(root(1), e);
+            assert!(!plan.is_object_in_nursery(e));
+        },
+        no_cleanup,
+    )
+}
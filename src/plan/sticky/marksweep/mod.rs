@@ -0,0 +1,9 @@
+pub(in crate::plan) mod gc_work;
+pub(in crate::plan) mod global;
+pub(in crate::plan) mod mutator;
+
+pub use global::StickyMarkSweep;
+pub use global::STICKY_MS_CONSTRAINTS;
+
+#[cfg(all(test, feature = "mock_test_side_mark_bit"))]
+mod mock_test_sticky_mark_sweep;
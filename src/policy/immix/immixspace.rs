@@ -8,6 +8,7 @@ use crate::policy::sft::SFT;
 use crate::policy::sft_map::SFTMap;
 use crate::policy::space::{CommonSpace, Space};
 use crate::util::alloc::allocator::AllocatorContext;
+use crate::util::alloc::AllocationError;
 use crate::util::constants::LOG_BYTES_IN_PAGE;
 use crate::util::heap::chunk_map::*;
 use crate::util::heap::BlockPageResource;
@@ -29,7 +30,7 @@ use crate::{
     MMTK,
 };
 use atomic::Ordering;
-use std::sync::{atomic::AtomicU8, atomic::AtomicUsize, Arc};
+use std::sync::{atomic::AtomicBool, atomic::AtomicU8, atomic::AtomicUsize, Arc};
 
 pub(crate) const TRACE_KIND_FAST: TraceKind = 0;
 pub(crate) const TRACE_KIND_DEFRAG: TraceKind = 1;
@@ -55,6 +56,9 @@ pub struct ImmixSpace<VM: VMBinding> {
     scheduler: Arc<GCWorkScheduler<VM>>,
     /// Some settings for this space
     space_args: ImmixSpaceArgs,
+    /// Whether newly allocated objects are considered live by the current GC. This is set during
+    /// concurrent marking, as the objects are allocated after the snapshot is taken.
+    allocate_as_live: AtomicBool,
 }
 
 /// Some arguments for Immix Space.
@@ -137,9 +141,12 @@ impl<VM: VMBinding> SFT for ImmixSpace<VM> {
     fn is_sane(&self) -> bool {
         true
     }
-    fn initialize_object_metadata(&self, _object: ObjectReference, _alloc: bool) {
+    fn initialize_object_metadata(&self, object: ObjectReference, _alloc: bool) {
         #[cfg(feature = "vo_bit")]
-        crate::util::metadata::vo_bit::set_vo_bit(_object);
+        crate::util::metadata::vo_bit::set_vo_bit(object);
+        if self.allocate_as_live.load(Ordering::Acquire) {
+            self.mark_as_live(object);
+        }
     }
     #[cfg(feature = "is_mmtk_object")]
     fn is_mmtk_object(&self, addr: Address) -> Option<ObjectReference> {
@@ -334,6 +341,7 @@ impl<VM: VMBinding> ImmixSpace<VM> {
             mark_state: Self::MARKED_STATE,
             scheduler: scheduler.clone(),
             space_args,
+            allocate_as_live: AtomicBool::new(false),
         }
     }
 
@@ -515,11 +523,16 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         self.pr.release_block(block);
     }
 
-    /// Allocate a clean block.
-    pub fn get_clean_block(&self, tls: VMThread, copy: bool) -> Option<Block> {
-        let block_address = self.acquire(tls, Block::PAGES);
+    /// Allocate a clean block. Return `None` if no block is available now, or an error if the
+    /// allocation must fail (see [`Space::acquire`]).
+    pub fn get_clean_block(
+        &self,
+        tls: VMThread,
+        copy: bool,
+    ) -> Result<Option<Block>, AllocationError> {
+        let block_address = self.acquire(tls, Block::PAGES)?;
         if block_address.is_zero() {
-            return None;
+            return Ok(None);
         }
         self.defrag.notify_new_clean_block(copy);
         let block = Block::from_aligned_address(block_address);
@@ -527,7 +540,7 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         self.chunk_map.set(block.chunk(), ChunkState::Allocated);
         self.lines_consumed
             .fetch_add(Block::LINES, Ordering::SeqCst);
-        Some(block)
+        Ok(Some(block))
     }
 
     /// Pop a reusable block from the reusable block list.
@@ -705,6 +718,38 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         }
     }
 
+    /// Let newly allocated objects be live (or not) in the current GC.
+    pub(crate) fn set_allocate_as_live(&self, live: bool) {
+        self.allocate_as_live.store(live, Ordering::Release);
+    }
+
+    /// Mark a newly allocated object and the lines (or the block) it occupies.
+    fn mark_as_live(&self, object: ObjectReference) {
+        self.attempt_mark(object, self.mark_state);
+        if super::BLOCK_ONLY {
+            Block::containing(object).set_state(BlockState::Marked);
+        } else {
+            self.mark_lines(object);
+        }
+    }
+
+    /// Mark an object and the lines (or the block) it occupies without enqueuing it for scanning.
+    /// Return true if the object was not marked before.  This is used by plans that find live
+    /// objects without a transitive closure, such as reference counting.
+    pub(crate) fn mark_without_tracing(&self, object: ObjectReference) -> bool {
+        if !self.attempt_mark(object, self.mark_state) {
+            return false;
+        }
+        #[cfg(feature = "vo_bit")]
+        vo_bit::helper::on_object_marked::<VM>(object);
+        if super::BLOCK_ONLY {
+            Block::containing(object).set_state(BlockState::Marked);
+        } else {
+            self.mark_lines(object);
+        }
+        true
+    }
+
     /// Mark all the lines that the given object spans.
     #[allow(clippy::assertions_on_constants)]
     pub fn mark_lines(&self, object: ObjectReference) {
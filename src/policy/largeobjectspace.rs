@@ -5,6 +5,7 @@ use crate::plan::VectorObjectQueue;
 use crate::policy::sft::GCWorkerMutRef;
 use crate::policy::sft::SFT;
 use crate::policy::space::{CommonSpace, Space};
+use crate::util::alloc::AllocationError;
 use crate::util::constants::BYTES_IN_PAGE;
 use crate::util::heap::{FreeListPageResource, PageResource};
 use crate::util::metadata;
@@ -303,7 +304,7 @@ impl<VM: VMBinding> LargeObjectSpace<VM> {
     }
 
     /// Allocate an object
-    pub fn allocate_pages(&self, tls: VMThread, pages: usize) -> Address {
+    pub fn allocate_pages(&self, tls: VMThread, pages: usize) -> Result<Address, AllocationError> {
         self.acquire(tls, pages)
     }
 
@@ -355,7 +356,7 @@ impl<VM: VMBinding> LargeObjectSpace<VM> {
     }
 
     /// Check if a given object is in nursery
-    fn is_in_nursery(&self, object: ObjectReference) -> bool {
+    pub(crate) fn is_in_nursery(&self, object: ObjectReference) -> bool {
         VM::VMObjectModel::LOCAL_LOS_MARK_NURSERY_SPEC.load_atomic::<VM, u8>(
             object,
             None,
@@ -7,6 +7,7 @@ use crate::policy::sft::GCWorkerMutRef;
 use crate::policy::sft::SFT;
 use crate::policy::space::{CommonSpace, Space};
 use crate::util::address::Address;
+use crate::util::alloc::AllocationError;
 
 use crate::util::conversions;
 use crate::util::heap::gc_trigger::GCTrigger;
@@ -136,7 +137,7 @@ impl<VM: VMBinding> Space<VM> for LockFreeImmortalSpace<VM> {
         data_pages + meta_pages
     }
 
-    fn acquire(&self, _tls: VMThread, pages: usize) -> Address {
+    fn acquire(&self, _tls: VMThread, pages: usize) -> Result<Address, AllocationError> {
         trace!("LockFreeImmortalSpace::acquire");
         let bytes = conversions::pages_to_bytes(pages);
         let start = self
@@ -151,7 +152,7 @@ impl<VM: VMBinding> Space<VM> for LockFreeImmortalSpace<VM> {
         if self.slow_path_zeroing {
             crate::util::memory::zero(start, bytes);
         }
-        start
+        Ok(start)
     }
 
     /// Get the name of the space
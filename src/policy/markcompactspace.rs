@@ -1,20 +1,25 @@
+use std::collections::VecDeque;
 use std::ops::Range;
+use std::sync::atomic::AtomicUsize;
+use std::sync::{Arc, Mutex};
 
 use super::sft::SFT;
 use super::space::{CommonSpace, Space};
 use crate::plan::VectorObjectQueue;
 use crate::policy::gc_work::{TraceKind, TRACE_KIND_TRANSITIVE_PIN};
 use crate::policy::sft::GCWorkerMutRef;
-use crate::scheduler::GCWorker;
+use crate::scheduler::{GCWork, GCWorkScheduler, GCWorker, WorkBucketStage};
 use crate::util::alloc::allocator::align_allocation_no_fill;
 use crate::util::constants::LOG_BYTES_IN_WORD;
 use crate::util::copy::CopySemantics;
 use crate::util::heap::{MonotonePageResource, PageResource};
+#[cfg(feature = "mark_compact_side_forwarding")]
+use crate::util::metadata::side_metadata::SideMetadataSpec;
 use crate::util::metadata::{extract_side_metadata, vo_bit};
 use crate::util::object_enum::{self, ObjectEnumerator};
 use crate::util::{Address, ObjectReference};
-use crate::{vm::*, ObjectQueue};
-use atomic::Ordering;
+use crate::{vm::*, ObjectQueue, MMTK};
+use atomic::{Atomic, Ordering};
 
 pub(crate) const TRACE_KIND_MARK: TraceKind = 0;
 pub(crate) const TRACE_KIND_FORWARD: TraceKind = 1;
@@ -22,6 +27,8 @@ pub(crate) const TRACE_KIND_FORWARD: TraceKind = 1;
 pub struct MarkCompactSpace<VM: VMBinding> {
     common: CommonSpace<VM>,
     pr: MonotonePageResource<VM>,
+    /// The end of the live objects after compaction, computed with the forwarding pointers.
+    compaction_top: Atomic<Address>,
 }
 
 const GC_MARK_BIT_MASK: u8 = 1;
@@ -29,18 +36,41 @@ const GC_MARK_BIT_MASK: u8 = 1;
 /// For each MarkCompact object, we need one extra word for storing forwarding pointer (Lisp-2 implementation).
 /// Note that considering the object alignment, we may end up allocating/reserving more than one word per object.
 /// See [`MarkCompactSpace::HEADER_RESERVED_IN_BYTES`].
+/// With the `mark_compact_side_forwarding` feature, the forwarding information is kept in side
+/// metadata instead, and no extra word is needed.
+#[cfg(not(feature = "mark_compact_side_forwarding"))]
 pub const GC_EXTRA_HEADER_WORD: usize = 1;
+#[cfg(feature = "mark_compact_side_forwarding")]
+pub const GC_EXTRA_HEADER_WORD: usize = 0;
 const GC_EXTRA_HEADER_BYTES: usize = GC_EXTRA_HEADER_WORD << LOG_BYTES_IN_WORD;
 
+/// The log of the size of a forwarding block.  With side forwarding, the forwarding table has one
+/// entry for each block.
+pub(crate) const LOG_BYTES_IN_FORWARDING_BLOCK: usize = 9;
+#[cfg(feature = "mark_compact_side_forwarding")]
+const BYTES_IN_FORWARDING_BLOCK: usize = 1 << LOG_BYTES_IN_FORWARDING_BLOCK;
+/// The forwarding table entry of a block whose objects are not moved, because some of them are
+/// pinned.
+#[cfg(feature = "mark_compact_side_forwarding")]
+const IN_PLACE: usize = usize::MAX;
+
 impl<VM: VMBinding> SFT for MarkCompactSpace<VM> {
     fn name(&self) -> &'static str {
         self.get_name()
     }
 
+    #[cfg(not(feature = "mark_compact_side_forwarding"))]
     fn get_forwarded_object(&self, object: ObjectReference) -> Option<ObjectReference> {
         Self::get_header_forwarding_pointer(object)
     }
 
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    fn get_forwarded_object(&self, object: ObjectReference) -> Option<ObjectReference> {
+        // Only live objects have entries in the forwarding table, and they are marked until they
+        // are compacted.
+        Self::is_marked(object).then(|| self.get_side_forwarding_pointer(object))
+    }
+
     fn is_live(&self, object: ObjectReference) -> bool {
         // Sanity checker cannot use this method to do the verification
         // since the mark bit will be cleared during the second trace(update forwarding pointer)
@@ -48,18 +78,18 @@ impl<VM: VMBinding> SFT for MarkCompactSpace<VM> {
     }
 
     #[cfg(feature = "object_pinning")]
-    fn pin_object(&self, _object: ObjectReference) -> bool {
-        panic!("Cannot pin/unpin objects of MarkCompactSpace.")
+    fn pin_object(&self, object: ObjectReference) -> bool {
+        VM::VMObjectModel::LOCAL_PINNING_BIT_SPEC.pin_object::<VM>(object)
     }
 
     #[cfg(feature = "object_pinning")]
-    fn unpin_object(&self, _object: ObjectReference) -> bool {
-        panic!("Cannot pin/unpin objects of MarkCompactSpace.")
+    fn unpin_object(&self, object: ObjectReference) -> bool {
+        VM::VMObjectModel::LOCAL_PINNING_BIT_SPEC.unpin_object::<VM>(object)
     }
 
     #[cfg(feature = "object_pinning")]
-    fn is_object_pinned(&self, _object: ObjectReference) -> bool {
-        false
+    fn is_object_pinned(&self, object: ObjectReference) -> bool {
+        VM::VMObjectModel::LOCAL_PINNING_BIT_SPEC.is_object_pinned::<VM>(object)
     }
 
     fn is_movable(&self) -> bool {
@@ -171,13 +201,26 @@ impl<VM: VMBinding> crate::policy::gc_work::PolicyTraceObject<VM> for MarkCompac
 
 impl<VM: VMBinding> MarkCompactSpace<VM> {
     /// We need one extra header word for each object. Considering the alignment requirement, this is
-    /// the actual bytes we need to reserve for each allocation.
-    pub const HEADER_RESERVED_IN_BYTES: usize = if VM::MAX_ALIGNMENT > GC_EXTRA_HEADER_BYTES {
-        VM::MAX_ALIGNMENT
+    /// the actual bytes we need to reserve for each allocation.  Nothing is reserved with side
+    /// forwarding.
+    pub const HEADER_RESERVED_IN_BYTES: usize = if GC_EXTRA_HEADER_BYTES == 0 {
+        0
+    } else if VM::MAX_ALIGNMENT > GC_EXTRA_HEADER_BYTES {
+        VM::MAX_ALIGNMENT.next_power_of_two()
     } else {
-        GC_EXTRA_HEADER_BYTES
-    }
-    .next_power_of_two();
+        GC_EXTRA_HEADER_BYTES.next_power_of_two()
+    };
+
+    /// With side forwarding, where the first live object of each forwarding block is moved to.
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    const FORWARDING_TABLE: SideMetadataSpec =
+        crate::util::metadata::side_metadata::spec_defs::MC_FORWARDING_TABLE;
+
+    /// With side forwarding, whether the fields of an object have been forwarded.  The mark bits
+    /// cannot be reused for this, because they tell the live objects of a block until compaction.
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    const FORWARDED_BIT: SideMetadataSpec =
+        crate::util::metadata::side_metadata::spec_defs::MC_FORWARDED_BIT;
 
     // The following are a few functions for manipulating header forwarding poiner.
     // Basically for each allocation request, we allocate extra bytes of [`HEADER_RESERVED_IN_BYTES`].
@@ -187,17 +230,20 @@ impl<VM: VMBinding> MarkCompactSpace<VM> {
     // From the cell address, `cell - GC_EXTRA_HEADER_WORD` is where we store the header forwarding pointer.
 
     /// Get the address for header forwarding pointer
+    #[cfg(not(feature = "mark_compact_side_forwarding"))]
     fn header_forwarding_pointer_address(object: ObjectReference) -> Address {
         object.to_object_start::<VM>() - GC_EXTRA_HEADER_BYTES
     }
 
     /// Get header forwarding pointer for an object
+    #[cfg(not(feature = "mark_compact_side_forwarding"))]
     fn get_header_forwarding_pointer(object: ObjectReference) -> Option<ObjectReference> {
         let addr = unsafe { Self::header_forwarding_pointer_address(object).load::<Address>() };
         ObjectReference::from_raw_address(addr)
     }
 
     /// Store header forwarding pointer for an object
+    #[cfg(not(feature = "mark_compact_side_forwarding"))]
     fn store_header_forwarding_pointer(
         object: ObjectReference,
         forwarding_pointer: ObjectReference,
@@ -209,6 +255,7 @@ impl<VM: VMBinding> MarkCompactSpace<VM> {
     }
 
     // Clear header forwarding pointer for an object
+    #[cfg(not(feature = "mark_compact_side_forwarding"))]
     fn clear_header_forwarding_pointer(object: ObjectReference) {
         crate::util::memory::zero(
             Self::header_forwarding_pointer_address(object),
@@ -216,10 +263,135 @@ impl<VM: VMBinding> MarkCompactSpace<VM> {
         );
     }
 
+    // With side forwarding, the forwarding pointers are not stored.  Instead, the forwarding table
+    // records where the first live object of each forwarding block is moved to, and the other live
+    // objects of the block follow it in address order.  The forwarding pointer of an object is
+    // computed by laying out the live objects of its block from the table entry.  An object
+    // belongs to the block that contains its object reference.
+
+    /// Get the forwarding block that an object belongs to.
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    fn forwarding_block(object: ObjectReference) -> Address {
+        object
+            .to_raw_address()
+            .align_down(BYTES_IN_FORWARDING_BLOCK)
+    }
+
+    /// Record where a live object is moved to if it is the first live object of its block.  The
+    /// table entries of the region must have been cleared.
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    fn store_side_forwarding_entry(object: ObjectReference, to: usize) {
+        let block = Self::forwarding_block(object);
+        if Self::FORWARDING_TABLE.load_atomic::<usize>(block, Ordering::Relaxed) == 0 {
+            Self::FORWARDING_TABLE.store_atomic::<usize>(block, to, Ordering::Relaxed);
+        }
+    }
+
+    /// Compute the forwarding pointer of a live object from the forwarding table.  This reads the
+    /// mark bits and the sizes of the objects before it in its block, so it cannot be used once
+    /// the block starts being compacted.
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    fn get_side_forwarding_pointer(&self, object: ObjectReference) -> ObjectReference {
+        let block = Self::forwarding_block(object);
+        let entry = Self::FORWARDING_TABLE.load_atomic::<usize>(block, Ordering::Relaxed);
+        if entry == IN_PLACE {
+            return object;
+        }
+        let mut to_cursor = unsafe { Address::from_usize(entry) };
+        for obj in self
+            .linear_scan_objects(block..block + BYTES_IN_FORWARDING_BLOCK)
+            .filter(Self::to_be_compacted)
+        {
+            to_cursor = self.skip_pinned_blocks(obj, to_cursor);
+            if obj == object {
+                return VM::VMObjectModel::get_reference_when_copied_to(obj, to_cursor);
+            }
+            to_cursor += VM::VMObjectModel::get_size_when_copied(obj);
+        }
+        panic!("Object {object} is not live in its forwarding block")
+    }
+
+    /// Move `to_cursor` to where an object is placed, past the pinned blocks in the way.  This finds
+    /// the pinned blocks with the forwarding table, and places the object as
+    /// [`Self::place_object`] does with the pinned blocks of its compaction region.
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    fn skip_pinned_blocks(&self, object: ObjectReference, mut to_cursor: Address) -> Address {
+        let copied_size = VM::VMObjectModel::get_size_when_copied(object);
+        'place: loop {
+            to_cursor = Self::align_to_cursor(object, to_cursor);
+            let end = to_cursor + copied_size;
+            // The memory of a pinned block may start before the block, but not by a whole block.
+            let mut block = to_cursor.align_down(BYTES_IN_FORWARDING_BLOCK);
+            while block < end + BYTES_IN_FORWARDING_BLOCK {
+                if Self::FORWARDING_TABLE.load_atomic::<usize>(block, Ordering::Relaxed) == IN_PLACE
+                {
+                    let pinned = self
+                        .pinned_blocks(block..block + BYTES_IN_FORWARDING_BLOCK)
+                        .pop_front()
+                        .unwrap();
+                    if pinned.start >= to_cursor && pinned.start < end {
+                        to_cursor = pinned.end;
+                        continue 'place;
+                    }
+                }
+                block += BYTES_IN_FORWARDING_BLOCK;
+            }
+            return to_cursor;
+        }
+    }
+
+    /// Find the forwarding blocks with pinned objects in the given memory.  All the live objects of
+    /// such a block stay where they are.  Return the memory from the start of the first live object
+    /// to the end of the last live object of each of the blocks, in address order.
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    fn pinned_blocks(&self, range: Range<Address>) -> VecDeque<Range<Address>> {
+        let mut pinned_blocks = VecDeque::new();
+        // The current block, the memory of its live objects, and whether it has pinned objects.
+        let mut current: Option<(Address, Range<Address>, bool)> = None;
+        for obj in self
+            .linear_scan_objects(range)
+            .filter(Self::to_be_compacted)
+        {
+            let block = Self::forwarding_block(obj);
+            let start = obj.to_object_start::<VM>();
+            let end = start + VM::VMObjectModel::get_current_size(obj);
+            match &mut current {
+                Some((current_block, memory, pinned)) if *current_block == block => {
+                    memory.end = end;
+                    *pinned |= Self::is_pinned(obj);
+                }
+                _ => {
+                    if let Some((_, memory, true)) = current.take() {
+                        pinned_blocks.push_back(memory);
+                    }
+                    current = Some((block, start..end, Self::is_pinned(obj)));
+                }
+            }
+        }
+        if let Some((_, memory, true)) = current {
+            pinned_blocks.push_back(memory);
+        }
+        pinned_blocks
+    }
+
+    /// Test and set the forwarded bit of an object.  Return true if the bit was not set.
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    fn test_and_set_forwarded(object: ObjectReference) -> bool {
+        Self::FORWARDED_BIT.fetch_or_atomic::<u8>(object.to_raw_address(), 1, Ordering::SeqCst) == 0
+    }
+
     pub fn new(args: crate::policy::space::PlanCreateSpaceArgs<VM>) -> Self {
         let vm_map = args.vm_map;
         let is_discontiguous = args.vmrequest.is_discontiguous();
-        let local_specs = extract_side_metadata(&[*VM::VMObjectModel::LOCAL_MARK_BIT_SPEC]);
+        let local_specs = extract_side_metadata(&[
+            *VM::VMObjectModel::LOCAL_MARK_BIT_SPEC,
+            #[cfg(feature = "object_pinning")]
+            *VM::VMObjectModel::LOCAL_PINNING_BIT_SPEC,
+            #[cfg(feature = "mark_compact_side_forwarding")]
+            crate::util::metadata::MetadataSpec::OnSide(Self::FORWARDING_TABLE),
+            #[cfg(feature = "mark_compact_side_forwarding")]
+            crate::util::metadata::MetadataSpec::OnSide(Self::FORWARDED_BIT),
+        ]);
         let common = CommonSpace::new(args.into_policy_args(true, false, local_specs));
         MarkCompactSpace {
             pr: if is_discontiguous {
@@ -228,12 +400,19 @@ impl<VM: VMBinding> MarkCompactSpace<VM> {
                 MonotonePageResource::new_contiguous(common.start, common.extent, vm_map)
             },
             common,
+            compaction_top: Atomic::new(Address::ZERO),
         }
     }
 
     pub fn prepare(&self) {}
 
-    pub fn release(&self) {}
+    pub fn release(&self) {
+        let to = self.compaction_top.load(Ordering::SeqCst);
+        debug!("Compact end: to = {}", to);
+
+        // reset the bump pointer
+        self.pr.reset_cursor(to);
+    }
 
     pub fn trace_mark_object<Q: ObjectQueue>(
         &self,
@@ -261,14 +440,26 @@ impl<VM: VMBinding> MarkCompactSpace<VM> {
             "{:x}: VO bit not set",
             object
         );
-        // from this stage and onwards, mark bit is no longer needed
-        // therefore, it can be reused to save one extra bit in metadata
-        if MarkCompactSpace::<VM>::test_and_clear_mark(object) {
-            queue.enqueue(object);
+        #[cfg(not(feature = "mark_compact_side_forwarding"))]
+        {
+            // from this stage and onwards, mark bit is no longer needed
+            // therefore, it can be reused to save one extra bit in metadata
+            if MarkCompactSpace::<VM>::test_and_clear_mark(object) {
+                queue.enqueue(object);
+            }
+
+            Self::get_header_forwarding_pointer(object)
+                .unwrap_or_else(|| panic!("Object {object} does not have a forwarding pointer"))
         }
 
-        Self::get_header_forwarding_pointer(object)
-            .unwrap_or_else(|| panic!("Object {object} does not have a forwarding pointer"))
+        #[cfg(feature = "mark_compact_side_forwarding")]
+        {
+            if MarkCompactSpace::<VM>::test_and_set_forwarded(object) {
+                queue.enqueue(object);
+            }
+
+            self.get_side_forwarding_pointer(object)
+        }
     }
 
     pub fn test_and_mark(object: ObjectReference) -> bool {
@@ -342,6 +533,22 @@ impl<VM: VMBinding> MarkCompactSpace<VM> {
         Self::is_marked(*object)
     }
 
+    /// Check if an object is pinned.
+    fn is_pinned(_object: ObjectReference) -> bool {
+        #[cfg(feature = "object_pinning")]
+        return VM::VMObjectModel::LOCAL_PINNING_BIT_SPEC.is_object_pinned::<VM>(_object);
+
+        #[cfg(not(feature = "object_pinning"))]
+        false
+    }
+
+    /// Move `to_cursor` to where an object would be placed to satisfy its alignment when copied.
+    fn align_to_cursor(object: ObjectReference, to_cursor: Address) -> Address {
+        let align = VM::VMObjectModel::get_align_when_copied(object);
+        let offset = VM::VMObjectModel::get_align_offset_when_copied(object);
+        align_allocation_no_fill::<VM>(to_cursor, align, offset)
+    }
+
     /// Linear scan all the live objects in the given memory region
     fn linear_scan_objects(&self, range: Range<Address>) -> impl Iterator<Item = ObjectReference> {
         crate::util::linear_scan::ObjectIterator::<VM, MarkCompactObjectSize<VM>, true>::new(
@@ -350,39 +557,141 @@ impl<VM: VMBinding> MarkCompactSpace<VM> {
         )
     }
 
-    pub fn calculate_forwarding_pointer(&self) {
-        let mut to_iter = self.pr.iterate_allocated_regions();
-        let Some((mut to_cursor, mut to_size)) = to_iter.next() else {
+    /// Schedule the work packets that compute the new locations of the live objects, and the work
+    /// packets that move them.  The space is divided into compaction regions that are processed in
+    /// parallel:
+    ///
+    /// 1. The live objects of each region are laid out as if they slid to the start of the first
+    ///    live object of the region.
+    /// 2. The regions are assigned destinations in order, so that the live objects of all the
+    ///    regions slide towards the start of the space.  A region with pinned objects is laid out
+    ///    again from its destination, with its other objects placed around its pinned objects.
+    ///    With side forwarding, all the live objects of a forwarding block with pinned objects stay
+    ///    where they are, and the other objects are placed around the block instead.
+    /// 3. The forwarding pointers of the objects in each region are stored, or with side
+    ///    forwarding, the forwarding table entries of the blocks in each region.
+    /// 4. Each region is compacted once all the regions its destination may overlap have been
+    ///    compacted.
+    pub fn schedule_compaction(&'static self, scheduler: &GCWorkScheduler<VM>) {
+        let allocated: Vec<Range<Address>> = self
+            .pr
+            .iterate_allocated_regions()
+            .map(|(start, size)| start..start + size)
+            .collect();
+        let mut regions = vec![];
+        for (index, range) in allocated.iter().enumerate() {
+            let mut start = range.start;
+            while start < range.end {
+                let end = (start + BYTES_IN_COMPACTION_REGION).min(range.end);
+                regions.push(CompactionRegion::new(start..end, index));
+                start = end;
+            }
+        }
+        if regions.is_empty() {
+            self.compaction_top.store(Address::ZERO, Ordering::SeqCst);
             return;
-        };
-        let mut to_end = to_cursor + to_size;
-        for (from_start, size) in self.pr.iterate_allocated_regions() {
-            let from_end = from_start + size;
-            // linear scan the contiguous region
-            for obj in self
-                .linear_scan_objects(from_start..from_end)
-                .filter(Self::to_be_compacted)
-            {
-                let copied_size =
-                    VM::VMObjectModel::get_size_when_copied(obj) + Self::HEADER_RESERVED_IN_BYTES;
-                let align = VM::VMObjectModel::get_align_when_copied(obj);
-                let offset = VM::VMObjectModel::get_align_offset_when_copied(obj);
-                // move to_cursor to aliged start address
-                to_cursor = align_allocation_no_fill::<VM>(to_cursor, align, offset);
-                // move to next to-block if there is no sufficient memory in current region
-                if to_cursor + copied_size > to_end {
-                    (to_cursor, to_size) = to_iter.next().unwrap();
-                    to_end = to_cursor + to_size;
-                    to_cursor = align_allocation_no_fill::<VM>(to_cursor, align, offset);
-                    debug_assert!(to_cursor + copied_size <= to_end);
+        }
+        let compaction = Arc::new(Compaction {
+            space: self,
+            allocated,
+            regions,
+        });
+        let bucket = &scheduler.work_buckets[WorkBucketStage::CalculateForwarding];
+        bucket.set_sentinel(Box::new(AssignDestinations {
+            compaction: compaction.clone(),
+        }));
+        bucket.bulk_add(
+            (0..compaction.regions.len())
+                .map(|index| {
+                    Box::new(CalculateRegionLayout {
+                        compaction: compaction.clone(),
+                        index,
+                    }) as Box<dyn GCWork<VM>>
+                })
+                .collect(),
+        );
+    }
+
+    /// Lay out the live objects of a compaction region from `to_start`, or from where the first
+    /// live object is if `to_start` is `None`.  Objects keep their order.  Pinned objects (or with
+    /// side forwarding, the live objects of the forwarding blocks with pinned objects) stay where
+    /// they are, and the other objects are placed around them.  If `forward` is true, the
+    /// forwarding pointers of the objects are stored.  Return `None` if the region has no live
+    /// objects.
+    fn layout_region(
+        &self,
+        range: Range<Address>,
+        to_start: Option<Address>,
+        forward: bool,
+    ) -> Option<RegionLayout> {
+        #[cfg(feature = "mark_compact_side_forwarding")]
+        if forward {
+            Self::FORWARDING_TABLE.bzero_metadata(range.start, range.end - range.start);
+        }
+        let mut objects = self
+            .linear_scan_objects(range.clone())
+            .filter(Self::to_be_compacted)
+            .peekable();
+        let from_start = objects.peek()?.to_object_start::<VM>() - Self::HEADER_RESERVED_IN_BYTES;
+        let to_start = to_start.unwrap_or(from_start);
+        let mut to_cursor = to_start;
+        // The memory of the pinned objects that the to-cursor has not reached yet, in address
+        // order.  The to-cursor never passes the from-cursor, so it meets every pinned object after
+        // the from-cursor has found it.  With side forwarding, the pinned blocks are found in
+        // advance.
+        #[cfg(not(feature = "mark_compact_side_forwarding"))]
+        let mut pinned: VecDeque<Range<Address>> = VecDeque::new();
+        #[cfg(not(feature = "mark_compact_side_forwarding"))]
+        let mut has_pinned = false;
+        #[cfg(feature = "mark_compact_side_forwarding")]
+        let mut pinned = self.pinned_blocks(range.clone());
+        #[cfg(feature = "mark_compact_side_forwarding")]
+        let has_pinned = !pinned.is_empty();
+        #[cfg(feature = "mark_compact_side_forwarding")]
+        let mut pinned_blocks = pinned.clone();
+        for obj in objects {
+            #[cfg(not(feature = "mark_compact_side_forwarding"))]
+            if Self::is_pinned(obj) {
+                // The object is forwarded to itself.  Its header forwarding pointer is in the
+                // bytes reserved before the object.
+                let start = obj.to_object_start::<VM>() - Self::HEADER_RESERVED_IN_BYTES;
+                let end = obj.to_object_start::<VM>() + VM::VMObjectModel::get_current_size(obj);
+                if forward {
+                    Self::store_header_forwarding_pointer(obj, obj);
+                    trace!("Calculate forward: {} is pinned at {}..{}", obj, start, end);
+                }
+                has_pinned = true;
+                pinned.push_back(start..end);
+                continue;
+            }
+            #[cfg(feature = "mark_compact_side_forwarding")]
+            if Self::in_pinned_block(obj, &mut pinned_blocks) {
+                if forward {
+                    Self::FORWARDING_TABLE.store_atomic::<usize>(
+                        Self::forwarding_block(obj),
+                        IN_PLACE,
+                        Ordering::Relaxed,
+                    );
+                    trace!("Calculate forward: {} is in a pinned block", obj);
                 }
+                continue;
+            }
+            let copied_size =
+                VM::VMObjectModel::get_size_when_copied(obj) + Self::HEADER_RESERVED_IN_BYTES;
+            to_cursor = Self::place_object(obj, to_cursor, copied_size, &mut pinned);
+            if forward {
                 // Get copied object
-                let new_obj = VM::VMObjectModel::get_reference_when_copied_to(
-                    obj,
-                    to_cursor + Self::HEADER_RESERVED_IN_BYTES,
-                );
-                // update forwarding pointer
-                Self::store_header_forwarding_pointer(obj, new_obj);
+                #[cfg(not(feature = "mark_compact_side_forwarding"))]
+                {
+                    let new_obj = VM::VMObjectModel::get_reference_when_copied_to(
+                        obj,
+                        to_cursor + Self::HEADER_RESERVED_IN_BYTES,
+                    );
+                    // update forwarding pointer
+                    Self::store_header_forwarding_pointer(obj, new_obj);
+                }
+                #[cfg(feature = "mark_compact_side_forwarding")]
+                Self::store_side_forwarding_entry(obj, to_cursor.as_usize());
                 trace!(
                     "Calculate forward: {} (size when copied = {}) ~> {} (size = {})",
                     obj,
@@ -390,45 +699,126 @@ impl<VM: VMBinding> MarkCompactSpace<VM> {
                     to_cursor,
                     copied_size
                 );
-                // bump to_cursor
-                to_cursor += copied_size;
             }
+            // bump to_cursor
+            to_cursor += copied_size;
         }
+        // Pinned objects beyond the last moved object are still live.
+        let to_end = pinned
+            .back()
+            .map_or(to_cursor, |last_pinned| last_pinned.end);
+        Some(RegionLayout {
+            to_start,
+            to_end,
+            has_pinned,
+        })
     }
 
-    pub fn compact(&self) {
-        let mut to = Address::ZERO;
-        for (from_start, size) in self.pr.iterate_allocated_regions() {
-            let from_end = from_start + size;
-            for obj in self.linear_scan_objects(from_start..from_end) {
-                let copied_size = VM::VMObjectModel::get_size_when_copied(obj);
-                // clear the VO bit
-                vo_bit::unset_vo_bit(obj);
+    /// Move `to_cursor` to where an object of `copied_size` bytes is placed.  `pinned` holds the
+    /// memory of the pinned objects that `to_cursor` has not reached yet, in address order.  The
+    /// object is placed after the pinned objects that are in the way.
+    fn place_object(
+        object: ObjectReference,
+        mut to_cursor: Address,
+        copied_size: usize,
+        pinned: &mut VecDeque<Range<Address>>,
+    ) -> Address {
+        loop {
+            // move to_cursor to aliged start address
+            to_cursor = Self::align_to_cursor(object, to_cursor);
+            // skip the next pinned object if it is in the way
+            match pinned.front() {
+                Some(next_pinned) if to_cursor + copied_size > next_pinned.start => {
+                    to_cursor = next_pinned.end;
+                    pinned.pop_front();
+                }
+                _ => return to_cursor,
+            }
+        }
+    }
 
-                let maybe_forwarding_pointer = Self::get_header_forwarding_pointer(obj);
-                if let Some(forwarding_pointer) = maybe_forwarding_pointer {
-                    trace!("Compact {} to {}", obj, forwarding_pointer);
-                    let new_object = forwarding_pointer;
-                    Self::clear_header_forwarding_pointer(new_object);
+    /// With side forwarding, check if a live object is in one of the pinned blocks found by
+    /// [`Self::pinned_blocks`].  The objects must be checked in address order, and the blocks
+    /// before the object are removed.
+    #[cfg(feature = "mark_compact_side_forwarding")]
+    fn in_pinned_block(
+        object: ObjectReference,
+        pinned_blocks: &mut VecDeque<Range<Address>>,
+    ) -> bool {
+        let start = object.to_object_start::<VM>();
+        while let Some(block) = pinned_blocks.front() {
+            if start < block.start {
+                return false;
+            }
+            if start < block.end {
+                return true;
+            }
+            pinned_blocks.pop_front();
+        }
+        false
+    }
+
+    /// Move the live objects of a compaction region to their forwarding pointers, in address
+    /// order.
+    fn compact_region(&self, region: &CompactionRegion) {
+        // With side forwarding, the forwarding pointers are computed again in address order, from
+        // where the region is moved to.  The pinned blocks are found before any object is moved.
+        #[cfg(feature = "mark_compact_side_forwarding")]
+        let mut pinned = self.pinned_blocks(region.range.clone());
+        #[cfg(feature = "mark_compact_side_forwarding")]
+        let mut pinned_blocks = pinned.clone();
+        #[cfg(feature = "mark_compact_side_forwarding")]
+        let mut to_cursor = region.to_start.load(Ordering::Relaxed);
+        for obj in self.linear_scan_objects(region.range.clone()) {
+            let copied_size = VM::VMObjectModel::get_size_when_copied(obj);
+            // clear the VO bit
+            vo_bit::unset_vo_bit(obj);
+
+            #[cfg(not(feature = "mark_compact_side_forwarding"))]
+            let maybe_forwarding_pointer = Self::get_header_forwarding_pointer(obj);
+            #[cfg(feature = "mark_compact_side_forwarding")]
+            let maybe_forwarding_pointer = Self::is_marked(obj).then(|| {
+                // Clear the bits before the object is copied, in case they are in its header.
+                Self::test_and_clear_mark(obj);
+                Self::FORWARDED_BIT.store_atomic::<u8>(obj.to_raw_address(), 0, Ordering::SeqCst);
+                if Self::in_pinned_block(obj, &mut pinned_blocks) {
+                    return obj;
+                }
+                to_cursor = Self::place_object(obj, to_cursor, copied_size, &mut pinned);
+                let new_object = VM::VMObjectModel::get_reference_when_copied_to(obj, to_cursor);
+                to_cursor += copied_size;
+                new_object
+            });
+            if let Some(forwarding_pointer) = maybe_forwarding_pointer {
+                trace!("Compact {} to {}", obj, forwarding_pointer);
+                let new_object = forwarding_pointer;
+                #[cfg(not(feature = "mark_compact_side_forwarding"))]
+                Self::clear_header_forwarding_pointer(new_object);
 
+                if Self::is_pinned(obj) {
+                    debug_assert_eq!(new_object, obj);
+                    trace!(" {} is pinned", obj);
+                } else if cfg!(feature = "mark_compact_side_forwarding") && new_object == obj {
+                    trace!(" {} is not moved", obj);
+                } else {
                     // copy object
                     trace!(" copy from {} to {}", obj, new_object);
                     let end_of_new_object =
                         VM::VMObjectModel::copy_to(obj, new_object, Address::ZERO);
-                    // update VO bit,
-                    vo_bit::set_vo_bit(new_object);
-                    to = new_object.to_object_start::<VM>() + copied_size;
-                    debug_assert_eq!(end_of_new_object, to);
-                } else {
-                    trace!("Skipping dead object {}", obj);
+                    debug_assert_eq!(
+                        end_of_new_object,
+                        new_object.to_object_start::<VM>() + copied_size
+                    );
                 }
+                // update VO bit,
+                vo_bit::set_vo_bit(new_object);
+            } else {
+                // Objects allocated later in its place should not be pinned.
+                #[cfg(feature = "object_pinning")]
+                VM::VMObjectModel::LOCAL_PINNING_BIT_SPEC.unpin_object::<VM>(obj);
+                trace!("Skipping dead object {}", obj);
             }
         }
-
-        debug!("Compact end: to = {}", to);
-
-        // reset the bump pointer
-        self.pr.reset_cursor(to);
     }
 }
 
@@ -438,3 +828,214 @@ impl<VM: VMBinding> crate::util::linear_scan::LinearScanObjectSize for MarkCompa
         VM::VMObjectModel::get_current_size(object)
     }
 }
+
+/// The size of a compaction region.  It is larger than the largest object in the space, so an
+/// object overlaps at most two regions.
+const BYTES_IN_COMPACTION_REGION: usize =
+    1 << (crate::util::heap::layout::vm_layout::LOG_BYTES_IN_CHUNK - 4);
+
+/// The layout of the live objects of a compaction region after compaction.
+#[derive(Clone, Copy, Debug)]
+struct RegionLayout {
+    /// The start of the first live object, including its reserved header bytes.
+    to_start: Address,
+    /// The end of the last live object.
+    to_end: Address,
+    /// Whether any live object of the region is pinned.  The layout of such a region depends on
+    /// where it starts.
+    has_pinned: bool,
+}
+
+/// A part of the space whose objects are forwarded and moved by the same work packets.  An object
+/// belongs to the region that contains its object reference.
+struct CompactionRegion {
+    range: Range<Address>,
+    /// The index of the allocated memory that contains this region.
+    allocated: usize,
+    /// The layout of the live objects if they slid to the start of the first live object.
+    layout: Mutex<Option<RegionLayout>>,
+    /// Where the live objects are moved to, or zero if the region has no live objects.
+    to_start: Atomic<Address>,
+    /// The regions from this index up to (but excluding) this region must be compacted before
+    /// this region, because the destination of this region may overlap their objects.  This is
+    /// non-decreasing across regions.
+    first_dependency: AtomicUsize,
+    /// The number of the regions above that have not been compacted yet.
+    pending_dependencies: AtomicUsize,
+}
+
+impl CompactionRegion {
+    fn new(range: Range<Address>, allocated: usize) -> Self {
+        Self {
+            range,
+            allocated,
+            layout: Mutex::new(None),
+            to_start: Atomic::new(Address::ZERO),
+            first_dependency: AtomicUsize::new(0),
+            pending_dependencies: AtomicUsize::new(0),
+        }
+    }
+}
+
+/// The compaction of a MarkCompactSpace in a GC, shared by its work packets.
+struct Compaction<VM: VMBinding> {
+    space: &'static MarkCompactSpace<VM>,
+    /// The memory allocated to the space, in the order that live objects slide towards.
+    allocated: Vec<Range<Address>>,
+    /// The compaction regions of all the allocated memory, in the same order.
+    regions: Vec<CompactionRegion>,
+}
+
+/// Lay out the live objects of a compaction region without storing forwarding pointers.
+struct CalculateRegionLayout<VM: VMBinding> {
+    compaction: Arc<Compaction<VM>>,
+    index: usize,
+}
+
+impl<VM: VMBinding> GCWork<VM> for CalculateRegionLayout<VM> {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let region = &self.compaction.regions[self.index];
+        let layout = self
+            .compaction
+            .space
+            .layout_region(region.range.clone(), None, false);
+        *region.layout.lock().unwrap() = layout;
+    }
+}
+
+/// Assign the destinations of all the compaction regions in order.  This is the sentinel of the
+/// `CalculateForwarding` bucket after all the regions are laid out.
+struct AssignDestinations<VM: VMBinding> {
+    compaction: Arc<Compaction<VM>>,
+}
+
+impl<VM: VMBinding> GCWork<VM> for AssignDestinations<VM> {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let compaction = &self.compaction;
+        let regions = &compaction.regions;
+        let mut to_index = 0;
+        let mut to_cursor = compaction.allocated[0].start;
+        let mut top = Address::ZERO;
+        // The first region whose memory the current destination may overlap.
+        let mut overlapped = 0;
+        let mut first_dependency = 0;
+        let mut forward_packets: Vec<Box<dyn GCWork<VM>>> = vec![];
+        let mut compact_packets: Vec<Box<dyn GCWork<VM>>> = vec![];
+        for (index, region) in regions.iter().enumerate() {
+            let mut pending_dependencies = 0;
+            if let Some(layout) = *region.layout.lock().unwrap() {
+                let (to_start, to_end) = if layout.has_pinned {
+                    debug_assert!(to_index < region.allocated || to_cursor <= layout.to_start);
+                    // Where the objects are placed around the pinned objects depends on where the
+                    // region starts, so the region is laid out again.
+                    if to_index < region.allocated {
+                        to_index = region.allocated;
+                        to_cursor = compaction.allocated[to_index].start;
+                    }
+                    let layout = compaction
+                        .space
+                        .layout_region(region.range.clone(), Some(to_cursor), false)
+                        .unwrap();
+                    (layout.to_start, layout.to_end)
+                } else {
+                    let size = layout.to_end - layout.to_start;
+                    loop {
+                        // Laid out from an address that differs from the original layout by a
+                        // multiple of the maximum alignment, the objects need the same amount of
+                        // memory.  Laid out from a lower address, they need no more than that.
+                        let to_end = to_cursor
+                            + (layout
+                                .to_start
+                                .as_usize()
+                                .wrapping_sub(to_cursor.as_usize())
+                                & (VM::MAX_ALIGNMENT - 1))
+                            + size;
+                        if to_end <= compaction.allocated[to_index].end {
+                            break (to_cursor, to_end);
+                        }
+                        // move to next to-block if there is no sufficient memory in current region
+                        to_index += 1;
+                        to_cursor = compaction.allocated[to_index].start;
+                    }
+                };
+                debug_assert!(to_index <= region.allocated);
+                to_cursor = to_end;
+                top = to_cursor;
+                region.to_start.store(to_start, Ordering::Relaxed);
+                while !(regions[overlapped].allocated == to_index
+                    && regions[overlapped].range.contains(&to_start))
+                {
+                    overlapped += 1;
+                }
+                // The object that overlaps the destination may start in the previous region.
+                first_dependency = overlapped.saturating_sub(1);
+                pending_dependencies = index - first_dependency;
+                forward_packets.push(Box::new(ForwardRegion {
+                    compaction: compaction.clone(),
+                    index,
+                }));
+            }
+            region
+                .first_dependency
+                .store(first_dependency, Ordering::Relaxed);
+            region
+                .pending_dependencies
+                .store(pending_dependencies, Ordering::Relaxed);
+            if pending_dependencies == 0 {
+                compact_packets.push(Box::new(CompactRegion {
+                    compaction: compaction.clone(),
+                    index,
+                }));
+            }
+        }
+        compaction.space.compaction_top.store(top, Ordering::SeqCst);
+        let scheduler = worker.scheduler();
+        scheduler.work_buckets[WorkBucketStage::CalculateForwarding].bulk_add(forward_packets);
+        scheduler.work_buckets[WorkBucketStage::Compact].bulk_add(compact_packets);
+    }
+}
+
+/// Store the forwarding pointers of the live objects of a compaction region.
+struct ForwardRegion<VM: VMBinding> {
+    compaction: Arc<Compaction<VM>>,
+    index: usize,
+}
+
+impl<VM: VMBinding> GCWork<VM> for ForwardRegion<VM> {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let region = &self.compaction.regions[self.index];
+        let to_start = region.to_start.load(Ordering::Relaxed);
+        self.compaction
+            .space
+            .layout_region(region.range.clone(), Some(to_start), true);
+    }
+}
+
+/// Compact a compaction region, and then schedule the regions that are waiting for it.
+struct CompactRegion<VM: VMBinding> {
+    compaction: Arc<Compaction<VM>>,
+    index: usize,
+}
+
+impl<VM: VMBinding> GCWork<VM> for CompactRegion<VM> {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let regions = &self.compaction.regions;
+        self.compaction.space.compact_region(&regions[self.index]);
+        for (index, region) in regions.iter().enumerate().skip(self.index + 1) {
+            if region.first_dependency.load(Ordering::Relaxed) > self.index {
+                break;
+            }
+            if !region.to_start.load(Ordering::Relaxed).is_zero()
+                && region.pending_dependencies.fetch_sub(1, Ordering::SeqCst) == 1
+            {
+                worker.add_work(
+                    WorkBucketStage::Compact,
+                    CompactRegion {
+                        compaction: self.compaction.clone(),
+                        index,
+                    },
+                );
+            }
+        }
+    }
+}
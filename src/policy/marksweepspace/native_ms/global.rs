@@ -1,5 +1,5 @@
 use std::sync::{
-    atomic::{AtomicUsize, Ordering},
+    atomic::{AtomicBool, AtomicUsize, Ordering},
     Arc,
 };
 
@@ -24,6 +24,7 @@ use crate::plan::ObjectQueue;
 use crate::plan::VectorObjectQueue;
 use crate::policy::sft::SFT;
 use crate::policy::space::{CommonSpace, Space};
+use crate::util::alloc::AllocationError;
 use crate::util::constants::LOG_BYTES_IN_PAGE;
 use crate::util::heap::chunk_map::*;
 use crate::util::linear_scan::Region;
@@ -34,7 +35,8 @@ use std::sync::Mutex;
 
 /// The result for `MarkSweepSpace.acquire_block()`. `MarkSweepSpace` will attempt
 /// to allocate from abandoned blocks first. If none found, it will get a new block
-/// from the page resource.
+/// from the page resource. `acquire_block()` returns an error instead if the allocation
+/// must fail (see [`Space::acquire`]).
 pub enum BlockAcquireResult {
     Exhausted,
     /// A new block we just acquired from the page resource
@@ -85,6 +87,26 @@ pub struct MarkSweepSpace<VM: VMBinding> {
     /// Count the number of pending `ReleaseMarkSweepSpace` and `ReleaseMutator` work packets during
     /// the `Release` stage.
     pending_release_packets: AtomicUsize,
+    /// True between `prepare` and `release`.  Mark bits are cleared in `prepare`, so unswept blocks
+    /// must not be swept (e.g. by GC workers copying objects into this space) until `release`.
+    marking: AtomicBool,
+    /// The number of pages in the blocks that allocators have acquired since the last GC.
+    pages_allocated: AtomicUsize,
+    /// Some settings for this space.
+    space_args: MarkSweepSpaceArgs,
+}
+
+/// Some arguments for mark sweep space.
+#[derive(Default)]
+pub struct MarkSweepSpaceArgs {
+    /// Mark an object as unlogged when we trace it.  In sticky mark sweep, an object becomes a
+    /// mature object when it is traced, and it needs to be unlogged so that the write barrier
+    /// remembers it when it is modified.
+    pub unlog_object_when_traced: bool,
+    /// Reset log bits at the start of a full heap GC.  In sticky mark sweep, nursery objects and
+    /// mature objects are in the same space, and the log bit tells them apart.  Dead mature objects
+    /// must not leave their unlogged bits to new objects allocated in their cells.
+    pub reset_log_bit_in_major_gc: bool,
 }
 
 unsafe impl<VM: VMBinding> Sync for MarkSweepSpace<VM> {}
@@ -190,9 +212,22 @@ impl<VM: VMBinding> SFT for MarkSweepSpace<VM> {
         true
     }
 
-    fn initialize_object_metadata(&self, _object: crate::util::ObjectReference, _alloc: bool) {
+    fn initialize_object_metadata(&self, object: crate::util::ObjectReference, _alloc: bool) {
+        if self.space_args.reset_log_bit_in_major_gc
+            && VM::VMObjectModel::GLOBAL_LOG_BIT_SPEC.is_in_header()
+        {
+            // We cannot bulk zero log bits in object headers in major GC (see `PrepareChunkMap`).
+            // Instead, we clear the log bit for new objects, which may reuse the header of a dead
+            // mature object.
+            VM::VMObjectModel::GLOBAL_LOG_BIT_SPEC.store_atomic::<VM, u8>(
+                object,
+                0,
+                None,
+                Ordering::SeqCst,
+            );
+        }
         #[cfg(feature = "vo_bit")]
-        crate::util::metadata::vo_bit::set_vo_bit(_object);
+        crate::util::metadata::vo_bit::set_vo_bit(object);
     }
 
     #[cfg(feature = "is_mmtk_object")]
@@ -284,6 +319,19 @@ impl<VM: VMBinding> MarkSweepSpace<VM> {
     }
 
     pub fn new(args: crate::policy::space::PlanCreateSpaceArgs<VM>) -> MarkSweepSpace<VM> {
+        Self::new_with_args(args, MarkSweepSpaceArgs::default())
+    }
+
+    pub fn new_with_args(
+        args: crate::policy::space::PlanCreateSpaceArgs<VM>,
+        space_args: MarkSweepSpaceArgs,
+    ) -> MarkSweepSpace<VM> {
+        if space_args.unlog_object_when_traced || space_args.reset_log_bit_in_major_gc {
+            assert!(
+                args.constraints.needs_log_bit,
+                "Invalid args when the plan does not use log bit"
+            );
+        }
         let scheduler = args.scheduler.clone();
         let vm_map = args.vm_map;
         let is_discontiguous = args.vmrequest.is_discontiguous();
@@ -327,6 +375,9 @@ impl<VM: VMBinding> MarkSweepSpace<VM> {
             abandoned: Mutex::new(AbandonedBlockLists::new()),
             abandoned_in_gc: Mutex::new(AbandonedBlockLists::new()),
             pending_release_packets: AtomicUsize::new(0),
+            marking: AtomicBool::new(false),
+            pages_allocated: AtomicUsize::new(0),
+            space_args,
         }
     }
 
@@ -395,6 +446,10 @@ impl<VM: VMBinding> MarkSweepSpace<VM> {
         if self.attempt_mark(object) {
             let block = Block::containing(object);
             block.set_state(BlockState::Marked);
+            if self.space_args.unlog_object_when_traced {
+                VM::VMObjectModel::GLOBAL_LOG_BIT_SPEC
+                    .mark_as_unlogged::<VM>(object, Ordering::SeqCst);
+            }
             queue.enqueue(object);
         }
         object
@@ -405,24 +460,48 @@ impl<VM: VMBinding> MarkSweepSpace<VM> {
         self.chunk_map.set(block.chunk(), ChunkState::Allocated);
     }
 
-    pub fn prepare(&mut self) {
+    /// Prepare the space for a GC.  If `full_heap` is false, the mark bits and the block marks of
+    /// the last GC are kept (i.e. they are sticky), and only unmarked objects are traced.
+    pub fn prepare(&mut self, full_heap: bool) {
         #[cfg(debug_assertions)]
         self.abandoned_in_gc.lock().unwrap().assert_empty();
 
-        // # Safety: MarkSweepSpace reference is always valid within this collection cycle.
-        let space = unsafe { &*(self as *const Self) };
-        let work_packets = self
-            .chunk_map
-            .generate_tasks(|chunk| Box::new(PrepareChunkMap { space, chunk }));
-        self.scheduler.work_buckets[crate::scheduler::WorkBucketStage::Prepare]
-            .bulk_add(work_packets);
+        if full_heap {
+            // # Safety: MarkSweepSpace reference is always valid within this collection cycle.
+            let space = unsafe { &*(self as *const Self) };
+            let work_packets = self
+                .chunk_map
+                .generate_tasks(|chunk| Box::new(PrepareChunkMap { space, chunk }));
+            self.scheduler.work_buckets[crate::scheduler::WorkBucketStage::Prepare]
+                .bulk_add(work_packets);
+        }
+        self.marking.store(true, Ordering::SeqCst);
+        self.pages_allocated.store(0, Ordering::SeqCst);
     }
 
     pub fn release(&mut self) {
-        let num_mutators = VM::VMActivePlan::number_of_mutators();
-        // all ReleaseMutator work packets plus the ReleaseMarkSweepSpace packet
+        // Each mutator has a free list allocator that does release work in `ReleaseMutator`.
+        self.release_with_local_allocators(VM::VMActivePlan::number_of_mutators());
+    }
+
+    /// Like `release`, but the free list allocators that do release work are the copy allocators of
+    /// GC workers (see [`MarkSweepCopyContext`]) instead of those of mutators.  This is used by
+    /// generational mark sweep, where mutators never allocate into this space.
+    ///
+    /// Only the workers that take part in the current GC execute `ReleaseCollector`.  The other
+    /// workers have not copied objects since the last GC they took part in, at the end of which
+    /// their allocators gave up all their blocks.
+    pub fn release_with_copy_allocators(&mut self) {
+        self.release_with_local_allocators(self.scheduler.num_active_workers());
+    }
+
+    /// `num_local_allocators` is the number of free list allocators that will call
+    /// `FreeListAllocator::release` in this GC.
+    fn release_with_local_allocators(&mut self, num_local_allocators: usize) {
+        self.marking.store(false, Ordering::SeqCst);
+        // all local allocators plus the ReleaseMarkSweepSpace packet
         self.pending_release_packets
-            .store(num_mutators + 1, Ordering::SeqCst);
+            .store(num_local_allocators + 1, Ordering::SeqCst);
 
         // Do work in separate work packet in order not to slow down the `Release` work packet which
         // blocks all `ReleaseMutator` packets.
@@ -431,6 +510,22 @@ impl<VM: VMBinding> MarkSweepSpace<VM> {
         self.scheduler.work_buckets[crate::scheduler::WorkBucketStage::Release].add(work_packet);
     }
 
+    /// Return true if the object is marked.
+    pub fn is_marked(&self, object: ObjectReference) -> bool {
+        VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.is_marked::<VM>(object, Ordering::SeqCst)
+    }
+
+    /// Return the number of pages in the blocks that allocators have acquired since the last GC.
+    pub(crate) fn get_pages_allocated(&self) -> usize {
+        self.pages_allocated.load(Ordering::SeqCst)
+    }
+
+    /// Return true if the space is being released in the current GC, and local allocators need to
+    /// do release work for their blocks.
+    pub fn is_releasing(&self) -> bool {
+        self.pending_release_packets.load(Ordering::SeqCst) != 0
+    }
+
     pub fn end_of_gc(&mut self) {
         epilogue::debug_assert_counter_zero(
             &self.pending_release_packets,
@@ -454,7 +549,12 @@ impl<VM: VMBinding> MarkSweepSpace<VM> {
         crate::util::metadata::vo_bit::bzero_vo_bit(block.start(), Block::BYTES);
     }
 
-    pub fn acquire_block(&self, tls: VMThread, size: usize, align: usize) -> BlockAcquireResult {
+    pub fn acquire_block(
+        &self,
+        tls: VMThread,
+        size: usize,
+        align: usize,
+    ) -> Result<BlockAcquireResult, AllocationError> {
         {
             let mut abandoned = self.abandoned.lock().unwrap();
             let bin = mi_bin::<VM>(size, align);
@@ -463,24 +563,33 @@ impl<VM: VMBinding> MarkSweepSpace<VM> {
                 let abandoned_available = &mut abandoned.available;
                 if !abandoned_available[bin].is_empty() {
                     let block = abandoned_available[bin].pop().unwrap();
-                    return BlockAcquireResult::AbandonedAvailable(block);
+                    self.pages_allocated
+                        .fetch_add(1 << Block::LOG_PAGES, Ordering::SeqCst);
+                    return Ok(BlockAcquireResult::AbandonedAvailable(block));
                 }
             }
 
-            {
+            // We cannot sweep blocks while their mark bits are being computed.
+            if !self.marking.load(Ordering::SeqCst) {
                 let abandoned_unswept = &mut abandoned.unswept;
                 if !abandoned_unswept[bin].is_empty() {
                     let block = abandoned_unswept[bin].pop().unwrap();
-                    return BlockAcquireResult::AbandonedUnswept(block);
+                    self.pages_allocated
+                        .fetch_add(1 << Block::LOG_PAGES, Ordering::SeqCst);
+                    return Ok(BlockAcquireResult::AbandonedUnswept(block));
                 }
             }
         }
 
-        let acquired = self.acquire(tls, Block::BYTES >> LOG_BYTES_IN_PAGE);
+        let acquired = self.acquire(tls, Block::BYTES >> LOG_BYTES_IN_PAGE)?;
         if acquired.is_zero() {
-            BlockAcquireResult::Exhausted
+            Ok(BlockAcquireResult::Exhausted)
         } else {
-            BlockAcquireResult::Fresh(Block::from_unaligned_address(acquired))
+            self.pages_allocated
+                .fetch_add(1 << Block::LOG_PAGES, Ordering::SeqCst);
+            Ok(BlockAcquireResult::Fresh(Block::from_unaligned_address(
+                acquired,
+            )))
         }
     }
 
@@ -579,6 +688,14 @@ impl<VM: VMBinding> GCWork<VM> for PrepareChunkMap<VM> {
             if let MetadataSpec::OnSide(side) = *VM::VMObjectModel::LOCAL_MARK_BIT_SPEC {
                 side.bzero_metadata(self.chunk.start(), Chunk::BYTES);
             }
+            if self.space.space_args.reset_log_bit_in_major_gc {
+                // We zero all the log bits, and unlog every object we trace again.  If the log bit
+                // is in the header, we cannot bulk zero it.  Instead, we clear the log bit for new
+                // objects in `initialize_object_metadata`.
+                if let MetadataSpec::OnSide(side) = *VM::VMObjectModel::GLOBAL_LOG_BIT_SPEC {
+                    side.bzero_metadata(self.chunk.start(), Chunk::BYTES);
+                }
+            }
         }
     }
 }
@@ -652,3 +769,63 @@ impl<VM: VMBinding> Drop for RecycleBlocks<VM> {
         epilogue::debug_assert_counter_zero(&self.counter, "RecycleBlocks::counter");
     }
 }
+
+use crate::policy::copy_context::PolicyCopyContext;
+use crate::util::alloc::allocator::AllocatorContext;
+use crate::util::alloc::Allocator;
+use crate::util::alloc::FreeListAllocator;
+use crate::util::opaque_pointer::VMWorkerThread;
+
+/// Mark sweep copy context. It has one free list allocator, and is used for promoting objects
+/// from the nursery to the mark sweep space in generational mark sweep.
+///
+/// The allocator gives up its blocks at the end of every GC.  In a GC that releases the space, it
+/// does the same release work as the allocator of a mutator.  In other GCs, it gives its blocks to
+/// the abandoned block lists of the space.  Either way, a worker that does not take part in a later
+/// GC holds no blocks that need release work.
+pub struct MarkSweepCopyContext<VM: VMBinding> {
+    allocator: FreeListAllocator<VM>,
+    space: &'static MarkSweepSpace<VM>,
+}
+
+impl<VM: VMBinding> PolicyCopyContext for MarkSweepCopyContext<VM> {
+    type VM = VM;
+
+    fn prepare(&mut self) {
+        self.allocator.prepare();
+    }
+    fn release(&mut self) {
+        if self.space.is_releasing() {
+            self.allocator.release();
+        } else {
+            self.allocator.abandon_all_blocks();
+        }
+    }
+    fn alloc_copy(
+        &mut self,
+        _original: ObjectReference,
+        bytes: usize,
+        align: usize,
+        offset: usize,
+    ) -> crate::util::Address {
+        self.allocator.alloc(bytes, align, offset)
+    }
+    fn post_copy(&mut self, obj: ObjectReference, _bytes: usize) {
+        // The copied object is live in this GC.
+        VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.mark::<VM>(obj, Ordering::SeqCst);
+        Block::containing(obj).set_state(BlockState::Marked);
+    }
+}
+
+impl<VM: VMBinding> MarkSweepCopyContext<VM> {
+    pub(crate) fn new(
+        tls: VMWorkerThread,
+        context: Arc<AllocatorContext<VM>>,
+        space: &'static MarkSweepSpace<VM>,
+    ) -> Self {
+        MarkSweepCopyContext {
+            allocator: FreeListAllocator::new(tls.0, space, context),
+            space,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::util::constants::LOG_BYTES_IN_PAGE;
+use crate::util::heap::chunk_map::Chunk;
+use crate::util::linear_scan::Region;
+use crate::util::metadata::side_metadata::SideMetadataSpec;
+use crate::util::object_enum::BlockMayHaveObjects;
+use crate::util::Address;
+use std::sync::atomic::Ordering;
+
+/// The allocation state of a heap region.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RegionState {
+    /// The region is not allocated.
+    Unallocated,
+    /// The region holds objects allocated by mutators since the last GC.
+    Young,
+    /// The region holds objects that survived at least one GC.
+    Old,
+}
+
+impl RegionState {
+    /// Private constant
+    const UNALLOCATED: u8 = 0;
+    /// Private constant
+    const YOUNG: u8 = 1;
+    /// Private constant
+    const OLD: u8 = 2;
+    /// The bit that is set in the state byte if the region is in the collection set.
+    const IN_COLLECTION_SET: u8 = 1 << 7;
+}
+
+impl From<u8> for RegionState {
+    fn from(state: u8) -> Self {
+        match state & !Self::IN_COLLECTION_SET {
+            Self::UNALLOCATED => RegionState::Unallocated,
+            Self::YOUNG => RegionState::Young,
+            Self::OLD => RegionState::Old,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl From<RegionState> for u8 {
+    fn from(state: RegionState) -> Self {
+        match state {
+            RegionState::Unallocated => RegionState::UNALLOCATED,
+            RegionState::Young => RegionState::YOUNG,
+            RegionState::Old => RegionState::OLD,
+        }
+    }
+}
+
+/// Data structure to reference a heap region. A heap region is a fixed-size unit of allocation
+/// and collection in [`super::RegionSpace`].
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Eq, Hash)]
+pub struct HeapRegion(Address);
+
+impl Region for HeapRegion {
+    const LOG_BYTES: usize = 18;
+
+    fn from_aligned_address(address: Address) -> Self {
+        debug_assert!(address.is_aligned_to(Self::BYTES));
+        Self(address)
+    }
+
+    fn start(&self) -> Address {
+        self.0
+    }
+}
+
+impl BlockMayHaveObjects for HeapRegion {
+    fn may_have_objects(&self) -> bool {
+        self.get_state() != RegionState::Unallocated
+    }
+}
+
+impl HeapRegion {
+    /// Log pages in region
+    pub const LOG_PAGES: usize = Self::LOG_BYTES - LOG_BYTES_IN_PAGE as usize;
+    /// Pages in region
+    pub const PAGES: usize = 1 << Self::LOG_PAGES;
+
+    /// Region state table (side)
+    pub const STATE_TABLE: SideMetadataSpec =
+        crate::util::metadata::side_metadata::spec_defs::RS_REGION_STATE;
+
+    /// Region live bytes table (side)
+    pub const LIVE_BYTES_TABLE: SideMetadataSpec =
+        crate::util::metadata::side_metadata::spec_defs::RS_REGION_LIVE_BYTES;
+
+    /// Get the chunk containing the region.
+    pub fn chunk(&self) -> Chunk {
+        Chunk::from_unaligned_address(self.0)
+    }
+
+    /// Get the region state.
+    pub fn get_state(&self) -> RegionState {
+        Self::STATE_TABLE
+            .load_atomic::<u8>(self.start(), Ordering::SeqCst)
+            .into()
+    }
+
+    /// Set the region state. This also removes the region from the collection set.
+    pub fn set_state(&self, state: RegionState) {
+        Self::STATE_TABLE.store_atomic::<u8>(self.start(), state.into(), Ordering::SeqCst);
+    }
+
+    /// Is the region in the collection set of the current GC? Objects in the collection set are
+    /// evacuated, and the region is released at the end of the GC.
+    pub fn in_collection_set(&self) -> bool {
+        Self::STATE_TABLE.load_atomic::<u8>(self.start(), Ordering::SeqCst)
+            & RegionState::IN_COLLECTION_SET
+            != 0
+    }
+
+    /// Add the region to the collection set.
+    pub fn add_to_collection_set(&self) {
+        debug_assert_ne!(self.get_state(), RegionState::Unallocated);
+        let state = u8::from(self.get_state()) | RegionState::IN_COLLECTION_SET;
+        Self::STATE_TABLE.store_atomic::<u8>(self.start(), state, Ordering::SeqCst);
+    }
+
+    /// Get the number of live bytes in the region. For old regions, this is the number of bytes
+    /// marked in the last full heap GC plus the bytes copied into the region since then.
+    pub fn get_live_bytes(&self) -> usize {
+        Self::LIVE_BYTES_TABLE.load_atomic::<usize>(self.start(), Ordering::SeqCst)
+    }
+
+    /// Set the number of live bytes in the region.
+    pub fn set_live_bytes(&self, bytes: usize) {
+        Self::LIVE_BYTES_TABLE.store_atomic::<usize>(self.start(), bytes, Ordering::SeqCst);
+    }
+
+    /// Add to the number of live bytes in the region.
+    pub fn add_live_bytes(&self, bytes: usize) {
+        Self::LIVE_BYTES_TABLE.fetch_add_atomic::<usize>(self.start(), bytes, Ordering::SeqCst);
+    }
+
+    /// Initialize a clean region after acquired from page-resource.
+    pub fn init(&self, state: RegionState) {
+        self.set_state(state);
+        self.set_live_bytes(0);
+    }
+
+    /// Deinitalize a region before releasing.
+    pub fn deinit(&self) {
+        self.set_state(RegionState::Unallocated);
+        self.set_live_bytes(0);
+    }
+}
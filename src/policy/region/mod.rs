@@ -0,0 +1,19 @@
+//! A region-based space for G1-style collectors.
+//!
+//! The space is divided into fixed-size [`HeapRegion`]s. Mutators allocate into young regions,
+//! and a GC evacuates a collection set of regions into fresh old regions. A collection set
+//! includes all the young regions, and may include some old regions. Each old region has a
+//! remembered set that records the objects outside the region that may point into it, so old
+//! regions can be evacuated without tracing the whole heap.
+
+pub mod heapregion;
+pub mod regionspace;
+
+pub use heapregion::*;
+pub use regionspace::*;
+
+use crate::util::linear_scan::Region;
+
+/// The max object size for the region space: half of a region. Larger objects are allocated in
+/// the large object space.
+pub const MAX_REGION_OBJECT_SIZE: usize = HeapRegion::BYTES >> 1;
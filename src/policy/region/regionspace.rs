@@ -0,0 +1,847 @@
+use super::heapregion::*;
+use crate::plan::{ObjectQueue, VectorObjectQueue};
+use crate::policy::copy_context::PolicyCopyContext;
+use crate::policy::gc_work::{TraceKind, TRACE_KIND_TRANSITIVE_PIN};
+use crate::policy::sft::GCWorkerMutRef;
+use crate::policy::sft::SFT;
+use crate::policy::sft_map::SFTMap;
+use crate::policy::space::{CommonSpace, Space};
+use crate::scheduler::gc_work::{ProcessEdgesWork, ScanObjects};
+use crate::scheduler::{GCWork, GCWorkScheduler, GCWorker, WorkBucketStage};
+use crate::util::alloc::allocator::AllocatorContext;
+use crate::util::alloc::{AllocationError, Allocator, RegionAllocator};
+use crate::util::copy::*;
+use crate::util::heap::chunk_map::*;
+use crate::util::heap::BlockPageResource;
+use crate::util::heap::PageResource;
+use crate::util::linear_scan::Region;
+use crate::util::metadata::mark_bit::MarkState;
+use crate::util::metadata::side_metadata::SideMetadataSpec;
+#[cfg(feature = "vo_bit")]
+use crate::util::metadata::vo_bit;
+use crate::util::metadata::{self, MetadataSpec};
+use crate::util::object_enum::{self, ObjectEnumerator};
+use crate::util::object_forwarding;
+use crate::util::opaque_pointer::{VMThread, VMWorkerThread};
+use crate::util::{Address, ObjectReference};
+use crate::vm::slot::{MemorySlice, Slot};
+use crate::vm::*;
+use crate::MMTK;
+use crossbeam::queue::SegQueue;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Old regions with fewer live bytes than this after a full heap GC are candidates for the
+/// collection set of later GCs.
+const CANDIDATE_LIVE_BYTES_THRESHOLD: usize = HeapRegion::BYTES / 100 * 85;
+
+/// The estimated cost of scanning a remembered set entry, measured in the number of bytes that can
+/// be copied in the same time.
+pub const REMSET_ENTRY_COST_BYTES: usize = 64;
+
+/// The max number of objects in a [`RefineObjects`] work packet.
+const REFINE_OBJECTS_PACKET_SIZE: usize = 512;
+
+/// A space that allocates objects into fixed-size regions, and evacuates a collection set of
+/// regions in each GC.
+///
+/// In a young GC, the collection set includes all the young regions and some old regions picked
+/// from the candidates found by the last full heap GC. Objects in old regions outside the
+/// collection set are not traced. In a full heap GC, the collection set only includes the young
+/// regions, and objects in old regions are marked in place. Old regions without live objects are
+/// released after a full heap GC.
+pub struct RegionSpace<VM: VMBinding> {
+    common: CommonSpace<VM>,
+    pr: BlockPageResource<VM, HeapRegion>,
+    /// Allocation status for all chunks in the region space
+    pub chunk_map: ChunkMap,
+    /// Object mark state. Objects are only marked in full heap GCs.
+    mark_state: MarkState,
+    /// Work packet scheduler
+    scheduler: Arc<GCWorkScheduler<VM>>,
+    /// Is the current GC a full heap GC?
+    full_heap: bool,
+    /// Young regions allocated by mutators since the last GC
+    young_regions: Mutex<Vec<HeapRegion>>,
+    /// The number of pages in young regions
+    young_pages: AtomicUsize,
+    /// Regions in the collection set of the current GC
+    collection_set: Vec<HeapRegion>,
+    /// Old regions that may be added to the collection set, with the region with the fewest live
+    /// bytes first.
+    candidates: VecDeque<HeapRegion>,
+    /// The remembered set of each old region. It records the objects outside the region that may
+    /// point into the region.
+    remsets: Mutex<HashMap<HeapRegion, HashSet<ObjectReference>>>,
+    /// Objects whose references need to be recorded in the remembered sets at the end of the
+    /// current GC. These are the objects modified by mutators, the objects copied by the GC, and
+    /// the objects found in the remembered sets of evacuated regions.
+    refinement_queue: SegQueue<ObjectReference>,
+    /// Memory slices modified by mutators whose objects are unknown. They are scanned in place
+    /// like modified objects, but there is no object to record in the remembered sets.
+    slice_refinement_queue: SegQueue<VM::VMMemorySlice>,
+    /// Old regions that may be referenced by the slots of memory slices without known objects.
+    /// They are never added to the collection set, because their remembered sets are incomplete.
+    unevacuable_regions: Mutex<HashSet<HeapRegion>>,
+    /// The number of remembered set entries scanned in the current GC
+    remset_entries_scanned: usize,
+    /// Bytes copied in the current GC
+    copied_bytes: AtomicUsize,
+    /// Bytes copied from young regions in the current GC
+    young_copied_bytes: AtomicUsize,
+}
+
+unsafe impl<VM: VMBinding> Sync for RegionSpace<VM> {}
+
+impl<VM: VMBinding> SFT for RegionSpace<VM> {
+    fn name(&self) -> &'static str {
+        self.get_name()
+    }
+
+    fn get_forwarded_object(&self, object: ObjectReference) -> Option<ObjectReference> {
+        if !HeapRegion::containing(object).in_collection_set() {
+            return None;
+        }
+
+        if object_forwarding::is_forwarded::<VM>(object) {
+            Some(object_forwarding::read_forwarding_pointer::<VM>(object))
+        } else {
+            None
+        }
+    }
+
+    fn is_live(&self, object: ObjectReference) -> bool {
+        if HeapRegion::containing(object).in_collection_set() {
+            object_forwarding::is_forwarded::<VM>(object)
+        } else if self.full_heap {
+            self.mark_state.is_marked::<VM>(object)
+        } else {
+            // Old objects outside the collection set are not traced in young GCs.
+            true
+        }
+    }
+
+    #[cfg(feature = "object_pinning")]
+    fn pin_object(&self, _object: ObjectReference) -> bool {
+        panic!("Cannot pin/unpin objects of RegionSpace.")
+    }
+
+    #[cfg(feature = "object_pinning")]
+    fn unpin_object(&self, _object: ObjectReference) -> bool {
+        panic!("Cannot pin/unpin objects of RegionSpace.")
+    }
+
+    #[cfg(feature = "object_pinning")]
+    fn is_object_pinned(&self, _object: ObjectReference) -> bool {
+        false
+    }
+
+    fn is_movable(&self) -> bool {
+        true
+    }
+
+    #[cfg(feature = "sanity")]
+    fn is_sane(&self) -> bool {
+        true
+    }
+
+    fn initialize_object_metadata(&self, object: ObjectReference, _alloc: bool) {
+        #[cfg(feature = "vo_bit")]
+        crate::util::metadata::vo_bit::set_vo_bit(object);
+        self.mark_state
+            .on_object_metadata_initialization::<VM>(object);
+    }
+
+    #[cfg(feature = "is_mmtk_object")]
+    fn is_mmtk_object(&self, addr: Address) -> Option<ObjectReference> {
+        crate::util::metadata::vo_bit::is_vo_bit_set_for_addr(addr)
+    }
+
+    #[cfg(feature = "is_mmtk_object")]
+    fn find_object_from_internal_pointer(
+        &self,
+        ptr: Address,
+        max_search_bytes: usize,
+    ) -> Option<ObjectReference> {
+        // We don't need to search more than the max object size in the region space.
+        let search_bytes = usize::min(super::MAX_REGION_OBJECT_SIZE, max_search_bytes);
+        crate::util::metadata::vo_bit::find_object_from_internal_pointer::<VM>(ptr, search_bytes)
+    }
+
+    fn sft_trace_object(
+        &self,
+        _queue: &mut VectorObjectQueue,
+        _object: ObjectReference,
+        _worker: GCWorkerMutRef,
+    ) -> ObjectReference {
+        panic!("We do not use SFT to trace objects for RegionSpace. sft_trace_object() cannot be used.")
+    }
+}
+
+impl<VM: VMBinding> Space<VM> for RegionSpace<VM> {
+    fn as_space(&self) -> &dyn Space<VM> {
+        self
+    }
+
+    fn as_sft(&self) -> &(dyn SFT + Sync + 'static) {
+        self
+    }
+
+    fn get_page_resource(&self) -> &dyn PageResource<VM> {
+        &self.pr
+    }
+
+    fn maybe_get_page_resource_mut(&mut self) -> Option<&mut dyn PageResource<VM>> {
+        Some(&mut self.pr)
+    }
+
+    fn common(&self) -> &CommonSpace<VM> {
+        &self.common
+    }
+
+    fn initialize_sft(&self, sft_map: &mut dyn SFTMap) {
+        self.common().initialize_sft(self.as_sft(), sft_map)
+    }
+
+    fn release_multiple_pages(&mut self, _start: Address) {
+        panic!("regionspace only releases pages in regions")
+    }
+
+    fn set_copy_for_sft_trace(&mut self, _semantics: Option<CopySemantics>) {
+        panic!("We do not use SFT to trace objects for RegionSpace. set_copy_context() cannot be used.")
+    }
+
+    fn enumerate_objects(&self, enumerator: &mut dyn ObjectEnumerator) {
+        object_enum::enumerate_blocks_from_chunk_map::<HeapRegion>(enumerator, &self.chunk_map);
+    }
+}
+
+impl<VM: VMBinding> crate::policy::gc_work::PolicyTraceObject<VM> for RegionSpace<VM> {
+    fn trace_object<Q: ObjectQueue, const KIND: TraceKind>(
+        &self,
+        queue: &mut Q,
+        object: ObjectReference,
+        copy: Option<CopySemantics>,
+        worker: &mut GCWorker<VM>,
+    ) -> ObjectReference {
+        debug_assert!(
+            KIND != TRACE_KIND_TRANSITIVE_PIN,
+            "RegionSpace does not support transitive pin trace."
+        );
+        self.trace_object(queue, object, copy, worker)
+    }
+
+    fn may_move_objects<const KIND: TraceKind>() -> bool {
+        true
+    }
+}
+
+impl<VM: VMBinding> RegionSpace<VM> {
+    /// Get side metadata specs
+    fn side_metadata_specs() -> Vec<SideMetadataSpec> {
+        metadata::extract_side_metadata(&[
+            MetadataSpec::OnSide(HeapRegion::STATE_TABLE),
+            MetadataSpec::OnSide(HeapRegion::LIVE_BYTES_TABLE),
+            MetadataSpec::OnSide(ChunkMap::ALLOC_TABLE),
+            *VM::VMObjectModel::LOCAL_MARK_BIT_SPEC,
+            *VM::VMObjectModel::LOCAL_FORWARDING_BITS_SPEC,
+            *VM::VMObjectModel::LOCAL_FORWARDING_POINTER_SPEC,
+        ])
+    }
+
+    pub fn new(args: crate::policy::space::PlanCreateSpaceArgs<VM>) -> Self {
+        #[cfg(feature = "vo_bit")]
+        vo_bit::helper::validate_config::<VM>();
+        let vm_map = args.vm_map;
+        let scheduler = args.scheduler.clone();
+        let common =
+            CommonSpace::new(args.into_policy_args(true, false, Self::side_metadata_specs()));
+        RegionSpace {
+            pr: if common.vmrequest.is_discontiguous() {
+                BlockPageResource::new_discontiguous(
+                    HeapRegion::LOG_PAGES,
+                    vm_map,
+                    scheduler.num_workers(),
+                )
+            } else {
+                BlockPageResource::new_contiguous(
+                    HeapRegion::LOG_PAGES,
+                    common.start,
+                    common.extent,
+                    vm_map,
+                    scheduler.num_workers(),
+                )
+            },
+            common,
+            chunk_map: ChunkMap::new(),
+            mark_state: MarkState::new(),
+            scheduler,
+            full_heap: false,
+            young_regions: Mutex::new(vec![]),
+            young_pages: AtomicUsize::new(0),
+            collection_set: vec![],
+            candidates: VecDeque::new(),
+            remsets: Mutex::new(HashMap::new()),
+            refinement_queue: SegQueue::new(),
+            slice_refinement_queue: SegQueue::new(),
+            unevacuable_regions: Mutex::new(HashSet::new()),
+            remset_entries_scanned: 0,
+            copied_bytes: AtomicUsize::new(0),
+            young_copied_bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Allocate a clean region. Mutators allocate into young regions, and GC workers copy objects
+    /// into old regions.
+    pub fn get_clean_region(
+        &self,
+        tls: VMThread,
+        copy: bool,
+    ) -> Result<Option<HeapRegion>, AllocationError> {
+        let region_address = self.acquire(tls, HeapRegion::PAGES)?;
+        if region_address.is_zero() {
+            return Ok(None);
+        }
+        let region = HeapRegion::from_aligned_address(region_address);
+        if copy {
+            region.init(RegionState::Old);
+        } else {
+            region.init(RegionState::Young);
+            self.young_regions.lock().unwrap().push(region);
+            self.young_pages
+                .fetch_add(HeapRegion::PAGES, Ordering::SeqCst);
+        }
+        self.chunk_map.set(region.chunk(), ChunkState::Allocated);
+        Ok(Some(region))
+    }
+
+    /// Get the number of pages in young regions.
+    pub fn young_pages(&self) -> usize {
+        self.young_pages.load(Ordering::SeqCst)
+    }
+
+    /// Get the number of bytes copied in the current GC, and how many of them are copied from
+    /// young regions. This is only accurate after the copy contexts are released.
+    pub fn copied_bytes(&self) -> (usize, usize) {
+        (
+            self.copied_bytes.load(Ordering::SeqCst),
+            self.young_copied_bytes.load(Ordering::SeqCst),
+        )
+    }
+
+    /// Get the number of remembered set entries scanned in the current GC.
+    pub fn remset_entries_scanned(&self) -> usize {
+        self.remset_entries_scanned
+    }
+
+    /// Prepare for a GC. All the young regions are added to the collection set. For a young GC,
+    /// old candidate regions are added to the collection set as long as the estimated cost of
+    /// evacuating them (see [`REMSET_ENTRY_COST_BYTES`]) is within `old_region_budget`.
+    pub fn prepare(&mut self, full_heap: bool, old_region_budget: usize) {
+        self.full_heap = full_heap;
+        self.remset_entries_scanned = 0;
+        self.copied_bytes.store(0, Ordering::SeqCst);
+        self.young_copied_bytes.store(0, Ordering::SeqCst);
+
+        let young_regions = std::mem::take(&mut *self.young_regions.lock().unwrap());
+        self.young_pages.store(0, Ordering::SeqCst);
+        for region in young_regions.iter() {
+            region.add_to_collection_set();
+        }
+        self.collection_set = young_regions;
+
+        if full_heap {
+            // Candidates will be found again after marking.
+            self.candidates.clear();
+            self.mark_state.on_global_prepare::<VM>();
+            // # Safety: RegionSpace reference is always valid within this collection cycle.
+            let space = unsafe { &*(self as *const Self) };
+            let work_packets = self
+                .chunk_map
+                .generate_tasks(|chunk| Box::new(PrepareChunk { space, chunk }));
+            self.scheduler.work_buckets[WorkBucketStage::Prepare].bulk_add(work_packets);
+
+            #[cfg(feature = "vo_bit")]
+            if vo_bit::helper::need_to_clear_vo_bits_before_tracing::<VM>() {
+                let work_packets = self
+                    .chunk_map
+                    .generate_tasks(|chunk| Box::new(ClearVOBitsAfterPrepare { chunk }));
+                self.scheduler.work_buckets[WorkBucketStage::ClearVOBits].bulk_add(work_packets);
+            }
+        } else {
+            self.select_old_regions(old_region_budget);
+        }
+    }
+
+    /// Add old candidate regions to the collection set, with the region with the most garbage
+    /// first.
+    fn select_old_regions(&mut self, budget: usize) {
+        // Objects modified by mutators are scanned in place by `ProcessModBuf`, so we cannot
+        // evacuate the regions that contain them. Those objects are all in the refinement queue.
+        let mut modified = vec![];
+        while let Some(object) = self.refinement_queue.pop() {
+            modified.push(object);
+        }
+        let mut modified_regions: HashSet<HeapRegion> = modified
+            .iter()
+            .filter(|object| self.in_space(**object))
+            .map(|object| HeapRegion::containing(*object))
+            .collect();
+        for object in modified {
+            self.refinement_queue.push(object);
+        }
+        // So are modified slices.
+        let mut modified_slices = vec![];
+        while let Some(slice) = self.slice_refinement_queue.pop() {
+            if self.address_in_space(slice.start()) {
+                modified_regions.insert(HeapRegion::from_unaligned_address(slice.start()));
+            }
+            modified_slices.push(slice);
+        }
+        for slice in modified_slices {
+            self.slice_refinement_queue.push(slice);
+        }
+        modified_regions.extend(self.unevacuable_regions.lock().unwrap().iter().copied());
+
+        let remsets = self.remsets.lock().unwrap();
+        let mut skipped = vec![];
+        let mut cost = 0;
+        while let Some(region) = self.candidates.pop_front() {
+            debug_assert_eq!(region.get_state(), RegionState::Old);
+            if modified_regions.contains(&region) {
+                skipped.push(region);
+                continue;
+            }
+            let region_cost = region.get_live_bytes()
+                + remsets.get(&region).map_or(0, |remset| remset.len()) * REMSET_ENTRY_COST_BYTES;
+            if cost + region_cost > budget {
+                self.candidates.push_front(region);
+                break;
+            }
+            cost += region_cost;
+            region.add_to_collection_set();
+            self.collection_set.push(region);
+        }
+        for region in skipped.into_iter().rev() {
+            self.candidates.push_front(region);
+        }
+    }
+
+    /// Scan the objects in the remembered sets of the old regions in the collection set, so the
+    /// references from outside the collection set are traced and updated. This is only needed
+    /// in young GCs.
+    pub fn scan_remembered_sets<E: ProcessEdgesWork<VM = VM>>(&mut self) {
+        debug_assert!(!self.full_heap);
+        let mut sources = HashSet::new();
+        {
+            let remsets = self.remsets.lock().unwrap();
+            for region in self
+                .collection_set
+                .iter()
+                .filter(|region| region.get_state() == RegionState::Old)
+            {
+                if let Some(remset) = remsets.get(region) {
+                    sources.extend(
+                        remset
+                            .iter()
+                            .copied()
+                            .filter(|object| !self.in_collection_set(*object)),
+                    );
+                }
+            }
+        }
+        self.remset_entries_scanned = sources.len();
+
+        let sources: Vec<ObjectReference> = sources.into_iter().collect();
+        for chunk in sources.chunks(E::CAPACITY) {
+            // The objects they point to may be moved, so their references need to be recorded
+            // again at the end of the GC.
+            for object in chunk {
+                self.refinement_queue.push(*object);
+            }
+            self.scheduler.work_buckets[WorkBucketStage::Closure].add(ScanObjects::<E>::new(
+                chunk.to_vec(),
+                false,
+                WorkBucketStage::Closure,
+            ));
+        }
+    }
+
+    /// Record an object whose references need to be recorded in the remembered sets at the end
+    /// of the current GC. The object may be in any space.
+    pub fn add_to_refinement_queue(&self, object: ObjectReference) {
+        self.refinement_queue.push(object);
+    }
+
+    /// Record a memory slice whose object is unknown. The old regions that its slots point to at
+    /// the end of the current GC can no longer be evacuated, because the slots cannot be recorded
+    /// in their remembered sets.
+    pub fn add_slice_to_refinement_queue(&self, slice: VM::VMMemorySlice) {
+        self.slice_refinement_queue.push(slice);
+    }
+
+    /// Release for the region space. `is_live` tells if an object in other spaces survived the
+    /// current GC. This has to be called before other spaces release dead objects.
+    pub fn release(&mut self, is_live: impl Fn(ObjectReference) -> bool) {
+        // Conservatively keep the old regions that modified slices point to. The slices may be
+        // in dead objects, and the regions may be released when they have no live objects.
+        {
+            let mut unevacuable = self.unevacuable_regions.lock().unwrap();
+            while let Some(slice) = self.slice_refinement_queue.pop() {
+                for slot in slice.iter_slots() {
+                    let Some(target) = slot.load() else {
+                        continue;
+                    };
+                    if self.in_space(target) && !self.in_collection_set(target) {
+                        let region = HeapRegion::containing(target);
+                        if region.get_state() == RegionState::Old {
+                            unevacuable.insert(region);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Find the objects that survived the GC in the refinement queue.
+        let mut survivors = HashSet::new();
+        while let Some(object) = self.refinement_queue.pop() {
+            if self.source_survived(object, &is_live) {
+                survivors.insert(object);
+            }
+        }
+
+        // Find the regions to release.
+        let mut released_regions = std::mem::take(&mut self.collection_set);
+        if self.full_heap {
+            let mut candidates = vec![];
+            for chunk in self
+                .chunk_map
+                .all_chunks()
+                .filter(|chunk| self.chunk_map.get(*chunk) == ChunkState::Allocated)
+            {
+                for region in chunk.iter_region::<HeapRegion>().filter(|region| {
+                    region.get_state() == RegionState::Old && !region.in_collection_set()
+                }) {
+                    let live_bytes = region.get_live_bytes();
+                    if live_bytes == 0 {
+                        released_regions.push(region);
+                        continue;
+                    }
+                    #[cfg(feature = "vo_bit")]
+                    vo_bit::helper::on_region_swept::<VM, _>(&region, true);
+                    if live_bytes < CANDIDATE_LIVE_BYTES_THRESHOLD
+                        && !self.unevacuable_regions.lock().unwrap().contains(&region)
+                    {
+                        candidates.push(region);
+                    }
+                }
+            }
+            candidates.sort_by_key(|region| region.get_live_bytes());
+            self.candidates = candidates.into();
+        }
+
+        // Remove dead objects and released regions from the remembered sets.
+        {
+            let mut remsets = self.remsets.lock().unwrap();
+            for region in released_regions.iter() {
+                remsets.remove(region);
+            }
+            remsets.retain(|_, remset| {
+                remset.retain(|object| self.source_survived(*object, &is_live));
+                !remset.is_empty()
+            });
+        }
+
+        for region in released_regions {
+            self.release_region(region);
+        }
+        self.pr.flush_all();
+        for chunk in self.chunk_map.all_chunks() {
+            if self.chunk_map.get(chunk) == ChunkState::Allocated
+                && chunk
+                    .iter_region::<HeapRegion>()
+                    .all(|region| region.get_state() == RegionState::Unallocated)
+            {
+                self.chunk_map.set(chunk, ChunkState::Free);
+            }
+        }
+
+        // Record the references of the survivors in the remembered sets.
+        // # Safety: RegionSpace reference is always valid within this collection cycle.
+        let space = unsafe { &*(self as *const Self) };
+        let survivors: Vec<ObjectReference> = survivors.into_iter().collect();
+        let work_packets: Vec<Box<dyn GCWork<VM>>> = survivors
+            .chunks(REFINE_OBJECTS_PACKET_SIZE)
+            .map(|objects| {
+                Box::new(RefineObjects {
+                    space,
+                    objects: objects.to_vec(),
+                }) as _
+            })
+            .collect();
+        self.scheduler.work_buckets[WorkBucketStage::Release].bulk_add(work_packets);
+
+        if self.full_heap {
+            self.mark_state.on_global_release::<VM>();
+        }
+    }
+
+    /// Is the object in a region in the collection set?
+    fn in_collection_set(&self, object: ObjectReference) -> bool {
+        self.in_space(object) && HeapRegion::containing(object).in_collection_set()
+    }
+
+    /// Did an object that may point into old regions survive the current GC? This is only
+    /// accurate before regions and other spaces are released.
+    fn source_survived(
+        &self,
+        object: ObjectReference,
+        is_live: &impl Fn(ObjectReference) -> bool,
+    ) -> bool {
+        if self.in_space(object) {
+            !HeapRegion::containing(object).in_collection_set()
+                && (!self.full_heap || self.mark_state.is_marked::<VM>(object))
+        } else {
+            is_live(object)
+        }
+    }
+
+    /// Release a region, and clear its metadata.
+    fn release_region(&self, region: HeapRegion) {
+        self.unevacuable_regions.lock().unwrap().remove(&region);
+        // Clear side forwarding bits, so no side forwarding bits are set in the next GC.
+        if let MetadataSpec::OnSide(side) = *VM::VMObjectModel::LOCAL_FORWARDING_BITS_SPEC {
+            side.bzero_metadata(region.start(), HeapRegion::BYTES);
+        }
+        self.mark_state
+            .on_block_reset::<VM>(region.start(), HeapRegion::BYTES);
+        // Copied objects are unlogged, and new objects should start as logged.
+        if let MetadataSpec::OnSide(side) = *VM::VMObjectModel::GLOBAL_LOG_BIT_SPEC {
+            side.bzero_metadata(region.start(), HeapRegion::BYTES);
+        }
+        #[cfg(feature = "vo_bit")]
+        vo_bit::bzero_vo_bit(region.start(), HeapRegion::BYTES);
+        region.deinit();
+        self.pr.release_block(region);
+    }
+
+    /// Add entries to the remembered sets.
+    fn add_remset_entries(&self, entries: Vec<(HeapRegion, ObjectReference)>) {
+        let mut remsets = self.remsets.lock().unwrap();
+        for (region, object) in entries {
+            remsets.entry(region).or_default().insert(object);
+        }
+    }
+
+    /// Trace an object. Objects in the collection set are evacuated. Objects in other regions are
+    /// marked in a full heap GC, and are not traced in a young GC.
+    pub fn trace_object<Q: ObjectQueue>(
+        &self,
+        queue: &mut Q,
+        object: ObjectReference,
+        semantics: Option<CopySemantics>,
+        worker: &mut GCWorker<VM>,
+    ) -> ObjectReference {
+        #[cfg(feature = "vo_bit")]
+        vo_bit::helper::on_trace_object::<VM>(object);
+
+        let region = HeapRegion::containing(object);
+        if region.in_collection_set() {
+            self.evacuate(queue, object, semantics.unwrap(), worker)
+        } else if self.full_heap {
+            if self.mark_state.test_and_mark::<VM>(object) {
+                region.add_live_bytes(VM::VMObjectModel::get_current_size(object));
+                #[cfg(feature = "vo_bit")]
+                vo_bit::helper::on_object_marked::<VM>(object);
+                queue.enqueue(object);
+            }
+            object
+        } else {
+            object
+        }
+    }
+
+    fn evacuate<Q: ObjectQueue>(
+        &self,
+        queue: &mut Q,
+        object: ObjectReference,
+        semantics: CopySemantics,
+        worker: &mut GCWorker<VM>,
+    ) -> ObjectReference {
+        let forwarding_status = object_forwarding::attempt_to_forward::<VM>(object);
+        if object_forwarding::state_is_forwarded_or_being_forwarded(forwarding_status) {
+            object_forwarding::spin_and_get_forwarded_object::<VM>(object, forwarding_status)
+        } else {
+            let new_object = object_forwarding::forward_object::<VM>(
+                object,
+                semantics,
+                worker.get_copy_context_mut(),
+                |_new_object| {
+                    #[cfg(feature = "vo_bit")]
+                    vo_bit::helper::on_object_forwarded::<VM>(_new_object);
+                },
+            );
+            trace!("Evacuated [{:?} -> {:?}]", object, new_object);
+            queue.enqueue(new_object);
+            new_object
+        }
+    }
+
+    /// Post copy routine for region copy contexts
+    fn post_copy(&self, object: ObjectReference, bytes: usize) {
+        HeapRegion::containing(object).add_live_bytes(bytes);
+        if self.full_heap {
+            self.mark_state.test_and_mark::<VM>(object);
+        }
+        self.refinement_queue.push(object);
+    }
+}
+
+/// A work packet to prepare the old regions in a chunk for a full heap GC.
+struct PrepareChunk<VM: VMBinding> {
+    space: &'static RegionSpace<VM>,
+    chunk: Chunk,
+}
+
+impl<VM: VMBinding> GCWork<VM> for PrepareChunk<VM> {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        for region in self
+            .chunk
+            .iter_region::<HeapRegion>()
+            .filter(|region| region.get_state() == RegionState::Old)
+        {
+            self.space
+                .mark_state
+                .on_block_reset::<VM>(region.start(), HeapRegion::BYTES);
+            region.set_live_bytes(0);
+        }
+    }
+}
+
+/// A work packet to clear VO bits of the old regions in a chunk before a full heap GC.
+#[cfg(feature = "vo_bit")]
+struct ClearVOBitsAfterPrepare {
+    chunk: Chunk,
+}
+
+#[cfg(feature = "vo_bit")]
+impl<VM: VMBinding> GCWork<VM> for ClearVOBitsAfterPrepare {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        for region in self
+            .chunk
+            .iter_region::<HeapRegion>()
+            .filter(|region| region.get_state() == RegionState::Old)
+        {
+            vo_bit::bzero_vo_bit(region.start(), HeapRegion::BYTES);
+        }
+    }
+}
+
+/// A work packet to record the references of objects in the remembered sets. For each reference
+/// into an old region other than the region of the object itself, the object is added to the
+/// remembered set of that region.
+struct RefineObjects<VM: VMBinding> {
+    space: &'static RegionSpace<VM>,
+    objects: Vec<ObjectReference>,
+}
+
+impl<VM: VMBinding> GCWork<VM> for RefineObjects<VM> {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        let tls = worker.tls;
+        let space = self.space;
+        let mut entries = vec![];
+        for object in self.objects.iter().copied() {
+            let source_region = space
+                .in_space(object)
+                .then(|| HeapRegion::containing(object));
+            let mut record = |target: ObjectReference| {
+                if space.in_space(target) {
+                    let region = HeapRegion::containing(target);
+                    if Some(region) != source_region && region.get_state() == RegionState::Old {
+                        entries.push((region, object));
+                    }
+                }
+            };
+            if VM::VMScanning::support_slot_enqueuing(tls, object) {
+                VM::VMScanning::scan_object(tls, object, &mut |slot: VM::VMSlot| {
+                    if let Some(target) = slot.load() {
+                        record(target);
+                    }
+                });
+            } else {
+                VM::VMScanning::scan_object_and_trace_edges(tls, object, &mut |target| {
+                    record(target);
+                    target
+                });
+            }
+        }
+        space.add_remset_entries(entries);
+    }
+}
+
+/// Copy context for the region space. GC workers copy objects into old regions.
+pub struct RegionCopyContext<VM: VMBinding> {
+    allocator: RegionAllocator<VM>,
+    /// Bytes copied by this context in the current GC
+    copied_bytes: usize,
+    /// Bytes copied from young regions by this context in the current GC
+    young_copied_bytes: usize,
+}
+
+impl<VM: VMBinding> PolicyCopyContext for RegionCopyContext<VM> {
+    type VM = VM;
+
+    fn prepare(&mut self) {
+        self.allocator.reset();
+    }
+
+    fn release(&mut self) {
+        self.allocator.reset();
+        let space = self.get_space();
+        space
+            .copied_bytes
+            .fetch_add(self.copied_bytes, Ordering::SeqCst);
+        space
+            .young_copied_bytes
+            .fetch_add(self.young_copied_bytes, Ordering::SeqCst);
+        self.copied_bytes = 0;
+        self.young_copied_bytes = 0;
+    }
+
+    fn alloc_copy(
+        &mut self,
+        original: ObjectReference,
+        bytes: usize,
+        align: usize,
+        offset: usize,
+    ) -> Address {
+        self.copied_bytes += bytes;
+        if HeapRegion::containing(original).get_state() == RegionState::Young {
+            self.young_copied_bytes += bytes;
+        }
+        self.allocator.alloc(bytes, align, offset)
+    }
+
+    fn post_copy(&mut self, obj: ObjectReference, bytes: usize) {
+        self.get_space().post_copy(obj, bytes)
+    }
+}
+
+impl<VM: VMBinding> RegionCopyContext<VM> {
+    pub(crate) fn new(
+        tls: VMWorkerThread,
+        context: Arc<AllocatorContext<VM>>,
+        space: &'static RegionSpace<VM>,
+    ) -> Self {
+        RegionCopyContext {
+            allocator: RegionAllocator::new(tls.0, space, context, true),
+            copied_bytes: 0,
+            young_copied_bytes: 0,
+        }
+    }
+
+    fn get_space(&self) -> &RegionSpace<VM> {
+        self.allocator.region_space()
+    }
+}
@@ -1,6 +1,7 @@
 use crate::global_state::GlobalState;
 use crate::plan::PlanConstraints;
 use crate::scheduler::GCWorkScheduler;
+use crate::util::alloc::AllocationError;
 use crate::util::conversions::*;
 use crate::util::metadata::side_metadata::{
     SideMetadataContext, SideMetadataSanity, SideMetadataSpec,
@@ -11,7 +12,7 @@ use crate::util::ObjectReference;
 
 use crate::util::heap::layout::vm_layout::{vm_layout, LOG_BYTES_IN_CHUNK};
 use crate::util::heap::{PageResource, VMRequest};
-use crate::util::options::Options;
+use crate::util::options::{Options, SpaceBudgetAction};
 use crate::vm::{ActivePlan, Collection};
 
 use crate::util::constants::{LOG_BYTES_IN_MBYTE, LOG_BYTES_IN_PAGE};
@@ -33,6 +34,7 @@ use crate::util::memory::{self, HugePageSupport, MmapProtection, MmapStrategy};
 use crate::vm::VMBinding;
 
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -71,16 +73,17 @@ pub trait Space<VM: VMBinding>: 'static + SFT + Sync + Downcast {
         let max_pages = self.get_gc_trigger().policy.get_max_heap_size_in_pages();
         let requested_pages = size >> LOG_BYTES_IN_PAGE;
         if requested_pages > max_pages {
-            VM::VMCollection::out_of_memory(
-                tls,
-                crate::util::alloc::AllocationError::HeapOutOfMemory,
-            );
+            VM::VMCollection::out_of_memory(tls, AllocationError::HeapOutOfMemory);
             return true;
         }
         false
     }
 
-    fn acquire(&self, tls: VMThread, pages: usize) -> Address {
+    /// Acquire a number of pages for the space. Return the start of the pages, or a zero address if
+    /// no pages are available now. In the latter case, a mutator may have been blocked for a GC,
+    /// and it may retry the allocation. Return an error if the allocation must fail without
+    /// retrying, that is, when a mutator allocates into a space that exceeds its budget.
+    fn acquire(&self, tls: VMThread, pages: usize) -> Result<Address, AllocationError> {
         trace!("Space.acquire, tls={:?}", tls);
 
         debug_assert!(
@@ -101,9 +104,34 @@ pub trait Space<VM: VMBinding>: 'static + SFT + Sync + Downcast {
         let pr = self.get_page_resource();
         let pages_reserved = pr.reserve_pages(pages);
         trace!("Pages reserved");
-        trace!("Polling ..");
 
-        if should_poll && self.get_gc_trigger().poll(false, Some(self.as_space())) {
+        // Check the budget of the space. Only mutators are limited, as GC must be able to copy
+        // objects.
+        let mut space_full = false;
+        if let Some((budget_pages, action)) = self.common().budget {
+            if should_poll && self.reserved_pages() > budget_pages {
+                // We only collect if it is allowed, and the last GC could bring the space under
+                // its budget.
+                if action == SpaceBudgetAction::Error
+                    || !allow_gc
+                    || self.common().over_budget_after_gc.load(Ordering::Relaxed)
+                {
+                    debug!("{} exceeds its budget", self.get_name());
+                    // The allocator will throw the error.
+                    pr.clear_request(pages_reserved);
+                    return Err(AllocationError::SpaceBudgetExceeded);
+                }
+                // Collect as if the space were full.
+                space_full = true;
+            }
+        }
+
+        trace!("Polling ..");
+        if should_poll
+            && self
+                .get_gc_trigger()
+                .poll(space_full, Some(self.as_space()))
+        {
             debug!("Collection required");
             assert!(allow_gc, "GC is not allowed here: collection is not initialized (did you call initialize_collection()?).");
 
@@ -114,7 +142,12 @@ pub trait Space<VM: VMBinding>: 'static + SFT + Sync + Downcast {
                 .on_pending_allocation(pages_reserved);
 
             VM::VMCollection::block_for_gc(VMMutatorThread(tls)); // We have checked that this is mutator
-            unsafe { Address::zero() }
+            Ok(unsafe { Address::zero() })
+        } else if should_poll && self.get_gc_trigger().soft_limit.is_out_of_memory() {
+            debug!("Soft heap limit exceeded");
+            // The allocator will throw an out of memory error.
+            pr.clear_request(pages_reserved);
+            Ok(unsafe { Address::zero() })
         } else {
             debug!("Collection not required");
 
@@ -181,6 +214,25 @@ pub trait Space<VM: VMBinding>: 'static + SFT + Sync + Downcast {
                         mmap();
                     }
 
+                    // GC threads bound to a NUMA node copy objects into memory on their node.  We
+                    // bind whole chunks once when they are fresh, instead of calling `mbind` for
+                    // every acquisition.  Later acquisitions from the chunk use the same node.
+                    #[cfg(target_os = "linux")]
+                    if res.new_chunk {
+                        if let Some(node) = crate::scheduler::affinity::current_numa_node() {
+                            let chunk_start = chunk_align_down(res.start);
+                            let chunk_bytes = chunk_align_up(res.start + bytes) - chunk_start;
+                            if let Err(e) =
+                                memory::bind_to_numa_node(chunk_start, chunk_bytes, node)
+                            {
+                                debug!(
+                                    "Failed to bind {} to NUMA node {}: {}",
+                                    chunk_start, node, e
+                                );
+                            }
+                        }
+                    }
+
                     // TODO: Concurrent zeroing
                     if self.common().zeroed {
                         memory::zero(res.start, bytes);
@@ -213,7 +265,7 @@ pub trait Space<VM: VMBinding>: 'static + SFT + Sync + Downcast {
                     }
 
                     debug!("Space.acquire(), returned = {}", res.start);
-                    res.start
+                    Ok(res.start)
                 }
                 Err(_) => {
                     drop(lock); // drop the lock immediately
@@ -234,7 +286,7 @@ pub trait Space<VM: VMBinding>: 'static + SFT + Sync + Downcast {
                         .on_pending_allocation(pages_reserved);
 
                     VM::VMCollection::block_for_gc(VMMutatorThread(tls)); // We asserted that this is mutator.
-                    unsafe { Address::zero() }
+                    Ok(unsafe { Address::zero() })
                 }
             }
         }
@@ -479,6 +531,12 @@ pub struct CommonSpace<VM: VMBinding> {
     pub global_state: Arc<GlobalState>,
     pub options: Arc<Options>,
 
+    /// The budget of the space in pages, and what to do when it is exceeded, set by the option
+    /// `space_budgets`.
+    pub(crate) budget: Option<(usize, SpaceBudgetAction)>,
+    /// Did the space exceed its budget at the end of the last GC?
+    pub(crate) over_budget_after_gc: AtomicBool,
+
     p: PhantomData<VM>,
 }
 
@@ -549,6 +607,13 @@ impl<VM: VMBinding> CommonSpace<VM> {
             acquire_lock: Mutex::new(()),
             global_state: args.plan_args.global_state,
             options: args.plan_args.options.clone(),
+            budget: args
+                .plan_args
+                .options
+                .space_budgets
+                .get(args.plan_args.name)
+                .map(|budget| (bytes_to_pages_up(budget.bytes), budget.action)),
+            over_budget_after_gc: AtomicBool::new(false),
             p: PhantomData,
         };
 
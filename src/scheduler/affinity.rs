@@ -1,11 +1,26 @@
 use super::worker::ThreadId;
-use crate::util::options::AffinityKind;
+use crate::util::options::{AffinityKind, NumaNode};
 #[cfg(target_os = "linux")]
-use libc::{cpu_set_t, sched_getaffinity, sched_setaffinity, CPU_COUNT, CPU_SET, CPU_ZERO};
+use libc::{
+    cpu_set_t, sched_getaffinity, sched_setaffinity, CPU_COUNT, CPU_ISSET, CPU_SET, CPU_SETSIZE,
+    CPU_ZERO,
+};
+use std::cell::Cell;
 
 /// Represents the ID of a logical CPU on a system.
 pub type CoreId = u16;
 
+thread_local! {
+    /// The NUMA node the current GC thread is bound to, if any.
+    static CURRENT_NUMA_NODE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Get the NUMA node the current thread is bound to. Return `None` if the current thread is not a
+/// GC thread bound to a NUMA node.
+pub fn current_numa_node() -> Option<usize> {
+    CURRENT_NUMA_NODE.with(|node| node.get())
+}
+
 // XXX: Maybe in the future we can use a library such as https://github.com/Elzair/core_affinity_rs
 // to have an OS agnostic way of setting thread affinity.
 #[cfg(target_os = "linux")]
@@ -26,6 +41,69 @@ pub fn get_total_num_cpus() -> u16 {
     unimplemented!()
 }
 
+#[cfg(target_os = "linux")]
+/// Return the NUMA nodes that have cores allocated to the program, and their cores that are
+/// allocated to the program.
+pub fn get_numa_nodes() -> Result<Vec<NumaNode>, String> {
+    use std::mem::MaybeUninit;
+    const NODE_DIR: &str = "/sys/devices/system/node";
+
+    let allowed = unsafe {
+        let mut cs = MaybeUninit::zeroed().assume_init();
+        CPU_ZERO(&mut cs);
+        sched_getaffinity(0, std::mem::size_of::<cpu_set_t>(), &mut cs);
+        cs
+    };
+
+    let entries =
+        std::fs::read_dir(NODE_DIR).map_err(|e| format!("Failed to read {}: {}", NODE_DIR, e))?;
+    let mut nodes = vec![];
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(id) = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix("node"))
+            .and_then(|id| id.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let cpulist_path = entry.path().join("cpulist");
+        let cpulist = std::fs::read_to_string(&cpulist_path)
+            .map_err(|e| format!("Failed to read {}: {}", cpulist_path.display(), e))?;
+        let cpulist = cpulist.trim();
+        // Nodes with memory but no cores have an empty list.
+        if cpulist.is_empty() {
+            continue;
+        }
+        let cores = AffinityKind::parse_core_list(cpulist)?
+            .into_iter()
+            .filter(|cpu| {
+                (*cpu as usize) < CPU_SETSIZE as usize
+                    && unsafe { CPU_ISSET(*cpu as usize, &allowed) }
+            })
+            .collect::<Vec<_>>();
+        if !cores.is_empty() {
+            nodes.push(NumaNode { id, cores });
+        }
+    }
+
+    if nodes.is_empty() {
+        return Err(format!(
+            "No NUMA node with allocated cores is found in {}",
+            NODE_DIR
+        ));
+    }
+    nodes.sort_unstable_by_key(|node| node.id);
+    Ok(nodes)
+}
+
+#[cfg(not(target_os = "linux"))]
+/// Return the NUMA nodes that have cores allocated to the program, and their cores that are
+/// allocated to the program.
+pub fn get_numa_nodes() -> Result<Vec<NumaNode>, String> {
+    Err("NUMA affinity is only supported on Linux".to_string())
+}
+
 impl AffinityKind {
     /// Resolve affinity of GC thread. Has a side-effect of calling into the kernel to set the
     /// thread affinity. Note that we assume that each GC thread is equivalent to an OS or hardware
@@ -36,26 +114,45 @@ impl AffinityKind {
             AffinityKind::RoundRobin(cpuset) => {
                 let cpu = cpuset[thread % cpuset.len()];
                 debug!("Set affinity for thread {} to core {}", thread, cpu);
-                bind_current_thread_to_core(cpu);
+                bind_current_thread_to_cores(&[cpu]);
             }
+            AffinityKind::Numa(nodes) => {
+                let node = &nodes[thread % nodes.len()];
+                debug!(
+                    "Set affinity for thread {} to NUMA node {}",
+                    thread, node.id
+                );
+                bind_current_thread_to_cores(&node.cores);
+                CURRENT_NUMA_NODE.with(|current| current.set(Some(node.id)));
+            }
+        }
+    }
+
+    /// Get the NUMA node of a GC thread. Return `None` if threads are not bound to NUMA nodes.
+    pub fn numa_node_of(&self, thread: ThreadId) -> Option<usize> {
+        match self {
+            AffinityKind::Numa(nodes) => Some(nodes[thread % nodes.len()].id),
+            _ => None,
         }
     }
 }
 
 #[cfg(target_os = "linux")]
-/// Bind the current thread to the specified core.
-fn bind_current_thread_to_core(cpu: CoreId) {
+/// Bind the current thread to the specified cores.
+fn bind_current_thread_to_cores(cpus: &[CoreId]) {
     use std::mem::MaybeUninit;
     unsafe {
         let mut cs = MaybeUninit::zeroed().assume_init();
         CPU_ZERO(&mut cs);
-        CPU_SET(cpu as usize, &mut cs);
+        for cpu in cpus {
+            CPU_SET(*cpu as usize, &mut cs);
+        }
         sched_setaffinity(0, std::mem::size_of::<cpu_set_t>(), &cs);
     }
 }
 
 #[cfg(not(target_os = "linux"))]
-/// Bind the current thread to the specified core.
-fn bind_current_thread_to_core(_cpu: CoreId) {
+/// Bind the current thread to the specified cores.
+fn bind_current_thread_to_cores(_cpus: &[CoreId]) {
     unimplemented!()
 }
@@ -0,0 +1,95 @@
+//! This module contains `DynamicWorkers` which decides how many GC workers take part in each GC.
+//!
+//! By default, every GC wakes up all GC workers.  If [`Options::work_per_gc_thread`] is set, each
+//! GC only wakes up enough workers for the work it is expected to do, and no more workers than the
+//! CPUs available to the process.  The other workers stay parked throughout the GC.
+//!
+//! [`Options::work_per_gc_thread`]: crate::util::options::Options::work_per_gc_thread
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::util::conversions;
+use crate::vm::VMBinding;
+use crate::MMTK;
+
+pub(crate) struct DynamicWorkers {
+    /// The expected bytes of GC work for each worker, or 0 if all workers take part in every GC.
+    work_per_worker: usize,
+    /// The bytes used by the plan after the last GC, or `usize::MAX` before the first GC.
+    live_bytes: AtomicUsize,
+}
+
+impl DynamicWorkers {
+    pub fn new(work_per_worker: usize) -> Self {
+        Self {
+            work_per_worker,
+            live_bytes: AtomicUsize::new(usize::MAX),
+        }
+    }
+
+    /// Return the number of workers, out of `worker_count` workers, that should take part in the
+    /// GC that is about to start.
+    pub fn workers_for_next_gc<VM: VMBinding>(
+        &self,
+        mmtk: &MMTK<VM>,
+        worker_count: usize,
+    ) -> usize {
+        let plan = mmtk.get_plan();
+        // Concurrent work runs outside GC, and is not considered here.
+        if self.work_per_worker == 0 || plan.concurrent().is_some() {
+            return worker_count;
+        }
+
+        // A generational plan is expected to do a nursery GC.  Otherwise, a GC is expected to
+        // trace as many objects as were live after the last GC, or all the used memory before the
+        // first GC.
+        let work = if plan.generational().is_some() {
+            mmtk.gc_trigger.get_max_nursery_bytes()
+        } else {
+            let used_bytes = conversions::pages_to_bytes(plan.get_used_pages());
+            used_bytes.min(self.live_bytes.load(Ordering::Relaxed))
+        };
+        let cpus = std::thread::available_parallelism().map_or(worker_count, |n| n.get());
+        let workers = self.workers_for_work(work, worker_count, cpus);
+        debug!(
+            "Expect {} bytes of GC work.  Use {} of {} workers.",
+            work, workers, worker_count
+        );
+        workers
+    }
+
+    /// Return the number of workers for `work` bytes of GC work, given `worker_count` workers and
+    /// `cpus` available CPUs.  At least one worker takes part in a GC.
+    fn workers_for_work(&self, work: usize, worker_count: usize, cpus: usize) -> usize {
+        // TODO: Use `usize::div_ceil` after bumping MSRV.
+        let workers = work / self.work_per_worker + usize::from(work % self.work_per_worker != 0);
+        workers.clamp(1, worker_count.min(cpus).max(1))
+    }
+
+    /// Called when a GC finishes.  `used_bytes` is the bytes used by the plan after the GC.
+    pub fn on_gc_finished(&self, used_bytes: usize) {
+        self.live_bytes.store(used_bytes, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: usize = 1 << 20;
+
+    #[test]
+    fn workers_for_work() {
+        let dynamic = DynamicWorkers::new(4 * MB);
+        // Enough workers for the work.
+        assert_eq!(dynamic.workers_for_work(0, 64, 64), 1);
+        assert_eq!(dynamic.workers_for_work(4 * MB, 64, 64), 1);
+        assert_eq!(dynamic.workers_for_work(4 * MB + 1, 64, 64), 2);
+        assert_eq!(dynamic.workers_for_work(40 * MB, 64, 64), 10);
+        // No more than the workers and the CPUs.
+        assert_eq!(dynamic.workers_for_work(1024 * MB, 64, 64), 64);
+        assert_eq!(dynamic.workers_for_work(1024 * MB, 64, 8), 8);
+        assert_eq!(dynamic.workers_for_work(1024 * MB, 4, 8), 4);
+        assert_eq!(dynamic.workers_for_work(1024 * MB, 4, 0), 1);
+    }
+}
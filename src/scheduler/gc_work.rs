@@ -3,8 +3,9 @@ use super::*;
 use crate::global_state::GcStatus;
 use crate::plan::ObjectsClosure;
 use crate::plan::VectorObjectQueue;
+use crate::util::constants::LOG_BYTES_IN_ADDRESS;
 use crate::util::*;
-use crate::vm::slot::Slot;
+use crate::vm::slot::{MemorySlice, Slot};
 use crate::vm::*;
 use crate::*;
 use std::marker::PhantomData;
@@ -16,11 +17,14 @@ impl<VM: VMBinding> GCWork<VM> for ScheduleCollection {
     fn do_work(&mut self, worker: &mut GCWorker<VM>, mmtk: &'static MMTK<VM>) {
         // Tell GC trigger that GC started.
         mmtk.gc_trigger.policy.on_gc_start(mmtk);
+        mmtk.gc_trigger.idle.on_gc_start();
 
         // Determine collection kind
         let is_emergency = mmtk.state.set_collection_kind(
             mmtk.get_plan().last_collection_was_exhaustive(),
             mmtk.gc_trigger.policy.can_heap_size_grow(),
+            // Clear soft references for the soft heap limit.
+            mmtk.gc_trigger.soft_limit.is_clearing_soft_references(),
         );
         if is_emergency {
             mmtk.get_plan().notify_emergency_collection();
@@ -71,7 +75,7 @@ impl<C: GCWorkContext> GCWork<C::VM> for Prepare<C> {
             mmtk.scheduler.work_buckets[WorkBucketStage::Prepare].bulk_add(prepare_mutator_packets);
         }
 
-        for w in &mmtk.scheduler.worker_group.workers_shared {
+        for w in mmtk.scheduler.active_workers_shared() {
             let result = w.designated_work.push(Box::new(PrepareCollector));
             debug_assert!(result.is_ok());
         }
@@ -149,7 +153,7 @@ impl<C: GCWorkContext + 'static> GCWork<C::VM> for Release<C> {
         );
         mmtk.scheduler.work_buckets[WorkBucketStage::Release].bulk_add(release_mutator_packets);
 
-        for w in &mmtk.scheduler.worker_group.workers_shared {
+        for w in mmtk.scheduler.active_workers_shared() {
             let result = w.designated_work.push(Box::new(ReleaseCollector));
             debug_assert!(result.is_ok());
         }
@@ -224,6 +228,23 @@ impl<C: GCWorkContext> GCWork<C::VM> for StopMutators<C> {
     }
 }
 
+/// Stop all mutators for an incremental pause.  Unlike [`StopMutators`], it does not scan roots.
+/// See [`crate::util::options::PauseBudget`].
+pub struct StopMutatorsForIncrementalPause;
+
+impl<VM: VMBinding> GCWork<VM> for StopMutatorsForIncrementalPause {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, mmtk: &'static MMTK<VM>) {
+        trace!("stop_all_mutators start");
+        <VM as VMBinding>::VMCollection::stop_all_mutators(worker.tls, |mutator| {
+            // Hand the buffers of the barrier (e.g. the SATB barrier) to GC workers.
+            mutator.flush();
+        });
+        trace!("stop_all_mutators end");
+        mmtk.scheduler
+            .notify_mutators_paused_for_incremental_pause();
+    }
+}
+
 /// This implements `ObjectTracer` by forwarding the `trace_object` calls to the wrapped
 /// `ProcessEdgesWork` instance.
 pub(crate) struct ProcessEdgesWorkTracer<E: ProcessEdgesWork> {
@@ -429,6 +450,10 @@ impl<C: GCWorkContext> GCWork<C::VM> for ScanMutatorRoots<C> {
             mmtk.set_gc_status(GcStatus::GcProper);
         }
     }
+
+    fn priority(&self) -> WorkPriority {
+        WorkPriority::High
+    }
 }
 
 #[derive(Default)]
@@ -450,6 +475,10 @@ impl<C: GCWorkContext> GCWork<C::VM> for ScanVMSpecificRoots<C> {
         >::new(mmtk);
         <C::VM as VMBinding>::VMScanning::scan_vm_specific_roots(worker.tls, factory);
     }
+
+    fn priority(&self) -> WorkPriority {
+        WorkPriority::High
+    }
 }
 
 pub struct ProcessEdgesBase<VM: VMBinding> {
@@ -661,6 +690,34 @@ impl<E: ProcessEdgesWork> GCWork<E::VM> for E {
         }
         trace!("ProcessEdgesWork End");
     }
+
+    fn priority(&self) -> WorkPriority {
+        if self.roots {
+            WorkPriority::High
+        } else {
+            WorkPriority::Normal
+        }
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn split_off(&mut self) -> Option<Box<dyn GCWork<E::VM>>> {
+        // With extreme assertions, slots are logged when a packet is created, and must not be
+        // logged twice.
+        if cfg!(feature = "extreme_assertions") || self.slots.len() < 2 {
+            return None;
+        }
+        let half = self.slots.len() / 2;
+        let slots = self.slots.split_off(half);
+        Some(Box::new(E::new(
+            slots,
+            self.roots,
+            self.mmtk(),
+            self.bucket,
+        )))
+    }
 }
 
 /// A general implementation of [`ProcessEdgesWork`] using SFT. A plan can always implement their
@@ -846,9 +903,22 @@ pub trait ScanObjectsWork<VM: VMBinding>: GCWork<VM> + Sized {
 
             for object in objects_to_scan.iter().copied() {
                 if <VM as VMBinding>::VMScanning::support_slot_enqueuing(tls, object) {
-                    trace!("Scan object (slot) {}", object);
-                    // If an object supports slot-enqueuing, we enqueue its slots.
-                    <VM as VMBinding>::VMScanning::scan_object(tls, object, &mut closure);
+                    if let Some(slice) =
+                        <VM as VMBinding>::VMScanning::reference_array_slice(tls, object)
+                    {
+                        trace!("Scan object (slice) {}", object);
+                        // Large arrays are scanned in separate packets that can be split.
+                        let work = ScanSlice::<Self::E>::new(slice, self.get_bucket());
+                        if work.estimated_cost() > GCWorker::<VM>::LONG_WORK_PACKET_COST {
+                            closure.worker.add_work(self.get_bucket(), work);
+                        } else {
+                            work.scan_slots(&mut closure);
+                        }
+                    } else {
+                        trace!("Scan object (slot) {}", object);
+                        // If an object supports slot-enqueuing, we enqueue its slots.
+                        <VM as VMBinding>::VMScanning::scan_object(tls, object, &mut closure);
+                    }
                     self.post_scan_object(object);
                 } else {
                     // If an object does not support slot-enqueuing, we have to use
@@ -889,6 +959,53 @@ pub trait ScanObjectsWork<VM: VMBinding>: GCWork<VM> + Sized {
     }
 }
 
+/// Enqueue the slots in a memory slice, usually the elements of a large reference array reported
+/// by [`Scanning::reference_array_slice`].  Long packets are split so that different workers can
+/// scan parts of the slice in parallel.
+pub struct ScanSlice<E: ProcessEdgesWork> {
+    slice: <E::VM as VMBinding>::VMMemorySlice,
+    bucket: WorkBucketStage,
+    phantom: PhantomData<E>,
+}
+
+impl<E: ProcessEdgesWork> ScanSlice<E> {
+    pub fn new(slice: <E::VM as VMBinding>::VMMemorySlice, bucket: WorkBucketStage) -> Self {
+        Self {
+            slice,
+            bucket,
+            phantom: PhantomData,
+        }
+    }
+
+    fn scan_slots(&self, closure: &mut ObjectsClosure<E>) {
+        for slot in self.slice.iter_slots() {
+            closure.visit_slot(slot);
+        }
+    }
+}
+
+impl<E: ProcessEdgesWork> GCWork<E::VM> for ScanSlice<E> {
+    fn do_work(&mut self, worker: &mut GCWorker<E::VM>, _mmtk: &'static MMTK<E::VM>) {
+        trace!("ScanSlice {:?}", self.slice);
+        self.scan_slots(&mut ObjectsClosure::new(worker, self.bucket));
+        trace!("ScanSlice End");
+    }
+
+    fn priority(&self) -> WorkPriority {
+        WorkPriority::High
+    }
+
+    fn estimated_cost(&self) -> usize {
+        // Assume that slots are words.
+        self.slice.bytes() >> LOG_BYTES_IN_ADDRESS
+    }
+
+    fn split_off(&mut self) -> Option<Box<dyn GCWork<E::VM>>> {
+        let rest = self.slice.split_off(self.slice.bytes() / 2)?;
+        Some(Box::new(Self::new(rest, self.bucket)))
+    }
+}
+
 /// Scan objects and enqueue the slots of the objects.  For objects that do not support
 /// slot-enqueuing, this work packet also traces their outgoing edges directly.
 ///
@@ -933,6 +1050,18 @@ impl<E: ProcessEdgesWork> GCWork<E::VM> for ScanObjects<E> {
         self.do_work_common(&self.buffer, worker, mmtk);
         trace!("ScanObjects End");
     }
+
+    fn estimated_cost(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn split_off(&mut self) -> Option<Box<dyn GCWork<E::VM>>> {
+        if self.buffer.len() < 2 {
+            return None;
+        }
+        let buffer = self.buffer.split_off(self.buffer.len() / 2);
+        Some(Box::new(Self::new(buffer, self.concurrent, self.bucket)))
+    }
 }
 
 use crate::mmtk::MMTK;
@@ -1152,6 +1281,22 @@ impl<VM: VMBinding, R2OPE: ProcessEdgesWork<VM = VM>, O2OPE: ProcessEdgesWork<VM
 
         trace!("ProcessRootNode End");
     }
+
+    fn priority(&self) -> WorkPriority {
+        WorkPriority::High
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.roots.len()
+    }
+
+    fn split_off(&mut self) -> Option<Box<dyn GCWork<VM>>> {
+        if self.roots.len() < 2 {
+            return None;
+        }
+        let roots = self.roots.split_off(self.roots.len() / 2);
+        Some(Box::new(Self::new(roots, self.bucket)))
+    }
 }
 
 /// A `ProcessEdgesWork` type that panics when any of its method is used.
@@ -0,0 +1,183 @@
+//! This module contains `IncrementalWork` which lets GC workers do concurrent work in bounded,
+//! stop-the-world pauses.
+//!
+//! By default, work packets in the `Concurrent` bucket are executed while mutators are running.
+//! If the [`PauseBudget`] option is not `Unbounded`, GC workers only execute concurrent work
+//! packets when mutators are stopped, either in a GC (such as the final-mark pause of a concurrent
+//! plan), or in an *incremental pause*.  An incremental pause is not a GC.  It only stops mutators,
+//! executes concurrent work packets until the budget runs out, and resumes mutators.  The state of
+//! concurrent work (such as marking) persists across incremental pauses.
+//!
+//! When an incremental pause ends, unexecuted work packets stay in the `Concurrent` bucket and in
+//! the local queues of GC workers, and will be executed in the next pause.
+//!
+//! The budget is checked between work packets, so concurrent work packets should be short.  Only
+//! plans with concurrent work honor the budget, which is currently ConcurrentImmix.  GCs of other
+//! plans, and the GC pauses of ConcurrentImmix, are not bounded.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::util::options::PauseBudget;
+
+pub(crate) struct IncrementalWork {
+    /// The budget of each incremental pause.
+    budget: PauseBudget,
+    /// True if GC workers may execute concurrent work packets now.  It is always true unless
+    /// concurrent work is done incrementally.
+    open: AtomicBool,
+    /// True if an incremental pause is in progress.
+    in_incremental_pause: AtomicBool,
+    /// The number of work packets executed in the current incremental pause.
+    packets: AtomicUsize,
+    /// When the current incremental pause started.
+    pause_start: Mutex<Instant>,
+    /// The next incremental pause should not start before this time.
+    next_pause: Mutex<Instant>,
+}
+
+impl IncrementalWork {
+    pub fn new(budget: PauseBudget) -> Self {
+        let now = Instant::now();
+        Self {
+            budget,
+            open: AtomicBool::new(true),
+            in_incremental_pause: AtomicBool::new(false),
+            packets: AtomicUsize::new(0),
+            pause_start: Mutex::new(now),
+            next_pause: Mutex::new(now),
+        }
+    }
+
+    /// Return true if concurrent work is done in incremental pauses.
+    pub fn is_enabled(&self) -> bool {
+        self.budget.is_incremental()
+    }
+
+    /// Return true if GC workers may execute concurrent work packets, including the packets in
+    /// their local queues and the packets they steal from other workers.
+    pub fn may_do_concurrent_work(&self) -> bool {
+        if !self.open.load(Ordering::Acquire) {
+            return false;
+        }
+        if let PauseBudget::Time(micros) = self.budget {
+            if self.in_incremental_pause.load(Ordering::Relaxed)
+                && self.pause_start.lock().unwrap().elapsed() >= Duration::from_micros(micros)
+            {
+                self.open.store(false, Ordering::Release);
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Called when a GC worker is about to execute a work packet.
+    pub fn on_work_polled(&self) {
+        if let PauseBudget::WorkPackets(max) = self.budget {
+            if self.in_incremental_pause.load(Ordering::Relaxed)
+                && self.packets.fetch_add(1, Ordering::Relaxed) + 1 >= max
+            {
+                self.open.store(false, Ordering::Release);
+            }
+        }
+    }
+
+    /// Called when mutators are stopped for a GC.  There is no budget in GC pauses.
+    pub fn on_gc_mutators_paused(&self) {
+        self.open.store(true, Ordering::Release);
+    }
+
+    /// Called at the end of a GC that took `elapsed`.  If `concurrent_work_pending` is true, the
+    /// remaining concurrent work will be done in incremental pauses.
+    pub fn on_gc_finished(&self, concurrent_work_pending: bool, elapsed: Duration) {
+        if self.is_enabled() && concurrent_work_pending {
+            self.open.store(false, Ordering::Release);
+            *self.next_pause.lock().unwrap() = Instant::now() + elapsed;
+        } else {
+            self.open.store(true, Ordering::Release);
+        }
+    }
+
+    /// Called when mutators are stopped for an incremental pause.
+    pub fn start_incremental_pause(&self) {
+        debug_assert!(!self.open.load(Ordering::Relaxed));
+        *self.pause_start.lock().unwrap() = Instant::now();
+        self.packets.store(0, Ordering::Relaxed);
+        self.in_incremental_pause.store(true, Ordering::Relaxed);
+        self.open.store(true, Ordering::Release);
+    }
+
+    /// Called by the last parked worker at the end of an incremental pause, right before resuming
+    /// mutators.
+    pub fn finish_incremental_pause(&self) {
+        self.open.store(false, Ordering::Release);
+        self.in_incremental_pause.store(false, Ordering::Relaxed);
+        let elapsed = self.pause_start.lock().unwrap().elapsed();
+        trace!("Incremental pause finished.  Took {:?}", elapsed);
+        *self.next_pause.lock().unwrap() = Instant::now() + elapsed;
+    }
+
+    /// Return how long mutators should keep running before the next incremental pause.
+    pub fn time_until_next_pause(&self) -> Duration {
+        self.next_pause
+            .lock()
+            .unwrap()
+            .saturating_duration_since(Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unbounded() {
+        let incremental = IncrementalWork::new(PauseBudget::Unbounded);
+        incremental.on_gc_finished(true, Duration::ZERO);
+        assert!(incremental.may_do_concurrent_work());
+    }
+
+    #[test]
+    fn test_work_packets_budget() {
+        let incremental = IncrementalWork::new(PauseBudget::WorkPackets(2));
+        incremental.on_gc_finished(true, Duration::ZERO);
+        assert!(!incremental.may_do_concurrent_work());
+
+        incremental.start_incremental_pause();
+        assert!(incremental.may_do_concurrent_work());
+        incremental.on_work_polled();
+        assert!(incremental.may_do_concurrent_work());
+        incremental.on_work_polled();
+        assert!(!incremental.may_do_concurrent_work());
+        incremental.finish_incremental_pause();
+
+        // The budget is renewed in the next pause.
+        incremental.start_incremental_pause();
+        assert!(incremental.may_do_concurrent_work());
+        incremental.finish_incremental_pause();
+        assert!(!incremental.may_do_concurrent_work());
+
+        // GC pauses are not bounded.
+        incremental.on_gc_mutators_paused();
+        incremental.on_work_polled();
+        incremental.on_work_polled();
+        assert!(incremental.may_do_concurrent_work());
+        incremental.on_gc_finished(false, Duration::ZERO);
+        assert!(incremental.may_do_concurrent_work());
+    }
+
+    #[test]
+    fn test_time_budget() {
+        let incremental = IncrementalWork::new(PauseBudget::Time(1000));
+        incremental.on_gc_finished(true, Duration::ZERO);
+        incremental.start_incremental_pause();
+        assert!(incremental.may_do_concurrent_work());
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!incremental.may_do_concurrent_work());
+        incremental.finish_incremental_pause();
+
+        // Mutators run at least as long as the last pause.
+        assert!(incremental.time_until_next_pause() > Duration::ZERO);
+    }
+}
@@ -6,12 +6,16 @@ pub(crate) mod affinity;
 mod scheduler;
 pub(crate) use scheduler::GCWorkScheduler;
 
+mod dynamic_workers;
+mod incremental;
 mod stat;
+#[cfg(feature = "timeline")]
+mod timeline;
 mod work_counter;
 
 mod work;
-pub use work::GCWork;
 pub(crate) use work::GCWorkContext;
+pub use work::{GCWork, WorkPriority};
 
 mod work_bucket;
 pub use work_bucket::WorkBucketStage;
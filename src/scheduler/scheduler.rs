@@ -1,16 +1,21 @@
 use self::worker::PollResult;
 
-use super::gc_work::ScheduleCollection;
+use super::dynamic_workers::DynamicWorkers;
+use super::gc_work::{ScheduleCollection, StopMutatorsForIncrementalPause};
+use super::incremental::IncrementalWork;
 use super::stat::SchedulerStat;
+#[cfg(feature = "timeline")]
+use super::timeline::Timeline;
 use super::work_bucket::*;
-use super::worker::{GCWorker, ThreadId, WorkerGroup};
+use super::worker::{GCWorker, GCWorkerShared, ThreadId, WorkerGroup};
 use super::worker_goals::{WorkerGoal, WorkerGoals};
 use super::worker_monitor::{LastParkedResult, WorkerMonitor};
 use super::*;
 use crate::global_state::GcStatus;
 use crate::mmtk::MMTK;
+use crate::util::conversions;
 use crate::util::opaque_pointer::*;
-use crate::util::options::AffinityKind;
+use crate::util::options::{AffinityKind, PauseBudget};
 use crate::util::rust_util::array_from_fn;
 use crate::vm::Collection;
 use crate::vm::VMBinding;
@@ -30,6 +35,14 @@ pub struct GCWorkScheduler<VM: VMBinding> {
     pub(crate) worker_monitor: Arc<WorkerMonitor>,
     /// How to assign the affinity of each GC thread. Specified by the user.
     affinity: AffinityKind,
+    /// Decides when workers may execute concurrent work packets if they are executed in
+    /// incremental pauses.
+    pub(crate) incremental: IncrementalWork,
+    /// Decides how many workers take part in each GC.
+    dynamic_workers: DynamicWorkers,
+    /// Records the timeline of work packets, work buckets and GC phases.
+    #[cfg(feature = "timeline")]
+    pub(crate) timeline: Timeline,
 }
 
 // FIXME: GCWorkScheduler should be naturally Sync, but we cannot remove this `impl` yet.
@@ -38,7 +51,13 @@ pub struct GCWorkScheduler<VM: VMBinding> {
 unsafe impl<VM: VMBinding> Sync for GCWorkScheduler<VM> {}
 
 impl<VM: VMBinding> GCWorkScheduler<VM> {
-    pub fn new(num_workers: usize, affinity: AffinityKind) -> Arc<Self> {
+    pub fn new(
+        num_workers: usize,
+        affinity: AffinityKind,
+        pause_budget: PauseBudget,
+        work_per_worker: usize,
+        #[cfg_attr(not(feature = "timeline"), allow(unused_variables))] timeline_capacity: usize,
+    ) -> Arc<Self> {
         let worker_monitor: Arc<WorkerMonitor> = Arc::new(WorkerMonitor::new(num_workers));
         let worker_group = WorkerGroup::new(num_workers);
 
@@ -56,10 +75,10 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
             let mut open_stages: Vec<WorkBucketStage> = vec![first_stw_stage];
             let stages = (0..WorkBucketStage::LENGTH).map(WorkBucketStage::from_usize);
             for stage in stages {
-                // Unconstrained is always open.
+                // Unconstrained is always open, and Concurrent is not part of the pause.
                 // The first STW stage (Prepare) will be opened when the world stopped
                 // (i.e. when all mutators are suspended).
-                if stage != WorkBucketStage::Unconstrained && stage != first_stw_stage {
+                if stage.is_stw() && stage != first_stw_stage {
                     // Other work packets will be opened after previous stages are done
                     // (i.e their buckets are drained and all workers parked).
                     let cur_stages = open_stages.clone();
@@ -78,6 +97,10 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
             worker_group,
             worker_monitor,
             affinity,
+            incremental: IncrementalWork::new(pause_budget),
+            dynamic_workers: DynamicWorkers::new(work_per_worker),
+            #[cfg(feature = "timeline")]
+            timeline: Timeline::new(timeline_capacity),
         })
     }
 
@@ -85,6 +108,21 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         self.worker_group.as_ref().worker_count()
     }
 
+    /// Get the shared parts of the workers that take part in the current GC.
+    pub(crate) fn active_workers_shared(&self) -> impl Iterator<Item = &Arc<GCWorkerShared<VM>>> {
+        self.worker_group
+            .workers_shared
+            .iter()
+            .enumerate()
+            .filter(|(ordinal, _)| self.worker_monitor.is_worker_active(*ordinal))
+            .map(|(_, shared)| shared)
+    }
+
+    /// Get the number of workers that take part in the current GC.
+    pub(crate) fn num_active_workers(&self) -> usize {
+        self.active_workers_shared().count()
+    }
+
     /// Create GC threads for the first time.  It will also create the `GCWorker` instances.
     ///
     /// Currently GC threads only include worker threads, and we currently have only one worker
@@ -271,6 +309,8 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
             buckets_updated = buckets_updated || bucket_opened;
             if bucket_opened {
                 probe!(mmtk, bucket_opened, id);
+                #[cfg(feature = "timeline")]
+                self.timeline.bucket_opened(id);
                 new_packets = new_packets || !bucket.is_drained();
                 if new_packets {
                     // Quit the loop. There are already new packets in the newly opened buckets.
@@ -290,7 +330,9 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
 
     pub fn deactivate_all(&self) {
         self.work_buckets.iter().for_each(|(id, bkt)| {
-            if id != WorkBucketStage::Unconstrained {
+            if id.is_stw() {
+                #[cfg(feature = "timeline")]
+                self.timeline.bucket_closed(id);
                 bkt.deactivate();
             }
         });
@@ -299,7 +341,7 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
     pub fn reset_state(&self) {
         let first_stw_stage = WorkBucketStage::first_stw_stage();
         self.work_buckets.iter().for_each(|(id, bkt)| {
-            if id != WorkBucketStage::Unconstrained && id != first_stw_stage {
+            if id.is_stw() && id != first_stw_stage {
                 bkt.deactivate();
             }
         });
@@ -308,7 +350,7 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
     pub fn debug_assert_all_buckets_deactivated(&self) {
         if cfg!(debug_assertions) {
             self.work_buckets.iter().for_each(|(id, bkt)| {
-                if id != WorkBucketStage::Unconstrained {
+                if id.is_stw() {
                     assert!(!bkt.is_activated());
                 }
             });
@@ -340,19 +382,41 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         if let Some(w) = worker.shared.designated_work.pop() {
             return Steal::Success(w);
         }
+        let may_do_concurrent_work = self.incremental.may_do_concurrent_work();
         // Try get a packet from a work bucket.
-        for work_bucket in self.work_buckets.values() {
+        for (stage, work_bucket) in self.work_buckets.iter() {
+            if stage == WorkBucketStage::Concurrent && !may_do_concurrent_work {
+                continue;
+            }
             match work_bucket.poll(&worker.local_work_buffer) {
                 Steal::Success(w) => return Steal::Success(w),
                 Steal::Retry => should_retry = true,
                 _ => {}
             }
         }
-        // Try steal some packets from any worker
-        for (id, worker_shared) in self.worker_group.workers_shared.iter().enumerate() {
-            if id == worker.ordinal {
-                continue;
-            }
+        // Try steal some packets from any worker.  If we may not do concurrent work now, the
+        // packets in the local queues of other workers must be concurrent work, too.
+        if !may_do_concurrent_work {
+            return if should_retry {
+                Steal::Retry
+            } else {
+                Steal::Empty
+            };
+        }
+        // Prefer workers on the same NUMA node, if workers are bound to NUMA nodes.
+        let node = self.affinity.numa_node_of(worker.ordinal);
+        let others = self
+            .worker_group
+            .workers_shared
+            .iter()
+            .enumerate()
+            .filter(|(id, _)| *id != worker.ordinal);
+        let same_node = |(id, _): &(usize, _)| self.affinity.numa_node_of(*id) == node;
+        let victims = others
+            .clone()
+            .filter(same_node)
+            .chain(others.filter(|victim| !same_node(victim)));
+        for (_, worker_shared) in victims {
             match worker_shared.stealer.as_ref().unwrap().steal() {
                 Steal::Success(w) => return Steal::Success(w),
                 Steal::Retry => should_retry = true,
@@ -420,9 +484,12 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
                 // We are in the progress of GC.
 
                 // In stop-the-world GC, mutators cannot request for GC while GC is in progress.
-                // When we support concurrent GC, we should remove this assertion.
+                // With a concurrent plan, a mutator may still be sending a request when the last
+                // parked worker requests a pause to finish the concurrent work.  That request
+                // will be responded to after the current GC.
                 assert!(
-                    !goals.debug_is_requested(WorkerGoal::Gc),
+                    worker.mmtk.get_plan().concurrent().is_some()
+                        || !goals.debug_is_requested(WorkerGoal::Gc),
                     "GC request sent to WorkerMonitor while GC is still in progress."
                 );
 
@@ -446,6 +513,16 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
                     self.respond_to_requests(worker, goals)
                 }
             }
+            WorkerGoal::IncrementalPause => {
+                // All workers parked.  Either the budget ran out, or there is no concurrent work
+                // left.  The rest of the concurrent work, if any, is done in the next pause.
+                trace!("The last worker parked in an incremental pause.  Resume mutators.");
+                self.on_incremental_pause_finished(worker);
+
+                // Clear the current goal
+                goals.on_current_goal_completed();
+                self.respond_to_requests(worker, goals)
+            }
             WorkerGoal::StopForFork => {
                 panic!(
                     "Worker {} parked again when it is asked to exit.",
@@ -464,7 +541,11 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         assert!(goals.current().is_none());
 
         let Some(goal) = goals.poll_next_goal() else {
-            // No requests.  Park this worker, too.
+            // No requests.  See if there is any concurrent work to do or to finish.
+            if let Some(result) = self.respond_to_concurrent_work(worker, goals) {
+                return result;
+            }
+            // Park this worker, too.
             return LastParkedResult::ParkSelf;
         };
 
@@ -475,6 +556,8 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
                 // We set the eBPF trace point here so that bpftrace scripts can start recording
                 // work packet events before the `ScheduleCollection` work packet starts.
                 probe!(mmtk, gc_start);
+                #[cfg(feature = "timeline")]
+                self.timeline.gc_started();
 
                 {
                     let mut gc_start_time = worker.mmtk.state.gc_start_time.borrow_mut();
@@ -482,16 +565,76 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
                     *gc_start_time = Some(Instant::now());
                 }
 
+                // Choose the workers for this GC, including this worker which will schedule it.
+                let active_workers = self
+                    .dynamic_workers
+                    .workers_for_next_gc(worker.mmtk, self.num_workers());
+                self.worker_monitor
+                    .set_active_workers(worker.ordinal, active_workers);
+
                 self.add_schedule_collection_packet();
                 LastParkedResult::WakeSelf
             }
+            WorkerGoal::IncrementalPause => {
+                trace!("Start an incremental pause.");
+                // We are still holding the mutex `WorkerMonitor::sync`.  Do not notify now.
+                self.work_buckets[WorkBucketStage::Unconstrained]
+                    .add_no_notify(StopMutatorsForIncrementalPause);
+                LastParkedResult::WakeSelf
+            }
             WorkerGoal::StopForFork => {
                 trace!("A mutator wanted to fork.");
+                // Surplus workers need to exit, too.
+                self.worker_monitor
+                    .set_active_workers(worker.ordinal, self.num_workers());
                 LastParkedResult::WakeAll
             }
         }
     }
 
+    /// Called by the last parked worker when there are no requests.  If the `Concurrent` bucket is
+    /// open, either wake up workers to do the remaining concurrent work (or start an incremental
+    /// pause to do it), or, if all concurrent work packets have been executed, request a GC so
+    /// that the plan can finish its concurrent work in a pause.  Return `None` if there is no
+    /// concurrent work in progress.
+    fn respond_to_concurrent_work(
+        &self,
+        worker: &GCWorker<VM>,
+        goals: &mut WorkerGoals,
+    ) -> Option<LastParkedResult> {
+        let concurrent_bucket = &self.work_buckets[WorkBucketStage::Concurrent];
+        if !concurrent_bucket.is_activated() {
+            return None;
+        }
+
+        // An incremental pause may end before workers drain their local queues.
+        if !concurrent_bucket.is_empty() || self.worker_group.has_local_work() {
+            if self.incremental.is_enabled() {
+                let wait = self.incremental.time_until_next_pause();
+                if !wait.is_zero() {
+                    trace!("Let mutators run for {:?} before the next pause.", wait);
+                    return Some(LastParkedResult::ParkSelfFor(wait));
+                }
+                goals.set_request(WorkerGoal::IncrementalPause);
+                return Some(self.respond_to_requests(worker, goals));
+            }
+
+            // Mutators may add work packets (e.g. by flushing barrier buffers) right after we
+            // polled the bucket.
+            trace!("Found concurrent work packets added by mutators.");
+            return Some(LastParkedResult::WakeAll);
+        }
+
+        trace!("Concurrent work drained.  Request a GC to finish it.");
+        let mmtk = worker.mmtk;
+        mmtk.state.set_internal_triggered_collection();
+        // We are holding the mutex of `WorkerMonitor`, so we add the request to `goals` directly
+        // instead of going through `GCRequester::request`.
+        mmtk.gc_requester.set_request_flag();
+        goals.set_request(WorkerGoal::Gc);
+        Some(self.respond_to_requests(worker, goals))
+    }
+
     /// Find more work for workers to do.  Return true if more work is available.
     fn find_more_work_for_workers(&self) -> bool {
         if self.worker_group.has_designated_work() {
@@ -519,7 +662,12 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
     fn on_gc_finished(&self, worker: &GCWorker<VM>) {
         // All GC workers must have parked by now.
         debug_assert!(!self.worker_group.has_designated_work());
-        debug_assert!(self.all_buckets_empty());
+        // Concurrent work packets created during the pause (e.g. by root scanning in an
+        // initial-mark pause) may still be pending.
+        debug_assert!(self
+            .work_buckets
+            .iter()
+            .all(|(id, bkt)| id == WorkBucketStage::Concurrent || bkt.is_empty()));
 
         // Deactivate all work buckets to prepare for the next GC.
         self.deactivate_all();
@@ -529,13 +677,44 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
 
         // Tell GC trigger that GC ended - this happens before we resume mutators.
         mmtk.gc_trigger.policy.on_gc_end(mmtk);
+        // Return free memory to the OS if the policy shrunk the heap.
+        mmtk.gc_trigger.decommit_free_memory();
+        let concurrent_work_in_progress = mmtk
+            .get_plan()
+            .concurrent()
+            .is_some_and(|plan| plan.concurrent_work_in_progress());
+        mmtk.gc_trigger.idle.on_gc_end(
+            mmtk.get_plan().get_reserved_pages(),
+            mmtk.get_plan().get_total_pages(),
+            concurrent_work_in_progress,
+        );
+        mmtk.gc_trigger
+            .soft_limit
+            .on_gc_end(mmtk.get_plan().get_used_pages());
+        mmtk.gc_trigger.check_space_budgets();
+        self.dynamic_workers
+            .on_gc_finished(conversions::pages_to_bytes(
+                mmtk.get_plan().get_used_pages(),
+            ));
 
         // All other workers are parked, so it is safe to access the Plan instance mutably.
         probe!(mmtk, plan_end_of_gc_begin);
+        #[cfg(feature = "timeline")]
+        let end_of_gc_begin = self.timeline.now();
         let plan_mut: &mut dyn Plan<VM = VM> = unsafe { mmtk.get_plan_mut() };
         plan_mut.end_of_gc(worker.tls);
+        #[cfg(feature = "timeline")]
+        self.timeline.phase("Plan::end_of_gc", end_of_gc_begin);
         probe!(mmtk, plan_end_of_gc_end);
 
+        // Let workers carry on with concurrent work, if any, after mutators resume.
+        let concurrent_bucket = &self.work_buckets[WorkBucketStage::Concurrent];
+        if concurrent_work_in_progress {
+            concurrent_bucket.activate();
+        } else if concurrent_bucket.is_activated() {
+            concurrent_bucket.deactivate();
+        }
+
         // Compute the elapsed time of the GC.
         let start_time = {
             let mut gc_start_time = worker.mmtk.state.gc_start_time.borrow_mut();
@@ -543,6 +722,10 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         };
         let elapsed = start_time.elapsed();
 
+        // If the concurrent work is done incrementally, hold it until the next incremental pause.
+        self.incremental
+            .on_gc_finished(concurrent_work_in_progress, elapsed);
+
         info!(
             "End of GC ({}/{} pages, took {} ms)",
             mmtk.get_plan().get_reserved_pages(),
@@ -552,6 +735,8 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
 
         // USDT tracepoint for the end of GC.
         probe!(mmtk, gc_end);
+        #[cfg(feature = "timeline")]
+        self.timeline.gc_finished();
 
         if *mmtk.get_options().count_live_bytes_in_gc {
             for (space_name, &stats) in mmtk.state.live_bytes_in_last_gc.borrow().iter() {
@@ -578,6 +763,15 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         <VM as VMBinding>::VMCollection::resume_mutators(worker.tls);
     }
 
+    /// Called when all workers have parked in an incremental pause.
+    fn on_incremental_pause_finished(&self, worker: &GCWorker<VM>) {
+        // All GC workers must have parked by now.
+        debug_assert!(!self.worker_group.has_designated_work());
+
+        self.incremental.finish_incremental_pause();
+        <VM as VMBinding>::VMCollection::resume_mutators(worker.tls);
+    }
+
     pub fn enable_stat(&self) {
         for worker in &self.worker_group.workers_shared {
             let worker_stat = worker.borrow_stat();
@@ -606,6 +800,17 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         // opening the first STW bucket.  In the future, we should redesign the opening condition
         // of work buckets to make the synchronization more robust,
         first_stw_bucket.activate();
+        #[cfg(feature = "timeline")]
+        self.timeline
+            .bucket_opened(WorkBucketStage::first_stw_stage());
+        // Concurrent work is not bounded in a GC, even if it is done incrementally.
+        self.incremental.on_gc_mutators_paused();
+        self.worker_monitor.notify_work_available(true);
+    }
+
+    /// Called when mutators are stopped for an incremental pause.
+    pub(crate) fn notify_mutators_paused_for_incremental_pause(&self) {
+        self.incremental.start_incremental_pause();
         self.worker_monitor.notify_work_available(true);
     }
 }
@@ -0,0 +1,316 @@
+//! This module contains `Timeline`, an in-process recorder of GC events.
+//!
+//! With the "timeline" Cargo feature, MMTk records when each GC worker executes each work packet,
+//! when each work bucket is opened and closed, and when each GC and some of its phases start and
+//! end.  Events are recorded into a fixed-size ring buffer without locking, so only the most recent
+//! events are kept.  How many are kept is set by [`Options::timeline_capacity`].  The timeline can be written in the Chrome trace event format with
+//! [`crate::memory_manager::dump_timeline`], or into the file specified by
+//! [`Options::timeline_file`] at `harness_end`.  The result can be opened with Perfetto UI
+//! (<https://ui.perfetto.dev/>) or `chrome://tracing`.
+//!
+//! Unlike the bpftrace scripts in `tools/tracing/timeline`, this does not need root privilege or
+//! USDT support, at the cost of recording fewer kinds of events.
+//!
+//! [`Options::timeline_capacity`]: crate::util::options::Options::timeline_capacity
+//! [`Options::timeline_file`]: crate::util::options::Options::timeline_file
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::sync::atomic::{fence, AtomicPtr, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::time::Instant;
+
+use enum_map::{Enum, EnumMap};
+
+use super::work_bucket::WorkBucketStage;
+use crate::util::rust_util::array_from_fn;
+
+/// The bucket open time of a bucket that is not open.
+const NOT_OPEN: u64 = u64::MAX;
+
+/// Chrome trace events are grouped into threads by `tid`.  Workers use their ordinals plus this.
+const FIRST_WORKER_TID: usize = 2;
+const PHASES_TID: usize = 0;
+const BUCKETS_TID: usize = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+enum EventKind {
+    /// A work packet executed by a worker.  The name is the type name of the packet.
+    WorkPacket,
+    /// A work bucket from its opening to its closing.  The name is the stage of the bucket.
+    Bucket,
+    /// A GC or a phase of a GC.
+    Phase,
+}
+
+impl EventKind {
+    fn from_u8(kind: u8) -> Self {
+        match kind {
+            0 => EventKind::WorkPacket,
+            1 => EventKind::Bucket,
+            2 => EventKind::Phase,
+            _ => unreachable!("Unknown event kind {}", kind),
+        }
+    }
+}
+
+/// A slot of the ring buffer.  All the fields are atomic so that a slot can be read while it is
+/// overwritten.  `seq` works as a sequence lock that tells if the other fields are consistent.
+#[derive(Default)]
+struct Slot {
+    /// The index of the event in this slot plus one, or 0 if the slot is being written or has
+    /// never been written.
+    seq: AtomicUsize,
+    kind: AtomicU8,
+    /// The worker ordinal for work packets.
+    worker: AtomicUsize,
+    /// The pointer to the name.  Null for buckets.
+    name_ptr: AtomicPtr<u8>,
+    /// The length of the name, or the stage for buckets.
+    name_len: AtomicUsize,
+    /// Nanoseconds since the timeline is created.
+    begin: AtomicU64,
+    end: AtomicU64,
+}
+
+/// An event read from the ring buffer.
+struct Event {
+    kind: EventKind,
+    worker: usize,
+    name_ptr: *mut u8,
+    name_len: usize,
+    begin: u64,
+    end: u64,
+}
+
+impl Event {
+    fn tid(&self) -> usize {
+        match self.kind {
+            EventKind::WorkPacket => FIRST_WORKER_TID + self.worker,
+            EventKind::Bucket => BUCKETS_TID,
+            EventKind::Phase => PHASES_TID,
+        }
+    }
+
+    fn write_name(&self, writer: &mut impl Write) -> io::Result<()> {
+        if self.kind == EventKind::Bucket {
+            return write!(writer, "{:?}", WorkBucketStage::from_usize(self.name_len));
+        }
+        // Safety: The name was a `&'static str` when it was recorded, and the sequence number
+        // confirmed that the pointer and the length were recorded together.
+        let name = unsafe {
+            std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.name_ptr, self.name_len))
+        };
+        for c in name.chars() {
+            match c {
+                '"' | '\\' => write!(writer, "\\{}", c)?,
+                _ => write!(writer, "{}", c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Timeline {
+    epoch: Instant,
+    slots: Box<[Slot]>,
+    /// The index of the next event.  Events are written to `slots[index % slots.len()]`.
+    cursor: AtomicUsize,
+    /// When each bucket was opened, or `NOT_OPEN`.
+    bucket_open_time: EnumMap<WorkBucketStage, AtomicU64>,
+    /// When the current GC started.
+    gc_start_time: AtomicU64,
+}
+
+impl Timeline {
+    /// Create a timeline that keeps the most recent `capacity` events.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        Self {
+            epoch: Instant::now(),
+            slots: (0..capacity).map(|_| Slot::default()).collect(),
+            cursor: AtomicUsize::new(0),
+            // TODO: Replace `array_from_fn` with `std::array::from_fn` after bumping MSRV.
+            bucket_open_time: EnumMap::from_array(array_from_fn(|_| AtomicU64::new(NOT_OPEN))),
+            gc_start_time: AtomicU64::new(0),
+        }
+    }
+
+    /// Nanoseconds since the timeline is created.
+    pub fn now(&self) -> u64 {
+        self.epoch.elapsed().as_nanos() as u64
+    }
+
+    fn record(&self, kind: EventKind, worker: usize, name: (*mut u8, usize), begin: u64) {
+        let end = self.now();
+        let index = self.cursor.fetch_add(1, Ordering::Relaxed);
+        let slot = &self.slots[index % self.slots.len()];
+        slot.seq.store(0, Ordering::Relaxed);
+        fence(Ordering::Release);
+        slot.kind.store(kind as u8, Ordering::Relaxed);
+        slot.worker.store(worker, Ordering::Relaxed);
+        slot.name_ptr.store(name.0, Ordering::Relaxed);
+        slot.name_len.store(name.1, Ordering::Relaxed);
+        slot.begin.store(begin, Ordering::Relaxed);
+        slot.end.store(end, Ordering::Relaxed);
+        slot.seq.store(index + 1, Ordering::Release);
+    }
+
+    /// Read the event of the given index.  Return `None` if the event has been overwritten or is
+    /// being written.
+    fn read(&self, index: usize) -> Option<Event> {
+        let slot = &self.slots[index % self.slots.len()];
+        let seq = slot.seq.load(Ordering::Acquire);
+        let event = Event {
+            kind: EventKind::from_u8(slot.kind.load(Ordering::Relaxed)),
+            worker: slot.worker.load(Ordering::Relaxed),
+            name_ptr: slot.name_ptr.load(Ordering::Relaxed),
+            name_len: slot.name_len.load(Ordering::Relaxed),
+            begin: slot.begin.load(Ordering::Relaxed),
+            end: slot.end.load(Ordering::Relaxed),
+        };
+        fence(Ordering::Acquire);
+        (seq == index + 1 && slot.seq.load(Ordering::Relaxed) == seq).then_some(event)
+    }
+
+    /// Record a work packet that the worker `ordinal` started executing at `begin` and just
+    /// finished.
+    pub fn work_packet(&self, ordinal: usize, name: &'static str, begin: u64) {
+        self.record(
+            EventKind::WorkPacket,
+            ordinal,
+            (name.as_ptr().cast_mut(), name.len()),
+            begin,
+        );
+    }
+
+    /// Record a phase that started at `begin` and just finished.
+    pub fn phase(&self, name: &'static str, begin: u64) {
+        self.record(
+            EventKind::Phase,
+            0,
+            (name.as_ptr().cast_mut(), name.len()),
+            begin,
+        );
+    }
+
+    pub fn gc_started(&self) {
+        self.gc_start_time.store(self.now(), Ordering::Relaxed);
+    }
+
+    pub fn gc_finished(&self) {
+        self.phase("GC", self.gc_start_time.load(Ordering::Relaxed));
+    }
+
+    pub fn bucket_opened(&self, stage: WorkBucketStage) {
+        self.bucket_open_time[stage].store(self.now(), Ordering::Relaxed);
+    }
+
+    pub fn bucket_closed(&self, stage: WorkBucketStage) {
+        let begin = self.bucket_open_time[stage].swap(NOT_OPEN, Ordering::Relaxed);
+        if begin != NOT_OPEN {
+            self.record(
+                EventKind::Bucket,
+                0,
+                (std::ptr::null_mut(), stage.into_usize()),
+                begin,
+            );
+        }
+    }
+
+    /// Write the recorded events in the Chrome trace event format.
+    pub fn dump(&self, mut writer: impl Write) -> io::Result<()> {
+        let end = self.cursor.load(Ordering::Acquire);
+        let start = end.saturating_sub(self.slots.len());
+        let mut tids = BTreeSet::new();
+
+        writeln!(writer, "{{\"traceEvents\":[")?;
+        let mut first = true;
+        for event in (start..end).filter_map(|index| self.read(index)) {
+            if !first {
+                writeln!(writer, ",")?;
+            }
+            first = false;
+            tids.insert(event.tid());
+            write!(writer, "{{\"name\":\"")?;
+            event.write_name(&mut writer)?;
+            write!(
+                writer,
+                "\",\"ph\":\"X\",\"pid\":1,\"tid\":{},\"ts\":{:.3},\"dur\":{:.3}}}",
+                event.tid(),
+                event.begin as f64 / 1000.0,
+                event.end.saturating_sub(event.begin) as f64 / 1000.0,
+            )?;
+        }
+        for tid in tids {
+            if !first {
+                writeln!(writer, ",")?;
+            }
+            first = false;
+            let name = match tid {
+                PHASES_TID => "GC phases".to_string(),
+                BUCKETS_TID => "Work buckets".to_string(),
+                _ => format!("GC worker {}", tid - FIRST_WORKER_TID),
+            };
+            write!(
+                writer,
+                "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{},\"args\":{{\"name\":\"{}\"}}}}",
+                tid, name
+            )?;
+        }
+        writeln!(writer, "\n]}}")?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dump_to_string(timeline: &Timeline) -> String {
+        let mut buffer = vec![];
+        timeline.dump(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_dump_events() {
+        let timeline = Timeline::new(16);
+        timeline.gc_started();
+        timeline.bucket_opened(WorkBucketStage::Closure);
+        let begin = timeline.now();
+        timeline.work_packet(3, "mmtk::Packet<\"quoted\">", begin);
+        timeline.bucket_closed(WorkBucketStage::Closure);
+        // A closed bucket is not recorded again.
+        timeline.bucket_closed(WorkBucketStage::Closure);
+        timeline.gc_finished();
+
+        let dump = dump_to_string(&timeline);
+        assert!(dump.starts_with("{\"traceEvents\":["));
+        assert!(dump.contains(
+            "\"name\":\"mmtk::Packet<\\\"quoted\\\">\",\"ph\":\"X\",\"pid\":1,\"tid\":5,"
+        ));
+        assert_eq!(dump.matches("\"name\":\"Closure\"").count(), 1);
+        assert!(dump.contains("\"name\":\"GC\",\"ph\":\"X\",\"pid\":1,\"tid\":0,"));
+        assert!(dump.contains("\"args\":{\"name\":\"GC worker 3\"}"));
+        assert!(dump.contains("\"args\":{\"name\":\"Work buckets\"}"));
+        assert!(dump.trim_end().ends_with("]}"));
+    }
+
+    #[test]
+    fn test_keep_recent_events() {
+        let timeline = Timeline::new(4);
+        const NAMES: [&str; 6] = ["P0", "P1", "P2", "P3", "P4", "P5"];
+        for name in NAMES {
+            timeline.work_packet(0, name, timeline.now());
+        }
+
+        let dump = dump_to_string(&timeline);
+        for name in &NAMES[..2] {
+            assert!(!dump.contains(&format!("\"name\":\"{}\"", name)));
+        }
+        for name in &NAMES[2..] {
+            assert!(dump.contains(&format!("\"name\":\"{}\"", name)));
+        }
+    }
+}
@@ -4,6 +4,18 @@ use crate::vm::VMBinding;
 #[cfg(feature = "work_packet_stats")]
 use std::any::{type_name, TypeId};
 
+/// The priority class of a work packet.  In each work bucket, workers poll packets of the `High`
+/// class before packets of the `Normal` class.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WorkPriority {
+    /// Most work packets.
+    #[default]
+    Normal,
+    /// Packets that should be executed as early as possible in their bucket, such as root
+    /// scanning, because they generate work for other workers.
+    High,
+}
+
 /// This defines a GC work packet which are assigned to the [`GCWorker`]s by the scheduler.
 /// Work packets carry payloads that indicate the work to be done. For example, a work packet may
 /// contain a pointer to a stack that must be scanned, or it may contain a large buffer of pointers
@@ -52,6 +64,26 @@ pub trait GCWork<VM: VMBinding>: 'static + Send {
         }
     }
 
+    /// The priority class of this packet.  Packets of the [`WorkPriority::High`] class are executed
+    /// before other packets in the same bucket.
+    fn priority(&self) -> WorkPriority {
+        WorkPriority::Normal
+    }
+
+    /// The estimated cost of this packet, in the number of slots or objects it processes.  If the
+    /// cost exceeds [`GCWorker::LONG_WORK_PACKET_COST`], the worker that polls this packet splits
+    /// it with [`GCWork::split_off`] before executing it.
+    fn estimated_cost(&self) -> usize {
+        1
+    }
+
+    /// Split off part of the work into a new packet, and keep the rest in this packet.  Other
+    /// workers may steal the new packet and execute it in parallel.  Return `None` if this packet
+    /// cannot be split.
+    fn split_off(&mut self) -> Option<Box<dyn GCWork<VM>>> {
+        None
+    }
+
     /// Get the compile-time static type name for the work packet.
     fn get_type_name(&self) -> &'static str {
         std::any::type_name::<Self>()
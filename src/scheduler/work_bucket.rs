@@ -44,7 +44,9 @@ pub type BucketOpenCondition<VM> = Box<dyn (Fn(&GCWorkScheduler<VM>) -> bool) +
 pub struct WorkBucket<VM: VMBinding> {
     active: AtomicBool,
     queue: BucketQueue<VM>,
-    prioritized_queue: Option<BucketQueue<VM>>,
+    /// Packets of the [`WorkPriority::High`] class, and packets added with `add_prioritized`.
+    /// Workers poll this queue before `queue`.
+    prioritized_queue: BucketQueue<VM>,
     monitor: Arc<WorkerMonitor>,
     can_open: Option<BucketOpenCondition<VM>>,
     /// After this bucket is activated and all pending work packets (including the packets in this
@@ -66,7 +68,7 @@ impl<VM: VMBinding> WorkBucket<VM> {
         Self {
             active: AtomicBool::new(active),
             queue: BucketQueue::new(),
-            prioritized_queue: None,
+            prioritized_queue: BucketQueue::new(),
             monitor,
             can_open: None,
             sentinel: Mutex::new(None),
@@ -102,12 +104,7 @@ impl<VM: VMBinding> WorkBucket<VM> {
 
     /// Test if the bucket is drained
     pub fn is_empty(&self) -> bool {
-        self.queue.is_empty()
-            && self
-                .prioritized_queue
-                .as_ref()
-                .map(|q| q.is_empty())
-                .unwrap_or(true)
+        self.queue.is_empty() && self.prioritized_queue.is_empty()
     }
 
     pub fn is_drained(&self) -> bool {
@@ -116,26 +113,33 @@ impl<VM: VMBinding> WorkBucket<VM> {
 
     /// Disable the bucket
     pub fn deactivate(&self) {
-        debug_assert!(self.queue.is_empty(), "Bucket not drained before close");
+        debug_assert!(self.is_empty(), "Bucket not drained before close");
         self.active.store(false, Ordering::Relaxed);
     }
 
-    /// Add a work packet to this bucket
-    /// Panic if this bucket cannot receive prioritized packets.
+    /// Push a work packet to the queue for its priority class.
+    fn push(&self, work: Box<dyn GCWork<VM>>) {
+        match work.priority() {
+            WorkPriority::Normal => self.queue.push(work),
+            WorkPriority::High => self.prioritized_queue.push(work),
+        }
+    }
+
+    /// Add a work packet to this bucket with a higher priority, regardless of its priority class.
     pub fn add_prioritized(&self, work: Box<dyn GCWork<VM>>) {
-        self.prioritized_queue.as_ref().unwrap().push(work);
+        self.prioritized_queue.push(work);
         self.notify_one_worker();
     }
 
     /// Add a work packet to this bucket
     pub fn add<W: GCWork<VM>>(&self, work: W) {
-        self.queue.push(Box::new(work));
+        self.push(Box::new(work));
         self.notify_one_worker();
     }
 
     /// Add a work packet to this bucket
     pub fn add_boxed(&self, work: Box<dyn GCWork<VM>>) {
-        self.queue.push(work);
+        self.push(work);
         self.notify_one_worker();
     }
 
@@ -144,18 +148,17 @@ impl<VM: VMBinding> WorkBucket<VM> {
     /// used for notifying workers.  This usually happens if the current thread is the last worker
     /// parked.
     pub(crate) fn add_no_notify<W: GCWork<VM>>(&self, work: W) {
-        self.queue.push(Box::new(work));
+        self.push(Box::new(work));
     }
 
     /// Like [`WorkBucket::add_no_notify`], but the work is boxed.
     pub(crate) fn add_boxed_no_notify(&self, work: Box<dyn GCWork<VM>>) {
-        self.queue.push(work);
+        self.push(work);
     }
 
-    /// Add multiple packets with a higher priority.
-    /// Panic if this bucket cannot receive prioritized packets.
+    /// Add multiple packets with a higher priority, regardless of their priority classes.
     pub fn bulk_add_prioritized(&self, work_vec: Vec<Box<dyn GCWork<VM>>>) {
-        self.prioritized_queue.as_ref().unwrap().push_all(work_vec);
+        self.prioritized_queue.push_all(work_vec);
         if self.is_activated() {
             self.notify_all_workers();
         }
@@ -166,7 +169,9 @@ impl<VM: VMBinding> WorkBucket<VM> {
         if work_vec.is_empty() {
             return;
         }
-        self.queue.push_all(work_vec);
+        for work in work_vec {
+            self.push(work);
+        }
         if self.is_activated() {
             self.notify_all_workers();
         }
@@ -177,13 +182,9 @@ impl<VM: VMBinding> WorkBucket<VM> {
         if !self.is_activated() || self.is_empty() {
             return Steal::Empty;
         }
-        if let Some(prioritized_queue) = self.prioritized_queue.as_ref() {
-            prioritized_queue
-                .steal_batch_and_pop(worker)
-                .or_else(|| self.queue.steal_batch_and_pop(worker))
-        } else {
-            self.queue.steal_batch_and_pop(worker)
-        }
+        self.prioritized_queue
+            .steal_batch_and_pop(worker)
+            .or_else(|| self.queue.steal_batch_and_pop(worker))
     }
 
     pub fn set_open_condition(
@@ -289,11 +290,23 @@ pub enum WorkBucketStage {
     VMRefForwarding,
     /// Compact objects (mark-compact-only).
     Compact,
+    /// Work packets that conclude the initial-mark pause of a concurrent GC, after the roots have
+    /// been scanned.  This includes enabling the barrier and starting concurrent marking.
+    InitialMark,
+    /// Work packets that conclude the final-mark (remark) pause of a concurrent GC, after the
+    /// transitive closure has been completed.  This includes disabling the barrier and finishing
+    /// concurrent marking.
+    FinalMark,
     /// Work packets that should be done just before GC shall go here.  This includes releasing
     /// resources and setting states in plans, spaces, GC workers, mutators, etc.
     Release,
     /// Resume mutators and end GC.
     Final,
+    /// Work packets that run concurrently with mutators, such as concurrent marking.  This bucket
+    /// is not part of any stop-the-world pause.  It is opened by the scheduler when a concurrent
+    /// plan has concurrent work in progress at the end of a pause, and it remains open until the
+    /// end of a pause after which the plan has no more concurrent work.
+    Concurrent,
 }
 
 impl WorkBucketStage {
@@ -301,4 +314,57 @@ impl WorkBucketStage {
     pub fn first_stw_stage() -> Self {
         WorkBucketStage::from_usize(1)
     }
+
+    /// Is this a stop-the-world stage?  Buckets of stop-the-world stages are opened one after
+    /// another during a pause, and are all closed at the end of the pause.  `Unconstrained` is
+    /// always open, and `Concurrent` is opened and closed by the scheduler according to the
+    /// concurrent work of the plan.
+    pub fn is_stw(&self) -> bool {
+        !matches!(
+            self,
+            WorkBucketStage::Unconstrained | WorkBucketStage::Concurrent
+        )
+    }
+}
+
+#[cfg(all(test, feature = "mock_test"))]
+mod tests {
+    use super::*;
+    use crate::util::test_util::mock_vm::MockVM;
+    use crate::MMTK;
+
+    struct Packet(WorkPriority);
+
+    impl GCWork<MockVM> for Packet {
+        fn do_work(&mut self, _worker: &mut GCWorker<MockVM>, _mmtk: &'static MMTK<MockVM>) {}
+
+        fn priority(&self) -> WorkPriority {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_poll_high_priority_first() {
+        let bucket = WorkBucket::<MockVM>::new(true, Arc::new(WorkerMonitor::new(1)));
+        bucket.add(Packet(WorkPriority::Normal));
+        bucket.add(Packet(WorkPriority::High));
+        bucket.add(Packet(WorkPriority::Normal));
+        bucket.add(Packet(WorkPriority::High));
+
+        let local = Worker::new_lifo();
+        let mut polled = vec![];
+        while let Some(work) = local.pop().or_else(|| bucket.poll(&local).success()) {
+            polled.push(work.priority());
+        }
+        assert_eq!(
+            polled,
+            [
+                WorkPriority::High,
+                WorkPriority::High,
+                WorkPriority::Normal,
+                WorkPriority::Normal
+            ]
+        );
+        assert!(bucket.is_empty());
+    }
 }
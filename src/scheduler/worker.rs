@@ -151,6 +151,10 @@ impl<VM: VMBinding> GCWorker<VM> {
 
     const LOCALLY_CACHED_WORK_PACKETS: usize = 16;
 
+    /// Work packets with a higher [estimated cost](GCWork::estimated_cost) are split before they
+    /// are executed.
+    pub const LONG_WORK_PACKET_COST: usize = 4096;
+
     /// Add a work packet to the work queue and mark it with a higher priority.
     /// If the bucket is activated, the packet will be pushed to the local queue, otherwise it will be
     /// pushed to the global bucket with a higher priority.
@@ -177,6 +181,22 @@ impl<VM: VMBinding> GCWorker<VM> {
         self.local_work_buffer.push(Box::new(work));
     }
 
+    /// Split off parts of a long work packet into the local queue, from which other workers can
+    /// steal them, until the rest of the packet is short or cannot be split.
+    fn split_long_work(&mut self, work: &mut dyn GCWork<VM>) {
+        let mut split = false;
+        while work.estimated_cost() > Self::LONG_WORK_PACKET_COST {
+            let Some(part) = work.split_off() else {
+                break;
+            };
+            self.local_work_buffer.push(part);
+            split = true;
+        }
+        if split {
+            self.scheduler.worker_monitor.notify_work_available(true);
+        }
+    }
+
     /// Get the scheduler. There is only one scheduler per MMTk instance.
     pub fn scheduler(&self) -> &GCWorkScheduler<VM> {
         &self.scheduler
@@ -193,16 +213,25 @@ impl<VM: VMBinding> GCWorker<VM> {
     /// 2. Poll from the local work queue.
     /// 3. Poll from activated global work-buckets
     /// 4. Steal from other workers
+    ///
+    /// Between incremental pauses, and after the budget of an incremental pause runs out, only
+    /// designated packets and packets in buckets other than `Concurrent` are polled.
     fn poll(&mut self) -> PollResult<VM> {
         if let Some(work) = self.shared.designated_work.pop() {
             return Ok(work);
         }
 
-        if let Some(work) = self.local_work_buffer.pop() {
-            return Ok(work);
+        // Packets in the local queue may be concurrent work left over from an incremental pause.
+        if self.scheduler().incremental.may_do_concurrent_work() {
+            if let Some(work) = self.local_work_buffer.pop() {
+                self.scheduler().incremental.on_work_polled();
+                return Ok(work);
+            }
         }
 
-        self.scheduler().poll(self)
+        let work = self.scheduler().poll(self)?;
+        self.scheduler().incremental.on_work_polled();
+        Ok(work)
     }
 
     /// Entry point of the worker thread.
@@ -241,6 +270,7 @@ impl<VM: VMBinding> GCWorker<VM> {
                 // The worker is asked to exit.  Break from the loop.
                 break;
             };
+            self.split_long_work(work.as_mut());
             // probe! expands to an empty block on unsupported platforms
             #[allow(unused_variables)]
             let typename = work.get_type_name();
@@ -252,7 +282,13 @@ impl<VM: VMBinding> GCWorker<VM> {
             std::hint::black_box(unsafe { *(typename.as_ptr()) });
 
             probe!(mmtk, work, typename.as_ptr(), typename.len());
+            #[cfg(feature = "timeline")]
+            let begin = mmtk.scheduler.timeline.now();
             work.do_work_with_stat(&mut self, mmtk);
+            #[cfg(feature = "timeline")]
+            mmtk.scheduler
+                .timeline
+                .work_packet(self.ordinal, typename, begin);
         }
         debug!(
             "Worker exiting. ordinal: {}, {}",
@@ -440,6 +476,14 @@ impl<VM: VMBinding> WorkerGroup<VM> {
             .any(|w| !w.designated_work.is_empty())
     }
 
+    /// Return true if any worker has packets left in its local work queue.  This only happens
+    /// when an incremental pause ends before workers drain their local queues.
+    pub fn has_local_work(&self) -> bool {
+        self.workers_shared
+            .iter()
+            .any(|w| !w.stealer.as_ref().unwrap().is_empty())
+    }
+
     /// Get the live bytes data from the worker, and clear the local data.
     pub fn get_and_clear_worker_live_bytes(&self) -> [usize; MAX_SPACES] {
         let mut ret = [0; MAX_SPACES];
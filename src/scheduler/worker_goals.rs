@@ -7,6 +7,8 @@
 //!
 //! -   When in the progress of GC, the last parker will try to open buckets or announce the GC
 //!     has finished.
+//! -   When in an incremental pause, the last parker will resume mutators and let them run until
+//!     the next incremental pause.
 //! -   When stopping for fork, every waken worker should save its thread state (giving in the
 //!     `GCWorker` struct) and exit.
 //!
@@ -31,6 +33,8 @@ pub(crate) struct WorkerGoals {
 pub(crate) enum WorkerGoal {
     /// Do a garbage collection.
     Gc,
+    /// Stop mutators, and do concurrent work until the budget of the incremental pause runs out.
+    IncrementalPause,
     /// Stop all GC threads so that the VM can call `fork()`.
     StopForFork,
 }
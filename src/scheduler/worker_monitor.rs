@@ -2,9 +2,12 @@
 //!
 //! -   allowing workers to park,
 //! -   letting the last parked worker take action, and
-//! -   letting workers and mutators notify workers when workers are given things to do.
+//! -   letting workers and mutators notify workers when workers are given things to do, and
+//! -   keeping surplus workers parked when a GC does not need all workers.
 
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Condvar, Mutex};
+use std::time::Duration;
 
 use super::{
     worker::WorkerShouldExit,
@@ -16,6 +19,9 @@ use super::{
 pub(crate) enum LastParkedResult {
     /// The last parked worker should wait, too, until more work packets are added.
     ParkSelf,
+    /// Like `ParkSelf`, but the last parked worker will also wake up after the given duration so
+    /// that it can check again whether the workers have anything to do.
+    ParkSelfFor(Duration),
     /// The last parked worker should unpark and find work packet to do.
     WakeSelf,
     /// Wake up all parked GC workers.
@@ -35,6 +41,49 @@ pub(crate) struct WorkerMonitor {
     /// -   any work packets available, and
     /// -   any field in `sync.goals.requests` set to true.
     workers_have_anything_to_do: Condvar,
+    /// Surplus workers wait on this instead of `workers_have_anything_to_do`.  Notified if some
+    /// surplus workers become active.
+    surplus_workers_may_run: Condvar,
+    /// The workers that take part in GC.
+    active: ActiveWorkers,
+}
+
+/// The workers that take part in GC.  They are `count` consecutive workers (wrapping around)
+/// starting from the worker `first`.  The other workers are *surplus* workers.  They stay parked,
+/// and are counted as parked, until they become active again.
+///
+/// The fields are only changed by the last parked worker while holding the mutex
+/// `WorkerMonitor::sync`, and do not change during GC.
+struct ActiveWorkers {
+    /// The total number of workers.
+    worker_count: usize,
+    /// The ordinal of the first active worker.
+    first: AtomicUsize,
+    /// The number of active workers.
+    count: AtomicUsize,
+}
+
+impl ActiveWorkers {
+    fn new(worker_count: usize) -> Self {
+        Self {
+            worker_count,
+            first: AtomicUsize::new(0),
+            count: AtomicUsize::new(worker_count),
+        }
+    }
+
+    /// Return true if `ordinal` is one of the `count` workers starting from `first`.
+    fn contains_in(&self, ordinal: usize, first: usize, count: usize) -> bool {
+        (ordinal + self.worker_count - first) % self.worker_count < count
+    }
+
+    fn contains(&self, ordinal: usize) -> bool {
+        self.contains_in(
+            ordinal,
+            self.first.load(Ordering::Relaxed),
+            self.count.load(Ordering::Relaxed),
+        )
+    }
 }
 
 /// The synchronized part of `WorkerMonitor`.
@@ -92,6 +141,44 @@ impl WorkerMonitor {
                 goals: Default::default(),
             }),
             workers_have_anything_to_do: Default::default(),
+            surplus_workers_may_run: Default::default(),
+            active: ActiveWorkers::new(worker_count),
+        }
+    }
+
+    /// Return true if the worker `ordinal` takes part in GC.
+    pub fn is_worker_active(&self, ordinal: usize) -> bool {
+        self.active.contains(ordinal)
+    }
+
+    /// Let `count` workers, including the worker `ordinal`, take part in GC from now on.  The
+    /// other workers become surplus workers, and will stay parked until they become active again.
+    ///
+    /// This must only be called in the `on_last_parked` call-back of `park_and_wait`, i.e. by the
+    /// last parked worker while all other workers are parked.
+    pub fn set_active_workers(&self, ordinal: usize, count: usize) {
+        debug_assert!(count > 0 && count <= self.active.worker_count);
+        let old_first = self.active.first.load(Ordering::Relaxed);
+        let old_count = self.active.count.load(Ordering::Relaxed);
+        // Keep the same workers active if possible so that we don't wake up surplus workers.
+        let first = if self.active.contains_in(ordinal, old_first, count) {
+            old_first
+        } else {
+            ordinal
+        };
+        self.active.first.store(first, Ordering::Relaxed);
+        self.active.count.store(count, Ordering::Relaxed);
+        trace!(
+            "Workers {}+{} of {} are active.",
+            first,
+            count,
+            self.active.worker_count
+        );
+
+        if first != old_first || count > old_count {
+            // Some surplus workers may become active.  They are waiting for the mutex which the
+            // last parked worker is holding, so they will see the new values after waking up.
+            self.surplus_workers_may_run.notify_all();
         }
     }
 
@@ -145,6 +232,7 @@ impl WorkerMonitor {
         );
 
         let mut should_wait = false;
+        let mut timeout = None;
 
         if all_parked {
             trace!("Worker {} is the last worker parked.", ordinal);
@@ -153,6 +241,10 @@ impl WorkerMonitor {
                 LastParkedResult::ParkSelf => {
                     should_wait = true;
                 }
+                LastParkedResult::ParkSelfFor(duration) => {
+                    should_wait = true;
+                    timeout = Some(duration);
+                }
                 LastParkedResult::WakeSelf => {
                     // Continue without waiting.
                 }
@@ -218,7 +310,33 @@ impl WorkerMonitor {
             //     and park again if not available.  The last parked worker will ensure the two
             //     conditions listed above are both false before blocking.  If either condition is
             //     true, the last parked worker will take action.
-            sync = self.workers_have_anything_to_do.wait(sync).unwrap();
+            //
+            // Notes on timeout:
+            //
+            // The last parked worker may wait with a timeout if there will be something to do
+            // after a while (e.g. starting the next incremental pause).  When it times out, it
+            // unparks as if it is spuriously woken up, and will park again as the last parked
+            // worker after finding no work packets.
+            sync = match timeout {
+                Some(duration) => {
+                    self.workers_have_anything_to_do
+                        .wait_timeout(sync, duration)
+                        .unwrap()
+                        .0
+                }
+                None => self.workers_have_anything_to_do.wait(sync).unwrap(),
+            };
+
+            // A surplus worker stays parked until it becomes active again.  Surplus workers do not
+            // take part in GC, and never become the last parked worker.
+            if !self.active.contains(ordinal) {
+                trace!("Worker {} is a surplus worker.", ordinal);
+                // The notification may have been meant for an active worker.  Pass it on.
+                self.notify_work_available(false);
+                while !self.active.contains(ordinal) {
+                    sync = self.surplus_workers_may_run.wait(sync).unwrap();
+                }
+            }
         }
 
         // Unpark this worker.
@@ -340,4 +458,50 @@ mod tests {
         // `on_last_parked` should only be called once.
         assert_eq!(on_last_parked_called.load(Ordering::SeqCst), 1);
     }
+
+    /// Test if surplus workers stay parked, and wake up when they become active again.
+    #[test]
+    fn test_surplus_workers_stay_parked() {
+        let number_threads = 4;
+        let number_active = 2;
+        let worker_monitor = Arc::new(WorkerMonitor::new(number_threads));
+        let on_last_parked_called = AtomicUsize::new(0);
+        let surplus_unparked = AtomicBool::new(false);
+        let should_unpark = AtomicBool::new(false);
+
+        std::thread::scope(|scope| {
+            for ordinal in 0..number_threads {
+                let worker_monitor = worker_monitor.clone();
+                let on_last_parked_called = &on_last_parked_called;
+                let surplus_unparked = &surplus_unparked;
+                let should_unpark = &should_unpark;
+                scope.spawn(move || {
+                    while !should_unpark.load(Ordering::SeqCst) {
+                        worker_monitor
+                            .park_and_wait(ordinal, |_goals| {
+                                if on_last_parked_called.fetch_add(1, Ordering::SeqCst) == 0 {
+                                    // Only this worker and the next one take part.
+                                    worker_monitor.set_active_workers(ordinal, number_active);
+                                } else {
+                                    // Wake up the surplus workers so that they can exit.
+                                    worker_monitor.set_active_workers(ordinal, number_threads);
+                                    should_unpark.store(true, Ordering::SeqCst);
+                                }
+                                super::LastParkedResult::WakeAll
+                            })
+                            .unwrap();
+                        if !should_unpark.load(Ordering::SeqCst)
+                            && !worker_monitor.is_worker_active(ordinal)
+                        {
+                            surplus_unparked.store(true, Ordering::SeqCst);
+                        }
+                    }
+                });
+            }
+        });
+
+        // The active workers parked again without waking up the surplus workers.
+        assert_eq!(on_last_parked_called.load(Ordering::SeqCst), 2);
+        assert!(!surplus_unparked.load(Ordering::SeqCst));
+    }
 }
@@ -25,6 +25,10 @@ pub enum AllocationError {
     /// The OS is unable to mmap or acquire more memory. Critical error. MMTk expects the VM to
     /// abort if such an error is thrown.
     MmapOutOfMemory,
+    /// The space to allocate into exceeds its budget set by the option
+    /// [`space_budgets`](crate::util::options::Options::space_budgets). Other spaces may still have
+    /// memory available.
+    SpaceBudgetExceeded,
 }
 
 pub fn align_allocation_no_fill<VM: VMBinding>(
@@ -251,6 +255,17 @@ pub trait Allocator<VM: VMBinding>: Downcast {
                 self.alloc_slow_once_traced(size, align, offset)
             };
 
+            // The allocation fails without retrying, e.g. the space exceeds its budget.
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    debug_assert!(is_mutator);
+                    trace!("Throw {:?}!", err);
+                    VM::VMCollection::out_of_memory(tls, err);
+                    return Address::ZERO;
+                }
+            };
+
             if !is_mutator {
                 debug_assert!(!result.is_zero());
                 return result;
@@ -310,6 +325,18 @@ pub trait Allocator<VM: VMBinding>: Downcast {
                 return result;
             }
 
+            // The heap usage is still above the soft heap limit after all the GCs for it.
+            if self
+                .get_context()
+                .gc_trigger
+                .soft_limit
+                .take_out_of_memory()
+            {
+                trace!("Throw HeapOutOfMemory for the soft heap limit!");
+                VM::VMCollection::out_of_memory(tls, AllocationError::HeapOutOfMemory);
+                return result;
+            }
+
             // It is possible to have cases where a thread is blocked for another GC (non emergency)
             // immediately after being blocked for a GC (emergency) (e.g. in stress test), that is saying
             // the thread does not leave this loop between the two GCs. The local var 'emergency_collection'
@@ -367,7 +394,16 @@ pub trait Allocator<VM: VMBinding>: Downcast {
     /// * `size`: the allocation size in bytes.
     /// * `align`: the required alignment in bytes.
     /// * `offset` the required offset in bytes.
-    fn alloc_slow_once(&mut self, size: usize, align: usize, offset: usize) -> Address;
+    ///
+    /// Return the address of the allocated object, or a zero address if the allocation failed but
+    /// may succeed after a GC. Return an error if the allocation must fail without retrying, such
+    /// as when the space exceeds its budget.
+    fn alloc_slow_once(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+    ) -> Result<Address, AllocationError>;
 
     /// A wrapper method for [`alloc_slow_once`](Allocator::alloc_slow_once) to insert USDT tracepoints.
     ///
@@ -375,7 +411,12 @@ pub trait Allocator<VM: VMBinding>: Downcast {
     /// * `size`: the allocation size in bytes.
     /// * `align`: the required alignment in bytes.
     /// * `offset` the required offset in bytes.
-    fn alloc_slow_once_traced(&mut self, size: usize, align: usize, offset: usize) -> Address {
+    fn alloc_slow_once_traced(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+    ) -> Result<Address, AllocationError> {
         probe!(mmtk, alloc_slow_once_start);
         // probe! expands to an empty block on unsupported platforms
         #[allow(clippy::let_and_return)]
@@ -418,7 +459,7 @@ pub trait Allocator<VM: VMBinding>: Downcast {
         align: usize,
         offset: usize,
         need_poll: bool,
-    ) -> Address {
+    ) -> Result<Address, AllocationError> {
         // If an allocator does thread local allocation but does not override this method to
         // provide a correct implementation, we will log a warning.
         if self.does_thread_local_allocation() && need_poll {
@@ -18,6 +18,7 @@ use crate::MMTK;
 use super::allocator::AllocatorContext;
 use super::FreeListAllocator;
 use super::MarkCompactAllocator;
+use super::RegionAllocator;
 
 pub(crate) const MAX_BUMP_ALLOCATORS: usize = 6;
 pub(crate) const MAX_LARGE_OBJECT_ALLOCATORS: usize = 2;
@@ -25,6 +26,7 @@ pub(crate) const MAX_MALLOC_ALLOCATORS: usize = 1;
 pub(crate) const MAX_IMMIX_ALLOCATORS: usize = 1;
 pub(crate) const MAX_FREE_LIST_ALLOCATORS: usize = 2;
 pub(crate) const MAX_MARK_COMPACT_ALLOCATORS: usize = 1;
+pub(crate) const MAX_REGION_ALLOCATORS: usize = 1;
 
 // The allocators set owned by each mutator. We provide a fixed number of allocators for each allocator type in the mutator,
 // and each plan will select part of the allocators to use.
@@ -38,6 +40,7 @@ pub struct Allocators<VM: VMBinding> {
     pub immix: [MaybeUninit<ImmixAllocator<VM>>; MAX_IMMIX_ALLOCATORS],
     pub free_list: [MaybeUninit<FreeListAllocator<VM>>; MAX_FREE_LIST_ALLOCATORS],
     pub markcompact: [MaybeUninit<MarkCompactAllocator<VM>>; MAX_MARK_COMPACT_ALLOCATORS],
+    pub region: [MaybeUninit<RegionAllocator<VM>>; MAX_REGION_ALLOCATORS],
 }
 
 impl<VM: VMBinding> Allocators<VM> {
@@ -57,6 +60,7 @@ impl<VM: VMBinding> Allocators<VM> {
             AllocatorSelector::MarkCompact(index) => {
                 self.markcompact[index as usize].assume_init_ref()
             }
+            AllocatorSelector::Region(index) => self.region[index as usize].assume_init_ref(),
             AllocatorSelector::None => panic!("Allocator mapping is not initialized"),
         }
     }
@@ -86,6 +90,7 @@ impl<VM: VMBinding> Allocators<VM> {
             AllocatorSelector::MarkCompact(index) => {
                 self.markcompact[index as usize].assume_init_mut()
             }
+            AllocatorSelector::Region(index) => self.region[index as usize].assume_init_mut(),
             AllocatorSelector::None => panic!("Allocator mapping is not initialized"),
         }
     }
@@ -111,6 +116,7 @@ impl<VM: VMBinding> Allocators<VM> {
             immix: unsafe { MaybeUninit::uninit().assume_init() },
             free_list: unsafe { MaybeUninit::uninit().assume_init() },
             markcompact: unsafe { MaybeUninit::uninit().assume_init() },
+            region: unsafe { MaybeUninit::uninit().assume_init() },
         };
         let context = Arc::new(AllocatorContext::new(mmtk));
 
@@ -159,6 +165,14 @@ impl<VM: VMBinding> Allocators<VM> {
                         context.clone(),
                     ));
                 }
+                AllocatorSelector::Region(index) => {
+                    ret.region[index as usize].write(RegionAllocator::new(
+                        mutator_tls.0,
+                        space,
+                        context.clone(),
+                        false,
+                    ));
+                }
                 AllocatorSelector::None => panic!("Allocator mapping is not initialized"),
             }
         }
@@ -195,6 +209,8 @@ pub enum AllocatorSelector {
     MarkCompact(u8),
     /// Represents a [`crate::util::alloc::free_list_allocator::FreeListAllocator`].
     FreeList(u8),
+    /// Represents a [`crate::util::alloc::region_allocator::RegionAllocator`].
+    Region(u8),
     /// No allocator found.
     #[default]
     None,
@@ -253,6 +269,14 @@ impl AllocatorInfo {
                 }
             }
 
+            AllocatorSelector::Region(_) => {
+                let bump_pointer_offset = offset_of!(RegionAllocator<VM>, bump_pointer);
+
+                AllocatorInfo::BumpPointer {
+                    bump_pointer_offset: base_offset + bump_pointer_offset,
+                }
+            }
+
             AllocatorSelector::FreeList(_) => AllocatorInfo::Unimplemented,
             _ => AllocatorInfo::None,
         }
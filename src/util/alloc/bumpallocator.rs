@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use crate::util::Address;
 
+use crate::util::alloc::AllocationError;
 use crate::util::alloc::Allocator;
 
 use crate::policy::space::Space;
@@ -120,7 +121,12 @@ impl<VM: VMBinding> Allocator<VM> for BumpAllocator<VM> {
         }
     }
 
-    fn alloc_slow_once(&mut self, size: usize, align: usize, offset: usize) -> Address {
+    fn alloc_slow_once(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+    ) -> Result<Address, AllocationError> {
         trace!("alloc_slow");
         self.acquire_block(size, align, offset, false)
     }
@@ -139,7 +145,7 @@ impl<VM: VMBinding> Allocator<VM> for BumpAllocator<VM> {
         align: usize,
         offset: usize,
         need_poll: bool,
-    ) -> Address {
+    ) -> Result<Address, AllocationError> {
         if need_poll {
             return self.acquire_block(size, align, offset, true);
         }
@@ -164,7 +170,7 @@ impl<VM: VMBinding> Allocator<VM> for BumpAllocator<VM> {
                 self.bump_pointer.cursor,
                 self.bump_pointer.limit
             );
-            result
+            Ok(result)
         }
     }
 
@@ -193,16 +199,18 @@ impl<VM: VMBinding> BumpAllocator<VM> {
         align: usize,
         offset: usize,
         stress_test: bool,
-    ) -> Address {
+    ) -> Result<Address, AllocationError> {
         if self.space.will_oom_on_acquire(self.tls, size) {
-            return Address::ZERO;
+            return Ok(Address::ZERO);
         }
 
         let block_size = (size + BLOCK_MASK) & (!BLOCK_MASK);
-        let acquired_start = self.space.acquire(self.tls, bytes_to_pages_up(block_size));
+        let acquired_start = self
+            .space
+            .acquire(self.tls, bytes_to_pages_up(block_size))?;
         if acquired_start.is_zero() {
             trace!("Failed to acquire a new block");
-            acquired_start
+            Ok(acquired_start)
         } else {
             trace!(
                 "Acquired a new block of size {} with start address {}",
@@ -211,7 +219,7 @@ impl<VM: VMBinding> BumpAllocator<VM> {
             );
             if !stress_test {
                 self.set_limit(acquired_start, acquired_start + block_size);
-                self.alloc(size, align, offset)
+                Ok(self.alloc(size, align, offset))
             } else {
                 // For a stress test, we artificially make the fastpath fail by
                 // manipulating the limit as below.
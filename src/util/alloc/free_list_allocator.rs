@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use crate::policy::marksweepspace::native_ms::*;
 use crate::util::alloc::allocator;
+use crate::util::alloc::AllocationError;
 use crate::util::alloc::Allocator;
 use crate::util::linear_scan::Region;
 use crate::util::Address;
@@ -83,13 +84,18 @@ impl<VM: VMBinding> Allocator<VM> for FreeListAllocator<VM> {
         self.alloc_slow(size, align, offset)
     }
 
-    fn alloc_slow_once(&mut self, size: usize, align: usize, offset: usize) -> Address {
+    fn alloc_slow_once(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+    ) -> Result<Address, AllocationError> {
         // Try get a block from the space
-        if let Some(block) = self.acquire_global_block(size, align, false) {
+        if let Some(block) = self.acquire_global_block(size, align, false)? {
             let addr = self.block_alloc(block);
-            allocator::align_allocation::<VM>(addr, align, offset)
+            Ok(allocator::align_allocation::<VM>(addr, align, offset))
         } else {
-            Address::ZERO
+            Ok(Address::ZERO)
         }
     }
 
@@ -107,24 +113,23 @@ impl<VM: VMBinding> Allocator<VM> for FreeListAllocator<VM> {
         align: usize,
         offset: usize,
         need_poll: bool,
-    ) -> Address {
+    ) -> Result<Address, AllocationError> {
         trace!("allow slow precise stress s={}", size);
         if need_poll {
-            self.acquire_global_block(0, 0, true);
+            self.acquire_global_block(0, 0, true)?;
         }
 
         // mimic what fastpath allocation does, except that we allocate from available_blocks_stress.
-        if let Some(block) = self.find_free_block_stress(size, align) {
+        if let Some(block) = self.find_free_block_stress(size, align)? {
             let cell = self.block_alloc(block);
-            allocator::align_allocation::<VM>(cell, align, offset)
+            Ok(allocator::align_allocation::<VM>(cell, align, offset))
         } else {
-            Address::ZERO
+            Ok(Address::ZERO)
         }
     }
 
     fn on_mutator_destroy(&mut self) {
-        let mut global = self.space.get_abandoned_block_lists().lock().unwrap();
-        self.abandon_blocks(&mut global);
+        self.abandon_all_blocks();
     }
 }
 
@@ -183,15 +188,22 @@ impl<VM: VMBinding> FreeListAllocator<VM> {
     }
 
     // Find an available block when stress GC is enabled. This includes getting a block from the space.
-    fn find_free_block_stress(&mut self, size: usize, align: usize) -> Option<Block> {
-        Self::find_free_block_with(
+    fn find_free_block_stress(
+        &mut self,
+        size: usize,
+        align: usize,
+    ) -> Result<Option<Block>, AllocationError> {
+        let local_block = Self::find_free_block_with(
             &mut self.available_blocks_stress,
             &mut self.consumed_blocks,
             size,
             align,
         )
-        .or_else(|| self.recycle_local_blocks(size, align, true))
-        .or_else(|| self.acquire_global_block(size, align, true))
+        .or_else(|| self.recycle_local_blocks(size, align, true));
+        match local_block {
+            Some(block) => Ok(Some(block)),
+            None => self.acquire_global_block(size, align, true),
+        }
     }
 
     // Find an available block from local block lists
@@ -293,14 +305,14 @@ impl<VM: VMBinding> FreeListAllocator<VM> {
         size: usize,
         align: usize,
         stress_test: bool,
-    ) -> Option<Block> {
+    ) -> Result<Option<Block>, AllocationError> {
         let bin = mi_bin::<VM>(size, align);
         loop {
-            match self.space.acquire_block(self.tls, size, align) {
+            match self.space.acquire_block(self.tls, size, align)? {
                 crate::policy::marksweepspace::native_ms::BlockAcquireResult::Exhausted => {
                     debug!("Acquire global block: None");
                     // GC
-                    return None;
+                    return Ok(None);
                 }
 
                 crate::policy::marksweepspace::native_ms::BlockAcquireResult::Fresh(block) => {
@@ -308,7 +320,7 @@ impl<VM: VMBinding> FreeListAllocator<VM> {
                     self.add_to_available_blocks(bin, block, stress_test);
                     self.init_block(block, self.available_blocks[bin].size);
 
-                    return Some(block);
+                    return Ok(Some(block));
                 }
 
                 crate::policy::marksweepspace::native_ms::BlockAcquireResult::AbandonedAvailable(block) => {
@@ -316,7 +328,7 @@ impl<VM: VMBinding> FreeListAllocator<VM> {
                     block.store_tls(self.tls);
                     if block.has_free_cells() {
                         self.add_to_available_blocks(bin, block, stress_test);
-                        return Some(block);
+                        return Ok(Some(block));
                     } else {
                         self.consumed_blocks[bin].push(block);
                     }
@@ -328,7 +340,7 @@ impl<VM: VMBinding> FreeListAllocator<VM> {
                     block.sweep::<VM>();
                     if block.has_free_cells() {
                         self.add_to_available_blocks(bin, block, stress_test);
-                        return Some(block);
+                        return Ok(Some(block));
                     } else {
                         self.consumed_blocks[bin].push(block);
                     }
@@ -452,6 +464,13 @@ impl<VM: VMBinding> FreeListAllocator<VM> {
         self.space.release_packet_done();
     }
 
+    /// Give all the blocks of this allocator to the abandoned block lists of the space, from which
+    /// any allocator can take them.
+    pub(crate) fn abandon_all_blocks(&mut self) {
+        let mut global = self.space.get_abandoned_block_lists().lock().unwrap();
+        self.abandon_blocks(&mut global);
+    }
+
     fn abandon_blocks(&mut self, global: &mut AbandonedBlockLists) {
         for i in 0..MI_BIN_FULL {
             let available = self.available_blocks.get_mut(i).unwrap();
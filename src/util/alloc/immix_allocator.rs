@@ -6,6 +6,7 @@ use crate::policy::immix::line::*;
 use crate::policy::immix::ImmixSpace;
 use crate::policy::space::Space;
 use crate::util::alloc::allocator::get_maximum_aligned_size;
+use crate::util::alloc::AllocationError;
 use crate::util::alloc::Allocator;
 use crate::util::linear_scan::Region;
 use crate::util::opaque_pointer::VMThread;
@@ -101,7 +102,12 @@ impl<VM: VMBinding> Allocator<VM> for ImmixAllocator<VM> {
     }
 
     /// Acquire a clean block from ImmixSpace for allocation.
-    fn alloc_slow_once(&mut self, size: usize, align: usize, offset: usize) -> Address {
+    fn alloc_slow_once(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+    ) -> Result<Address, AllocationError> {
         trace!("{:?}: alloc_slow_once", self.tls);
         self.acquire_clean_block(size, align, offset)
     }
@@ -116,7 +122,7 @@ impl<VM: VMBinding> Allocator<VM> for ImmixAllocator<VM> {
         align: usize,
         offset: usize,
         need_poll: bool,
-    ) -> Address {
+    ) -> Result<Address, AllocationError> {
         trace!("{:?}: alloc_slow_once_precise_stress", self.tls);
         // If we are required to make a poll, we call acquire_clean_block() which will acquire memory
         // from the space which includes a GC poll.
@@ -153,7 +159,7 @@ impl<VM: VMBinding> Allocator<VM> for ImmixAllocator<VM> {
             // This `alloc()` call should always succeed given the if-branch checks if we are out
             // of thread local block space
             trace!("{:?}: alloc_slow_once_precise_stress - alloc()", self.tls,);
-            self.alloc(size, align, offset)
+            Ok(self.alloc(size, align, offset))
         };
         // Set fake limits
         self.set_limit_for_stress();
@@ -288,9 +294,14 @@ impl<VM: VMBinding> ImmixAllocator<VM> {
     }
 
     // Get a clean block from ImmixSpace.
-    fn acquire_clean_block(&mut self, size: usize, align: usize, offset: usize) -> Address {
-        match self.immix_space().get_clean_block(self.tls, self.copy) {
-            None => Address::ZERO,
+    fn acquire_clean_block(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+    ) -> Result<Address, AllocationError> {
+        match self.immix_space().get_clean_block(self.tls, self.copy)? {
+            None => Ok(Address::ZERO),
             Some(block) => {
                 trace!(
                     "{:?}: Acquired a new block {:?} -> {:?}",
@@ -305,7 +316,7 @@ impl<VM: VMBinding> ImmixAllocator<VM> {
                     self.bump_pointer.cursor = block.start();
                     self.bump_pointer.limit = block.end();
                 }
-                self.alloc(size, align, offset)
+                Ok(self.alloc(size, align, offset))
             }
         }
     }
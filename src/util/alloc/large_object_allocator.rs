@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use crate::policy::largeobjectspace::LargeObjectSpace;
 use crate::policy::space::Space;
-use crate::util::alloc::{allocator, Allocator};
+use crate::util::alloc::{allocator, AllocationError, Allocator};
 use crate::util::opaque_pointer::*;
 use crate::util::Address;
 use crate::vm::VMBinding;
@@ -48,9 +48,14 @@ impl<VM: VMBinding> Allocator<VM> for LargeObjectAllocator<VM> {
         }
     }
 
-    fn alloc_slow_once(&mut self, size: usize, align: usize, _offset: usize) -> Address {
+    fn alloc_slow_once(
+        &mut self,
+        size: usize,
+        align: usize,
+        _offset: usize,
+    ) -> Result<Address, AllocationError> {
         if self.space.will_oom_on_acquire(self.tls, size) {
-            return Address::ZERO;
+            return Ok(Address::ZERO);
         }
 
         let maxbytes = allocator::get_maximum_aligned_size::<VM>(size, align);
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use crate::policy::marksweepspace::malloc_ms::MallocSpace;
 use crate::policy::space::Space;
+use crate::util::alloc::AllocationError;
 use crate::util::alloc::Allocator;
 use crate::util::opaque_pointer::*;
 use crate::util::Address;
@@ -41,8 +42,13 @@ impl<VM: VMBinding> Allocator<VM> for MallocAllocator<VM> {
         false
     }
 
-    fn alloc_slow_once(&mut self, size: usize, align: usize, offset: usize) -> Address {
-        self.space.alloc(self.tls, size, align, offset)
+    fn alloc_slow_once(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+    ) -> Result<Address, AllocationError> {
+        Ok(self.space.alloc(self.tls, size, align, offset))
     }
 }
 
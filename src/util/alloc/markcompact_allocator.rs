@@ -3,6 +3,7 @@ use std::sync::Arc;
 use super::allocator::AllocatorContext;
 use super::BumpAllocator;
 use crate::policy::space::Space;
+use crate::util::alloc::AllocationError;
 use crate::util::alloc::Allocator;
 use crate::util::opaque_pointer::*;
 use crate::util::Address;
@@ -63,7 +64,12 @@ impl<VM: VMBinding> Allocator<VM> for MarkCompactAllocator<VM> {
         }
     }
 
-    fn alloc_slow_once(&mut self, size: usize, align: usize, offset: usize) -> Address {
+    fn alloc_slow_once(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+    ) -> Result<Address, AllocationError> {
         trace!("alloc_slow");
         self.bump_allocator.alloc_slow_once(size, align, offset)
     }
@@ -82,7 +88,7 @@ impl<VM: VMBinding> Allocator<VM> for MarkCompactAllocator<VM> {
         align: usize,
         offset: usize,
         need_poll: bool,
-    ) -> Address {
+    ) -> Result<Address, AllocationError> {
         self.bump_allocator
             .alloc_slow_once_precise_stress(size, align, offset, need_poll)
     }
@@ -35,5 +35,9 @@ pub use free_list_allocator::FreeListAllocator;
 mod markcompact_allocator;
 pub use markcompact_allocator::MarkCompactAllocator;
 
+/// Region allocator (a bump pointer allocator that allocates into whole regions)
+pub mod region_allocator;
+pub use region_allocator::RegionAllocator;
+
 /// Embedded metadata pages
 pub(crate) mod embedded_meta_data;
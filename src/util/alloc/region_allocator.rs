@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use super::allocator::{align_allocation_no_fill, fill_alignment_gap, AllocatorContext};
+use super::BumpPointer;
+use crate::policy::region::{HeapRegion, RegionSpace};
+use crate::policy::space::Space;
+use crate::util::alloc::AllocationError;
+use crate::util::alloc::Allocator;
+use crate::util::linear_scan::Region;
+use crate::util::opaque_pointer::VMThread;
+use crate::util::Address;
+use crate::vm::VMBinding;
+
+/// A bump pointer allocator for `RegionSpace`. It bump allocates into a whole region, and
+/// acquires a clean region from the space when the current region is used up.
+#[repr(C)]
+pub struct RegionAllocator<VM: VMBinding> {
+    /// [`VMThread`] associated with this allocator instance
+    pub tls: VMThread,
+    /// The fastpath bump pointer.
+    pub bump_pointer: BumpPointer,
+    /// [`Space`](src/policy/space/Space) instance associated with this allocator instance.
+    space: &'static RegionSpace<VM>,
+    context: Arc<AllocatorContext<VM>>,
+    /// Is this a copy allocator? Mutators allocate into young regions, and GC workers copy
+    /// objects into old regions.
+    copy: bool,
+}
+
+impl<VM: VMBinding> RegionAllocator<VM> {
+    pub(crate) fn new(
+        tls: VMThread,
+        space: &'static dyn Space<VM>,
+        context: Arc<AllocatorContext<VM>>,
+        copy: bool,
+    ) -> Self {
+        RegionAllocator {
+            tls,
+            bump_pointer: BumpPointer::default(),
+            space: space.downcast_ref::<RegionSpace<VM>>().unwrap(),
+            context,
+            copy,
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.bump_pointer.reset(Address::ZERO, Address::ZERO);
+    }
+
+    pub(crate) fn region_space(&self) -> &'static RegionSpace<VM> {
+        self.space
+    }
+
+    /// Acquire a clean region from the space, and allocate from it.
+    fn acquire_clean_region(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+        stress_test: bool,
+    ) -> Result<Address, AllocationError> {
+        match self.space.get_clean_region(self.tls, self.copy)? {
+            None => Ok(Address::ZERO),
+            Some(region) => {
+                trace!(
+                    "{:?}: Acquired a new region {:?} -> {:?}",
+                    self.tls,
+                    region.start(),
+                    region.end()
+                );
+                if !stress_test {
+                    self.bump_pointer.reset(region.start(), region.end());
+                    Ok(self.alloc(size, align, offset))
+                } else {
+                    // For a stress test, we artificially make the fastpath fail by
+                    // manipulating the limit as below.
+                    // The assumption here is that we use an address range such that
+                    // cursor > region size always.
+                    self.bump_pointer.reset(region.start(), unsafe {
+                        Address::from_usize(HeapRegion::BYTES)
+                    });
+                    self.alloc_slow_once_precise_stress(size, align, offset, false)
+                }
+            }
+        }
+    }
+}
+
+impl<VM: VMBinding> Allocator<VM> for RegionAllocator<VM> {
+    fn get_space(&self) -> &'static dyn Space<VM> {
+        self.space as _
+    }
+
+    fn get_context(&self) -> &AllocatorContext<VM> {
+        &self.context
+    }
+
+    fn does_thread_local_allocation(&self) -> bool {
+        true
+    }
+
+    fn get_thread_local_buffer_granularity(&self) -> usize {
+        HeapRegion::BYTES
+    }
+
+    fn alloc(&mut self, size: usize, align: usize, offset: usize) -> Address {
+        debug_assert!(
+            size <= crate::policy::region::MAX_REGION_OBJECT_SIZE,
+            "Trying to allocate a {} bytes object, which is larger than MAX_REGION_OBJECT_SIZE {}",
+            size,
+            crate::policy::region::MAX_REGION_OBJECT_SIZE
+        );
+        let result = align_allocation_no_fill::<VM>(self.bump_pointer.cursor, align, offset);
+        let new_cursor = result + size;
+
+        if new_cursor > self.bump_pointer.limit {
+            trace!(
+                "{:?}: Thread local buffer used up, go to alloc slow path",
+                self.tls
+            );
+            self.alloc_slow(size, align, offset)
+        } else {
+            fill_alignment_gap::<VM>(self.bump_pointer.cursor, result);
+            self.bump_pointer.cursor = new_cursor;
+            trace!(
+                "{:?}: Bump allocation size: {}, result: {}, new_cursor: {}, limit: {}",
+                self.tls,
+                size,
+                result,
+                self.bump_pointer.cursor,
+                self.bump_pointer.limit
+            );
+            result
+        }
+    }
+
+    /// Acquire a clean region from RegionSpace for allocation.
+    fn alloc_slow_once(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+    ) -> Result<Address, AllocationError> {
+        trace!("{:?}: alloc_slow_once", self.tls);
+        self.acquire_clean_region(size, align, offset, false)
+    }
+
+    /// Slow path for allocation if precise stress testing has been enabled.
+    /// It works by manipulating the limit to be always below the cursor.
+    /// Can have three different cases:
+    ///  - acquires a new region if the hard limit has been met;
+    ///  - allocates an object using the bump pointer semantics from the
+    ///    fastpath if there is sufficient space; and
+    ///  - does not allocate an object but forces a poll for GC if the stress
+    ///    factor has been crossed.
+    fn alloc_slow_once_precise_stress(
+        &mut self,
+        size: usize,
+        align: usize,
+        offset: usize,
+        need_poll: bool,
+    ) -> Result<Address, AllocationError> {
+        if need_poll {
+            return self.acquire_clean_region(size, align, offset, true);
+        }
+
+        trace!("{:?}: alloc_slow_once_precise_stress", self.tls);
+        let result = align_allocation_no_fill::<VM>(self.bump_pointer.cursor, align, offset);
+        let new_cursor = result + size;
+
+        // For stress test, limit is [0, region_size) to artificially make the
+        // check in the fastpath (alloc()) fail. The real limit is recovered by
+        // adding it to the current cursor.
+        if new_cursor > self.bump_pointer.cursor + self.bump_pointer.limit.as_usize() {
+            self.acquire_clean_region(size, align, offset, true)
+        } else {
+            fill_alignment_gap::<VM>(self.bump_pointer.cursor, result);
+            self.bump_pointer.limit -= new_cursor - self.bump_pointer.cursor;
+            self.bump_pointer.cursor = new_cursor;
+            Ok(result)
+        }
+    }
+
+    fn get_tls(&self) -> VMThread {
+        self.tls
+    }
+}
@@ -7,6 +7,8 @@ use crate::policy::copyspace::CopySpace;
 use crate::policy::copyspace::CopySpaceCopyContext;
 use crate::policy::immix::ImmixSpace;
 use crate::policy::immix::{ImmixCopyContext, ImmixHybridCopyContext};
+use crate::policy::marksweepspace::native_ms::{MarkSweepCopyContext, MarkSweepSpace};
+use crate::policy::region::{RegionCopyContext, RegionSpace};
 use crate::policy::space::Space;
 use crate::util::object_forwarding;
 use crate::util::opaque_pointer::VMWorkerThread;
@@ -24,6 +26,8 @@ use super::alloc::allocator::AllocatorContext;
 const MAX_COPYSPACE_COPY_ALLOCATORS: usize = 1;
 const MAX_IMMIX_COPY_ALLOCATORS: usize = 1;
 const MAX_IMMIX_HYBRID_COPY_ALLOCATORS: usize = 1;
+const MAX_MARK_SWEEP_COPY_ALLOCATORS: usize = 1;
+const MAX_REGION_COPY_ALLOCATORS: usize = 1;
 
 type CopySpaceMapping<VM> = Vec<(CopySelector, &'static dyn Space<VM>)>;
 
@@ -59,6 +63,10 @@ pub struct GCWorkerCopyContext<VM: VMBinding> {
     pub immix: [MaybeUninit<ImmixCopyContext<VM>>; MAX_IMMIX_COPY_ALLOCATORS],
     /// Copy allocators for ImmixSpace
     pub immix_hybrid: [MaybeUninit<ImmixHybridCopyContext<VM>>; MAX_IMMIX_HYBRID_COPY_ALLOCATORS],
+    /// Copy allocators for MarkSweepSpace
+    pub mark_sweep: [MaybeUninit<MarkSweepCopyContext<VM>>; MAX_MARK_SWEEP_COPY_ALLOCATORS],
+    /// Copy allocators for RegionSpace
+    pub region: [MaybeUninit<RegionCopyContext<VM>>; MAX_REGION_COPY_ALLOCATORS],
     /// The config for the plan
     config: CopyConfig<VM>,
 }
@@ -99,6 +107,12 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
                 unsafe { self.immix_hybrid[index as usize].assume_init_mut() }
                     .alloc_copy(original, bytes, align, offset)
             }
+            CopySelector::MarkSweep(index) => {
+                unsafe { self.mark_sweep[index as usize].assume_init_mut() }
+                    .alloc_copy(original, bytes, align, offset)
+            }
+            CopySelector::Region(index) => unsafe { self.region[index as usize].assume_init_mut() }
+                .alloc_copy(original, bytes, align, offset),
             CopySelector::Unused => unreachable!(),
         }
     }
@@ -137,6 +151,13 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
                 unsafe { self.immix_hybrid[index as usize].assume_init_mut() }
                     .post_copy(object, bytes)
             }
+            CopySelector::MarkSweep(index) => {
+                unsafe { self.mark_sweep[index as usize].assume_init_mut() }
+                    .post_copy(object, bytes)
+            }
+            CopySelector::Region(index) => {
+                unsafe { self.region[index as usize].assume_init_mut() }.post_copy(object, bytes)
+            }
             CopySelector::Unused => unreachable!(),
         }
     }
@@ -155,6 +176,12 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
                 CopySelector::ImmixHybrid(index) => {
                     unsafe { self.immix_hybrid[*index as usize].assume_init_mut() }.prepare()
                 }
+                CopySelector::MarkSweep(index) => {
+                    unsafe { self.mark_sweep[*index as usize].assume_init_mut() }.prepare()
+                }
+                CopySelector::Region(index) => {
+                    unsafe { self.region[*index as usize].assume_init_mut() }.prepare()
+                }
                 CopySelector::Unused => {}
             }
         }
@@ -174,6 +201,12 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
                 CopySelector::ImmixHybrid(index) => {
                     unsafe { self.immix_hybrid[*index as usize].assume_init_mut() }.release()
                 }
+                CopySelector::MarkSweep(index) => {
+                    unsafe { self.mark_sweep[*index as usize].assume_init_mut() }.release()
+                }
+                CopySelector::Region(index) => {
+                    unsafe { self.region[*index as usize].assume_init_mut() }.release()
+                }
                 CopySelector::Unused => {}
             }
         }
@@ -190,6 +223,8 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
             copy: unsafe { MaybeUninit::uninit().assume_init() },
             immix: unsafe { MaybeUninit::uninit().assume_init() },
             immix_hybrid: unsafe { MaybeUninit::uninit().assume_init() },
+            mark_sweep: unsafe { MaybeUninit::uninit().assume_init() },
+            region: unsafe { MaybeUninit::uninit().assume_init() },
             config,
         };
         let context = Arc::new(AllocatorContext::new(mmtk));
@@ -218,6 +253,20 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
                         space.downcast_ref::<ImmixSpace<VM>>().unwrap(),
                     ));
                 }
+                CopySelector::MarkSweep(index) => {
+                    ret.mark_sweep[index as usize].write(MarkSweepCopyContext::new(
+                        worker_tls,
+                        context.clone(),
+                        space.downcast_ref::<MarkSweepSpace<VM>>().unwrap(),
+                    ));
+                }
+                CopySelector::Region(index) => {
+                    ret.region[index as usize].write(RegionCopyContext::new(
+                        worker_tls,
+                        context.clone(),
+                        space.downcast_ref::<RegionSpace<VM>>().unwrap(),
+                    ));
+                }
                 CopySelector::Unused => unreachable!(),
             }
         }
@@ -231,6 +280,8 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
             copy: unsafe { MaybeUninit::uninit().assume_init() },
             immix: unsafe { MaybeUninit::uninit().assume_init() },
             immix_hybrid: unsafe { MaybeUninit::uninit().assume_init() },
+            mark_sweep: unsafe { MaybeUninit::uninit().assume_init() },
+            region: unsafe { MaybeUninit::uninit().assume_init() },
             config: CopyConfig::default(),
         }
     }
@@ -266,6 +317,8 @@ pub(crate) enum CopySelector {
     CopySpace(u8),
     Immix(u8),
     ImmixHybrid(u8),
+    MarkSweep(u8),
+    Region(u8),
     #[default]
     Unused,
 }
@@ -8,6 +8,7 @@ use crate::util::heap::pageresource::CommonPageResource;
 use crate::util::heap::space_descriptor::SpaceDescriptor;
 use crate::util::linear_scan::Region;
 use crate::util::opaque_pointer::*;
+use crate::util::options::DecommitPolicy;
 use crate::util::rust_util::zeroed_alloc::new_zeroed_vec;
 use crate::vm::*;
 use atomic::Ordering;
@@ -56,6 +57,32 @@ impl<VM: VMBinding, B: Region> PageResource<VM> for BlockPageResource<VM, B> {
         let _sync = self.sync.lock().unwrap();
         self.flpr.get_available_physical_pages()
     }
+
+    fn decommit_free_pages(&self, policy: DecommitPolicy) -> usize {
+        let _sync = self.sync.lock().unwrap();
+        let mut blocks = vec![];
+        self.block_queue
+            .iterate_blocks(&mut |block| blocks.push(block.start()));
+        blocks.sort_unstable();
+        // Decommit runs of adjacent blocks together to make fewer system calls.
+        let mut pages = 0;
+        let mut run: Option<(Address, Address)> = None;
+        for block in blocks {
+            match run {
+                Some((start, end)) if end == block => run = Some((start, block + B::BYTES)),
+                _ => {
+                    if let Some((start, end)) = run {
+                        pages += self.common().decommit(start, end - start, policy);
+                    }
+                    run = Some((block, block + B::BYTES));
+                }
+            }
+        }
+        if let Some((start, end)) = run {
+            pages += self.common().decommit(start, end - start, policy);
+        }
+        pages
+    }
 }
 
 impl<VM: VMBinding, B: Region> BlockPageResource<VM, B> {
@@ -16,6 +16,7 @@ use crate::util::heap::pageresource::CommonPageResource;
 use crate::util::heap::space_descriptor::SpaceDescriptor;
 use crate::util::memory;
 use crate::util::opaque_pointer::*;
+use crate::util::options::DecommitPolicy;
 use crate::util::raw_memory_freelist::RawMemoryFreeList;
 use crate::vm::*;
 use std::marker::PhantomData;
@@ -77,6 +78,25 @@ impl<VM: VMBinding> PageResource<VM> for FreeListPageResource<VM> {
         rtn
     }
 
+    fn decommit_free_pages(&self, policy: DecommitPolicy) -> usize {
+        let sync = self.sync.lock().unwrap();
+        // A growable free list may not have any units yet. Don't walk it.
+        if sync.pages_currently_on_freelist == 0 {
+            return 0;
+        }
+        let mut pages = 0;
+        // Walk the free units on the list of this page resource.
+        let head = sync.free_list.head();
+        let mut unit = sync.free_list.get_next(head);
+        while unit != head {
+            let start = sync.start + conversions::pages_to_bytes(unit as _);
+            let bytes = conversions::pages_to_bytes(sync.free_list.size(unit) as _);
+            pages += self.common.decommit(start, bytes, policy);
+            unit = sync.free_list.get_next(unit);
+        }
+        pages
+    }
+
     fn alloc_pages(
         &self,
         space_descriptor: SpaceDescriptor,
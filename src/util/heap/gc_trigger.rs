@@ -4,9 +4,11 @@ use crate::global_state::GlobalState;
 use crate::plan::gc_requester::GCRequester;
 use crate::plan::Plan;
 use crate::policy::space::Space;
-use crate::util::constants::BYTES_IN_PAGE;
+use crate::util::constants::{BYTES_IN_PAGE, LOG_BYTES_IN_PAGE};
 use crate::util::conversions;
-use crate::util::options::{GCTriggerSelector, Options, DEFAULT_MAX_NURSERY, DEFAULT_MIN_NURSERY};
+use crate::util::options::{
+    DecommitPolicy, GCTriggerSelector, Options, DEFAULT_MAX_NURSERY, DEFAULT_MIN_NURSERY,
+};
 use crate::vm::VMBinding;
 use crate::MMTK;
 use std::mem::MaybeUninit;
@@ -26,6 +28,15 @@ pub struct GCTrigger<VM: VMBinding> {
     gc_requester: Arc<GCRequester<VM>>,
     options: Arc<Options>,
     state: Arc<GlobalState>,
+    /// The heap size in pages at the end of the last GC. We return free memory to the OS when the
+    /// heap shrinks below this.
+    last_heap_pages: AtomicUsize,
+    /// Decides whether to collect in idle time.
+    pub(crate) idle: IdleGCTrigger,
+    /// Resizes the nursery if the nursery size is Adaptive.
+    nursery: AdaptiveNursery,
+    /// Escalates GCs when the heap usage exceeds the soft heap limit.
+    pub(crate) soft_limit: SoftHeapLimit,
 }
 
 impl<VM: VMBinding> GCTrigger<VM> {
@@ -34,11 +45,11 @@ impl<VM: VMBinding> GCTrigger<VM> {
         gc_requester: Arc<GCRequester<VM>>,
         state: Arc<GlobalState>,
     ) -> Self {
-        GCTrigger {
+        let trigger = GCTrigger {
             plan: MaybeUninit::uninit(),
             policy: match *options.gc_trigger {
                 GCTriggerSelector::FixedHeapSize(size) => Box::new(FixedHeapSizeTrigger {
-                    total_pages: conversions::bytes_to_pages_up(size),
+                    total_pages: AtomicUsize::new(conversions::bytes_to_pages_up(size)),
                 }),
                 GCTriggerSelector::DynamicHeapSize(min, max) => 'dynamic_heap_size: {
                     let min_pages = conversions::bytes_to_pages_up(min);
@@ -47,20 +58,71 @@ impl<VM: VMBinding> GCTrigger<VM> {
                     if *options.plan == crate::util::options::PlanSelector::NoGC {
                         warn!("Cannot use dynamic heap size with NoGC.  Using fixed heap size trigger instead.");
                         break 'dynamic_heap_size Box::new(FixedHeapSizeTrigger {
-                            total_pages: max_pages,
+                            total_pages: AtomicUsize::new(max_pages),
                         });
                     }
 
-                    Box::new(MemBalancerTrigger::new(min_pages, max_pages))
+                    Box::new(MemBalancerTrigger::new(
+                        min_pages,
+                        max_pages,
+                        *options.mem_balancer_tuning_factor,
+                    ))
                 }
-                GCTriggerSelector::Delegated => {
-                    <VM::VMCollection as crate::vm::Collection<VM>>::create_gc_trigger()
+                GCTriggerSelector::GCTimeRatio(min, max, ratio) => 'gc_time_ratio: {
+                    let min_pages = conversions::bytes_to_pages_up(min);
+                    let max_pages = conversions::bytes_to_pages_up(max);
+
+                    if *options.plan == crate::util::options::PlanSelector::NoGC {
+                        warn!("Cannot use GC time ratio with NoGC.  Using fixed heap size trigger instead.");
+                        break 'gc_time_ratio Box::new(FixedHeapSizeTrigger {
+                            total_pages: AtomicUsize::new(max_pages),
+                        });
+                    }
+
+                    Box::new(GCTimeRatioTrigger::new(min_pages, max_pages, ratio))
+                }
+                GCTriggerSelector::MemoryPressure(min, max) => 'memory_pressure: {
+                    let min_pages = conversions::bytes_to_pages_up(min);
+                    let max_pages = conversions::bytes_to_pages_up(max);
+
+                    if *options.plan == crate::util::options::PlanSelector::NoGC {
+                        warn!("Cannot use memory pressure with NoGC.  Using fixed heap size trigger instead.");
+                        break 'memory_pressure Box::new(FixedHeapSizeTrigger {
+                            total_pages: AtomicUsize::new(max_pages),
+                        });
+                    }
+
+                    Box::new(MemoryPressureTrigger::new(
+                        min_pages,
+                        max_pages,
+                        MemoryPressureSource {
+                            cgroup_path: (*options.cgroup_memory_path).clone().into(),
+                            psi_path: (*options.memory_pressure_path).clone().into(),
+                        },
+                    ))
                 }
+                GCTriggerSelector::Delegated => Box::new(DelegatedTrigger::new(
+                    <VM::VMCollection as crate::vm::Collection<VM>>::create_gc_trigger(),
+                )),
             },
+            nursery: AdaptiveNursery::new(match *options.nursery {
+                crate::util::options::NurserySize::Adaptive { min, max: _ } => min,
+                _ => 0,
+            }),
+            soft_limit: SoftHeapLimit::new(conversions::bytes_to_pages_up(
+                *options.soft_heap_limit,
+            )),
             options,
             gc_requester,
             state,
-        }
+            last_heap_pages: AtomicUsize::new(0),
+            idle: IdleGCTrigger::new(),
+        };
+        trigger.last_heap_pages.store(
+            trigger.policy.get_current_heap_size_in_pages(),
+            Ordering::Relaxed,
+        );
+        trigger
     }
 
     /// Set the plan. This is called in `create_plan()` after we created a boxed plan.
@@ -81,6 +143,10 @@ impl<VM: VMBinding> GCTrigger<VM> {
     /// * `space`: The space that triggered the poll. This could `None` if the poll is not triggered by a space.
     pub fn poll(&self, space_full: bool, space: Option<&dyn Space<VM>>) -> bool {
         let plan = unsafe { self.plan.assume_init() };
+        if self.poll_soft_heap_limit(plan) {
+            self.gc_requester.request();
+            return true;
+        }
         if self
             .policy
             .is_gc_required(space_full, space.map(|s| SpaceStats::new(s)), plan)
@@ -102,6 +168,111 @@ impl<VM: VMBinding> GCTrigger<VM> {
         false
     }
 
+    /// Escalate to the next step if the heap usage exceeds the soft heap limit, and tell the binding
+    /// about it. Return true if a GC is needed for the step.
+    fn poll_soft_heap_limit(&self, plan: &dyn Plan<VM = VM>) -> bool {
+        use crate::vm::Collection;
+        if !self.soft_limit.is_enabled()
+            || !plan.constraints().collects_garbage
+            || !self.state.is_initialized()
+        {
+            return false;
+        }
+        let used_pages = plan.get_used_pages();
+        let Some(stage) = self
+            .soft_limit
+            .escalate(used_pages, plan.generational().is_some())
+        else {
+            return false;
+        };
+        info!(
+            "[POLL] Soft heap limit exceeded ({}/{} pages): {:?}",
+            used_pages, self.soft_limit.limit_pages, stage
+        );
+        VM::VMCollection::soft_heap_limit_exceeded(stage);
+        match stage {
+            SoftHeapLimitStage::NurseryGC => true,
+            SoftHeapLimitStage::FullHeapGC => {
+                if let Some(gen) = plan.generational() {
+                    gen.force_full_heap_collection();
+                }
+                true
+            }
+            // The GC will be an emergency collection. See `SoftHeapLimit::is_clearing_soft_references`.
+            SoftHeapLimitStage::ClearSoftReferences => true,
+            SoftHeapLimitStage::OutOfMemory => false,
+        }
+    }
+
+    /// Return free memory in all the spaces to the OS if the heap size is lower than it was at the
+    /// end of the last GC. This is called at the end of a GC, after the policy has updated the heap
+    /// size, and before mutators resume.
+    pub(crate) fn decommit_free_memory(&self) {
+        let heap_pages = self.policy.get_current_heap_size_in_pages();
+        let last_heap_pages = self.last_heap_pages.swap(heap_pages, Ordering::Relaxed);
+        let policy = *self.options.decommit_policy;
+        if policy == DecommitPolicy::Never || heap_pages >= last_heap_pages {
+            return;
+        }
+        let mut pages = 0;
+        self.plan().for_each_space(&mut |space| {
+            pages += space.get_page_resource().decommit_free_pages(policy);
+        });
+        debug!(
+            "Heap shrunk from {} to {} pages. Decommitted {} free pages.",
+            last_heap_pages, heap_pages, pages
+        );
+    }
+
+    /// Record which spaces still exceed their budgets (the option `space_budgets`) at the end of a
+    /// GC. Allocations into those spaces fail instead of triggering another GC.
+    pub(crate) fn check_space_budgets(&self) {
+        self.plan().for_each_space(&mut |space| {
+            let common = space.common();
+            if let Some((budget_pages, _)) = common.budget {
+                let reserved_pages = space.reserved_pages();
+                let over_budget = reserved_pages > budget_pages;
+                if over_budget {
+                    info!(
+                        "{} exceeds its budget after GC ({}/{} pages)",
+                        space.get_name(),
+                        reserved_pages,
+                        budget_pages
+                    );
+                }
+                common
+                    .over_budget_after_gc
+                    .store(over_budget, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// The binding tells us that mutators are idle until the deadline. Request a GC if the option
+    /// `idle_gc` is set, a GC is likely needed soon, and its first pause is expected to end before
+    /// the deadline. Return true if a GC is requested.
+    pub(crate) fn notify_idle(&self, deadline: Instant) -> bool {
+        use crate::vm::Collection;
+        let plan = self.plan();
+        if !*self.options.idle_gc
+            || !plan.constraints().collects_garbage
+            || !self.state.is_initialized()
+            || !VM::VMCollection::is_collection_enabled()
+        {
+            return false;
+        }
+        if !self.idle.should_collect(
+            Instant::now(),
+            deadline,
+            plan.get_reserved_pages(),
+            plan.get_total_pages(),
+        ) {
+            return false;
+        }
+        info!("[IDLE] Triggering collection");
+        self.gc_requester.request();
+        true
+    }
+
     pub fn should_do_stress_gc(&self) -> bool {
         Self::should_do_stress_gc_inner(&self.state, &self.options)
     }
@@ -137,6 +308,7 @@ impl<VM: VMBinding> GCTrigger<VM> {
                 }
             }
             NurserySize::Fixed(sz) => sz,
+            NurserySize::Adaptive { min: _, max: _ } => self.nursery.size(),
         }
     }
 
@@ -160,6 +332,7 @@ impl<VM: VMBinding> GCTrigger<VM> {
                 }
             }
             NurserySize::Fixed(sz) => sz,
+            NurserySize::Adaptive { min, max: _ } => min,
         }
     }
 
@@ -172,6 +345,25 @@ impl<VM: VMBinding> GCTrigger<VM> {
     pub fn get_min_nursery_pages(&self) -> usize {
         crate::util::conversions::bytes_to_pages_up(self.get_min_nursery_bytes())
     }
+
+    /// A nursery GC starts. `nursery_pages` is the number of pages allocated into the nursery since
+    /// the last GC, and `used_pages` is the number of pages used by the plan, including the nursery.
+    pub(crate) fn on_nursery_gc_start(&self, nursery_pages: usize, used_pages: usize) {
+        use crate::util::options::NurserySize;
+        if let NurserySize::Adaptive { .. } = *self.options.nursery {
+            self.nursery.on_gc_start(nursery_pages, used_pages);
+        }
+    }
+
+    /// A nursery GC ends. `used_pages` is the number of pages used by the plan after the GC. This
+    /// resizes the nursery if it is Adaptive.
+    pub(crate) fn on_nursery_gc_end(&self, used_pages: usize) {
+        use crate::util::options::NurserySize;
+        if let NurserySize::Adaptive { min, max } = *self.options.nursery {
+            let pause_goal = std::time::Duration::from_millis(*self.options.pause_time_goal as u64);
+            self.nursery.on_gc_end(used_pages, pause_goal, min, max);
+        }
+    }
 }
 
 /// Provides statistics about the space. This is exposed to bindings, as it is used
@@ -185,6 +377,11 @@ impl<'a, VM: VMBinding> SpaceStats<'a, VM> {
         Self(space)
     }
 
+    /// Get the name of the space.
+    pub fn name(&self) -> &'static str {
+        self.0.get_name()
+    }
+
     /// Get the number of reserved pages for the space.
     pub fn reserved_pages(&self) -> usize {
         self.0.reserved_pages()
@@ -235,11 +432,59 @@ pub trait GCTriggerPolicy<VM: VMBinding>: Sync + Send {
     fn get_max_heap_size_in_pages(&self) -> usize;
     /// Can the heap size grow?
     fn can_heap_size_grow(&self) -> bool;
+    /// Set the current heap size (in pages). Return false if the policy does not allow the heap
+    /// size to be set this way, or the size is out of the min and max heap size.
+    fn set_current_heap_size_in_pages(&self, _pages: usize) -> bool {
+        false
+    }
+    /// Change the min and max heap size (in pages) at run time. The caller has checked that
+    /// `min_pages <= max_pages`. The policy should move the current heap size into the new bounds
+    /// right away. Return false if the policy does not allow the bounds to be changed.
+    fn set_heap_size_bounds(&self, _min_pages: usize, _max_pages: usize) -> bool {
+        false
+    }
+}
+
+/// The min and max heap size of a policy. They can be changed at run time by
+/// [`crate::memory_manager::set_heap_size_bounds`], so readers may see an old min with a new max
+/// or the other way around. Use [`HeapSizeBounds::clamp`] instead of `usize::clamp`, which panics
+/// if the min is larger than the max.
+struct HeapSizeBounds {
+    min_pages: AtomicUsize,
+    max_pages: AtomicUsize,
+}
+
+impl HeapSizeBounds {
+    fn new(min_pages: usize, max_pages: usize) -> Self {
+        Self {
+            min_pages: AtomicUsize::new(min_pages),
+            max_pages: AtomicUsize::new(max_pages),
+        }
+    }
+
+    fn min(&self) -> usize {
+        self.min_pages.load(Ordering::Relaxed)
+    }
+
+    fn max(&self) -> usize {
+        self.max_pages.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, min_pages: usize, max_pages: usize) {
+        debug_assert!(min_pages <= max_pages);
+        self.min_pages.store(min_pages, Ordering::Relaxed);
+        self.max_pages.store(max_pages, Ordering::Relaxed);
+    }
+
+    /// Move the heap size into the bounds. The max wins if the bounds are being changed.
+    fn clamp(&self, pages: usize) -> usize {
+        pages.max(self.min()).min(self.max())
+    }
 }
 
 /// A simple GC trigger that uses a fixed heap size.
 pub struct FixedHeapSizeTrigger {
-    total_pages: usize,
+    total_pages: AtomicUsize,
 }
 impl<VM: VMBinding> GCTriggerPolicy<VM> for FixedHeapSizeTrigger {
     fn is_gc_required(
@@ -254,20 +499,26 @@ impl<VM: VMBinding> GCTriggerPolicy<VM> for FixedHeapSizeTrigger {
 
     fn is_heap_full(&self, plan: &dyn Plan<VM = VM>) -> bool {
         // If reserved pages is larger than the total pages, the heap is full.
-        plan.get_reserved_pages() > self.total_pages
+        plan.get_reserved_pages() > self.total_pages.load(Ordering::Relaxed)
     }
 
     fn get_current_heap_size_in_pages(&self) -> usize {
-        self.total_pages
+        self.total_pages.load(Ordering::Relaxed)
     }
 
     fn get_max_heap_size_in_pages(&self) -> usize {
-        self.total_pages
+        self.total_pages.load(Ordering::Relaxed)
     }
 
     fn can_heap_size_grow(&self) -> bool {
         false
     }
+
+    fn set_heap_size_bounds(&self, _min_pages: usize, max_pages: usize) -> bool {
+        // The heap size is fixed, so use the max heap size, as we do for NoGC with a dynamic heap.
+        self.total_pages.store(max_pages, Ordering::Relaxed);
+        true
+    }
 }
 
 use atomic_refcell::AtomicRefCell;
@@ -275,16 +526,16 @@ use std::time::Instant;
 
 /// An implementation of MemBalancer (Optimal heap limits for reducing browser memory use, <https://dl.acm.org/doi/10.1145/3563323>)
 /// We use MemBalancer to decide a heap limit between the min heap and the max heap.
-/// The current implementation is a simplified version of mem balancer and it does not take collection/allocation speed into account,
-/// and uses a fixed constant instead.
-// TODO: implement a complete mem balancer.
+/// At the end of each (mature) GC, the heap limit is set to the live memory `L` plus an extra heap of
+/// `sqrt(L * g / (c * s))`, where `g` is the smoothed allocation rate, `s` is the smoothed collection
+/// speed, and `c` is the tuning factor from the option `mem_balancer_tuning_factor`.
 pub struct MemBalancerTrigger {
-    /// The min heap size
-    min_heap_pages: usize,
-    /// The max heap size
-    max_heap_pages: usize,
+    /// The min and max heap size
+    bounds: HeapSizeBounds,
     /// The current heap size
     current_heap_pages: AtomicUsize,
+    /// The tuning factor `c`. A smaller value trades more memory for less GC time.
+    tuning_factor: f64,
     /// The number of pending allocation pages. The allocation requests for them have failed, and a GC is triggered.
     /// We will need to take them into consideration so that the new heap size can accomodate those allocations.
     pending_pages: AtomicUsize,
@@ -294,7 +545,7 @@ pub struct MemBalancerTrigger {
 
 #[derive(Copy, Clone, Debug)]
 struct MemBalancerStats {
-    // Allocation/collection stats in the previous estimation. We keep this so we can use them to smooth the current value
+    // Smoothed allocation/collection stats up to the previous estimation. We keep this so we can use them to smooth the current value
     /// Previous allocated memory in pages.
     allocation_pages_prev: Option<f64>,
     /// Previous allocation duration in secs
@@ -528,18 +779,26 @@ impl<VM: VMBinding> GCTriggerPolicy<VM> for MemBalancerTrigger {
     }
 
     fn get_max_heap_size_in_pages(&self) -> usize {
-        self.max_heap_pages
+        self.bounds.max()
     }
 
     fn can_heap_size_grow(&self) -> bool {
-        self.current_heap_pages.load(Ordering::Relaxed) < self.max_heap_pages
+        self.current_heap_pages.load(Ordering::Relaxed) < self.bounds.max()
+    }
+
+    fn set_heap_size_bounds(&self, min_pages: usize, max_pages: usize) -> bool {
+        self.bounds.set(min_pages, max_pages);
+        let current = self.current_heap_pages.load(Ordering::Relaxed);
+        self.current_heap_pages
+            .store(self.bounds.clamp(current), Ordering::Relaxed);
+        true
     }
 }
 impl MemBalancerTrigger {
-    fn new(min_heap_pages: usize, max_heap_pages: usize) -> Self {
+    fn new(min_heap_pages: usize, max_heap_pages: usize, tuning_factor: f64) -> Self {
         Self {
-            min_heap_pages,
-            max_heap_pages,
+            bounds: HeapSizeBounds::new(min_heap_pages, max_heap_pages),
+            tuning_factor,
             pending_pages: AtomicUsize::new(0),
             // start with min heap
             current_heap_pages: AtomicUsize::new(min_heap_pages),
@@ -566,7 +825,6 @@ impl MemBalancerTrigger {
         // Constants from the original paper
         const ALLOCATION_SMOOTH_FACTOR: f64 = 0.95;
         const COLLECTION_SMOOTH_FACTOR: f64 = 0.5;
-        const TUNING_FACTOR: f64 = 0.2;
 
         // Smooth memory/time for allocation/collection
         let smooth = |prev: Option<f64>, cur, factor| {
@@ -604,28 +862,27 @@ impl MemBalancerTrigger {
             gc_time
         );
 
-        // We got the smoothed stats. Now save the current stats as previous stats
-        stats.allocation_pages_prev = Some(stats.allocation_pages);
+        // We got the smoothed stats. Now save them as previous stats, so that the stats are
+        // exponentially smoothed across all the estimations.
+        stats.allocation_pages_prev = Some(alloc_mem);
         stats.allocation_pages = 0f64;
-        stats.allocation_time_prev = Some(stats.allocation_time);
+        stats.allocation_time_prev = Some(alloc_time);
         stats.allocation_time = 0f64;
-        stats.collection_pages_prev = Some(stats.collection_pages);
+        stats.collection_pages_prev = Some(gc_mem);
         stats.collection_pages = 0f64;
-        stats.collection_time_prev = Some(stats.collection_time);
+        stats.collection_time_prev = Some(gc_time);
         stats.collection_time = 0f64;
 
-        // Calculate the square root
-        let e: f64 = if alloc_mem != 0f64 && gc_mem != 0f64 && alloc_time != 0f64 && gc_time != 0f64
-        {
-            let mut e = live as f64;
-            e *= alloc_mem / alloc_time;
-            e /= TUNING_FACTOR;
-            e /= gc_mem / gc_time;
-            e.sqrt()
-        } else {
+        let e = Self::optimal_extra_pages(
+            live,
+            alloc_mem / alloc_time,
+            gc_mem / gc_time,
+            self.tuning_factor,
+        )
+        .unwrap_or_else(|| {
             // If any collected stat is abnormal, we use the fallback heuristics.
             (live as f64 * 4096f64).sqrt()
-        };
+        });
 
         // Get pending allocations
         let pending_pages = self.pending_pages.load(Ordering::SeqCst);
@@ -633,18 +890,1288 @@ impl MemBalancerTrigger {
         // This is the optimal heap limit due to mem balancer. We will need to clamp the value to the defined min/max range.
         let optimal_heap = live + e as usize + extra_reserve + pending_pages;
         trace!(
-            "optimal = live {} + sqrt(live) {} + extra {}",
+            "optimal = live {} + sqrt(live * g / (c * s)) {} + extra {}",
             live,
             e,
             extra_reserve
         );
 
         // The new heap size must be within min/max.
-        let new_heap = optimal_heap.clamp(self.min_heap_pages, self.max_heap_pages);
+        let new_heap = self.bounds.clamp(optimal_heap);
         debug!(
             "MemBalander: new heap limit = {} pages (optimal = {}, clamped to [{}, {}])",
-            new_heap, optimal_heap, self.min_heap_pages, self.max_heap_pages
+            new_heap,
+            optimal_heap,
+            self.bounds.min(),
+            self.bounds.max()
+        );
+        self.current_heap_pages.store(new_heap, Ordering::Relaxed);
+    }
+
+    /// The extra heap in pages on top of the live memory, `sqrt(L * g / (c * s))`, where `L` is
+    /// the live pages, `g` is the allocation rate and `s` is the collection speed, both in pages
+    /// per second, and `c` is the tuning factor.  Return `None` if the rates are not usable.
+    fn optimal_extra_pages(
+        live: usize,
+        allocation_rate: f64,
+        collection_rate: f64,
+        tuning_factor: f64,
+    ) -> Option<f64> {
+        let usable = |rate: f64| rate.is_finite() && rate > 0f64;
+        if !usable(allocation_rate) || !usable(collection_rate) {
+            return None;
+        }
+        Some((live as f64 * allocation_rate / (tuning_factor * collection_rate)).sqrt())
+    }
+}
+
+/// A GC trigger that sizes the heap between the min heap and the max heap so that the time spent in
+/// GC is at most `1 / (1 + ratio)` of the total time, like the `GCTimeRatio` option of HotSpot.
+/// This favors throughput over memory footprint.
+///
+/// At the end of each GC, we compute the fraction of time spent in GC since the end of the last GC,
+/// and smooth it with the previous ones. If GC takes more than the goal, the heap grows. If GC takes
+/// less than half of the goal, the heap shrinks. In either case, we resize the free heap (the heap
+/// above the live memory) so that the fraction of GC time would be in the middle of the two, assuming
+/// that the GC time does not depend on the heap size, and the mutator time between GCs is
+/// proportional to the free heap.
+pub struct GCTimeRatioTrigger {
+    /// The min and max heap size
+    bounds: HeapSizeBounds,
+    /// The current heap size
+    current_heap_pages: AtomicUsize,
+    /// The max fraction of time that should be spent in GC, i.e. `1 / (1 + ratio)`.
+    gc_time_goal: f64,
+    /// The number of pending allocation pages. The allocation requests for them have failed, and a GC is triggered.
+    pending_pages: AtomicUsize,
+    /// Statistics
+    stats: AtomicRefCell<GCTimeRatioStats>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct GCTimeRatioStats {
+    // We measure the time ourselves, as the timers in `Stats` only run while the harness is
+    // gathering statistics.
+    /// The time when this GC starts
+    gc_start_time: Instant,
+    /// The time when the last GC ends, or when the trigger is created before the first GC.
+    gc_end_time: Instant,
+    /// The smoothed fraction of time spent in GC
+    gc_time_fraction: Option<f64>,
+}
+
+impl<VM: VMBinding> GCTriggerPolicy<VM> for GCTimeRatioTrigger {
+    fn is_gc_required(
+        &self,
+        space_full: bool,
+        space: Option<SpaceStats<VM>>,
+        plan: &dyn Plan<VM = VM>,
+    ) -> bool {
+        // Let the plan decide
+        plan.collection_required(space_full, space)
+    }
+
+    fn on_pending_allocation(&self, pages: usize) {
+        self.pending_pages.fetch_add(pages, Ordering::SeqCst);
+    }
+
+    fn on_gc_start(&self, _mmtk: &'static MMTK<VM>) {
+        self.stats.borrow_mut().gc_start_time = Instant::now();
+    }
+
+    fn on_gc_end(&self, mmtk: &'static MMTK<VM>) {
+        let mut stats = self.stats.borrow_mut();
+        let now = Instant::now();
+        let gc_time = (now - stats.gc_start_time).as_secs_f64();
+        let total_time = (now - stats.gc_end_time).as_secs_f64();
+        stats.gc_end_time = now;
+
+        let plan = mmtk.get_plan();
+        let mut extra_reserve = plan.get_collection_reserved_pages();
+        if plan.generational().is_some() {
+            // Leave room for the min nursery so that the next GC is not forced to be a full heap GC.
+            extra_reserve += mmtk.gc_trigger.get_min_nursery_pages();
+        }
+        self.compute_new_heap_limit(
+            plan.get_reserved_pages(),
+            extra_reserve,
+            gc_time,
+            total_time,
+            &mut stats,
+        );
+        // Clear pending allocation pages at the end of GC, no matter we used it or not.
+        self.pending_pages.store(0, Ordering::SeqCst);
+    }
+
+    fn is_heap_full(&self, plan: &dyn Plan<VM = VM>) -> bool {
+        // If reserved pages is larger than the current heap size, the heap is full.
+        plan.get_reserved_pages() > self.current_heap_pages.load(Ordering::Relaxed)
+    }
+
+    fn get_current_heap_size_in_pages(&self) -> usize {
+        self.current_heap_pages.load(Ordering::Relaxed)
+    }
+
+    fn get_max_heap_size_in_pages(&self) -> usize {
+        self.bounds.max()
+    }
+
+    fn can_heap_size_grow(&self) -> bool {
+        self.current_heap_pages.load(Ordering::Relaxed) < self.bounds.max()
+    }
+
+    fn set_heap_size_bounds(&self, min_pages: usize, max_pages: usize) -> bool {
+        self.bounds.set(min_pages, max_pages);
+        let current = self.current_heap_pages.load(Ordering::Relaxed);
+        self.current_heap_pages
+            .store(self.bounds.clamp(current), Ordering::Relaxed);
+        true
+    }
+}
+
+impl GCTimeRatioTrigger {
+    fn new(min_heap_pages: usize, max_heap_pages: usize, ratio: usize) -> Self {
+        let now = Instant::now();
+        Self {
+            bounds: HeapSizeBounds::new(min_heap_pages, max_heap_pages),
+            // start with min heap
+            current_heap_pages: AtomicUsize::new(min_heap_pages),
+            gc_time_goal: 1f64 / (1f64 + ratio as f64),
+            pending_pages: AtomicUsize::new(0),
+            stats: AtomicRefCell::new(GCTimeRatioStats {
+                gc_start_time: now,
+                gc_end_time: now,
+                gc_time_fraction: None,
+            }),
+        }
+    }
+
+    fn compute_new_heap_limit(
+        &self,
+        live: usize,
+        extra_reserve: usize,
+        gc_time: f64,
+        total_time: f64,
+        stats: &mut GCTimeRatioStats,
+    ) {
+        const SMOOTH_FACTOR: f64 = 0.5;
+        // Do not grow or shrink the free heap by more than this factor in one GC.
+        const MAX_RESIZE_FACTOR: f64 = 2.0;
+
+        if total_time <= 0f64 {
+            return;
+        }
+        let fraction = gc_time / total_time;
+        let fraction = stats
+            .gc_time_fraction
+            .map(|prev| prev * SMOOTH_FACTOR + fraction * (1f64 - SMOOTH_FACTOR))
+            .unwrap_or(fraction);
+        stats.gc_time_fraction = Some(fraction);
+
+        let current_heap = self.current_heap_pages.load(Ordering::Relaxed);
+        let free = current_heap.saturating_sub(live + extra_reserve).max(1);
+        let new_free = if fraction > self.gc_time_goal || fraction < self.gc_time_goal / 2f64 {
+            let target = self.gc_time_goal * 0.75;
+            // With a fixed GC time `g`, the mutator time is `g * (1 - f) / f` for a GC time
+            // fraction `f`. Scale the free heap with the mutator time.
+            let scale = if fraction > 0f64 {
+                (fraction * (1f64 - target)) / (target * (1f64 - fraction))
+            } else {
+                MAX_RESIZE_FACTOR.recip()
+            };
+            let scale = scale.clamp(MAX_RESIZE_FACTOR.recip(), MAX_RESIZE_FACTOR);
+            (free as f64 * scale) as usize
+        } else {
+            free
+        };
+
+        // Get pending allocations
+        let pending_pages = self.pending_pages.load(Ordering::SeqCst);
+
+        let heap = live + new_free + extra_reserve + pending_pages;
+        let new_heap = self.bounds.clamp(heap);
+        debug!(
+            "GCTimeRatio: GC time fraction = {:.4} (goal = {:.4}), new heap limit = {} pages (wanted = {}, clamped to [{}, {}])",
+            fraction, self.gc_time_goal, new_heap, heap, self.bounds.min(), self.bounds.max()
+        );
+        self.current_heap_pages.store(new_heap, Ordering::Relaxed);
+    }
+}
+
+/// A GC trigger for processes that run in containers. The heap size varies between the min heap and
+/// the max heap, and is further limited to what fits in the memory limit of the cgroup of the process
+/// alongside the memory that the process uses outside the heap.
+///
+/// * We read `memory.max` and `memory.current` of the cgroup at the end of each GC, and periodically
+///   when allocations poll the trigger. If the container nears its limit, the heap limit drops, and
+///   the next poll triggers a GC.
+/// * We read the memory pressure from PSI at the end of each GC. While the pressure is high, the heap
+///   shrinks towards the live memory. Otherwise, it grows back towards the max heap.
+///
+/// Files that cannot be read are ignored, so the trigger behaves like a fixed heap of the max heap
+/// size outside cgroups v2.
+pub struct MemoryPressureTrigger {
+    /// The min and max heap size
+    bounds: HeapSizeBounds,
+    /// The current heap size
+    current_heap_pages: AtomicUsize,
+    /// The number of pending allocation pages. The allocation requests for them have failed, and a GC is triggered.
+    pending_pages: AtomicUsize,
+    /// Where we read the memory limit, usage and pressure
+    source: MemoryPressureSource,
+    state: std::sync::Mutex<MemoryPressureState>,
+}
+
+struct MemoryPressureState {
+    /// When we last read the files
+    last_refresh: Instant,
+    /// The heap size we aim for regardless of the memory limit. It shrinks under memory pressure.
+    target_heap_pages: usize,
+    /// The heap size we do not go below: the live memory at the end of the last GC, and what we
+    /// reserve for the next GC.
+    floor_heap_pages: usize,
+}
+
+/// The files of a cgroup (v2) and PSI that [`MemoryPressureTrigger`] reads.
+pub(crate) struct MemoryPressureSource {
+    /// The cgroup directory that has `memory.max` and `memory.current`
+    pub(crate) cgroup_path: std::path::PathBuf,
+    /// The PSI file of memory, usually `/proc/pressure/memory`
+    pub(crate) psi_path: std::path::PathBuf,
+}
+
+/// What we read from a [`MemoryPressureSource`]. Each value is `None` if it is unavailable.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct MemoryPressureSample {
+    /// The memory limit of the cgroup in bytes
+    limit: Option<usize>,
+    /// The memory usage of the cgroup in bytes
+    current: Option<usize>,
+    /// The percentage of time in the last 10 seconds in which some tasks stalled on memory
+    pressure: Option<f64>,
+}
+
+impl MemoryPressureSource {
+    fn read(&self) -> MemoryPressureSample {
+        let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok();
+        MemoryPressureSample {
+            limit: read(self.cgroup_path.join("memory.max"))
+                .and_then(|s| Self::parse_memory_bytes(&s)),
+            current: read(self.cgroup_path.join("memory.current"))
+                .and_then(|s| Self::parse_memory_bytes(&s)),
+            pressure: read(self.psi_path.clone()).and_then(|s| Self::parse_psi(&s)),
+        }
+    }
+
+    /// Parse `memory.max` or `memory.current`. `memory.max` is `max` if there is no limit.
+    fn parse_memory_bytes(s: &str) -> Option<usize> {
+        s.trim().parse::<usize>().ok()
+    }
+
+    /// Parse the `avg10` value of the `some` line of a PSI file, which looks like
+    /// `some avg10=0.12 avg60=0.05 avg300=0.01 total=123456`.
+    fn parse_psi(s: &str) -> Option<f64> {
+        let line = s.lines().find(|line| line.starts_with("some "))?;
+        line.split_whitespace()
+            .find_map(|field| field.strip_prefix("avg10="))
+            .and_then(|v| v.parse::<f64>().ok())
+    }
+}
+
+impl<VM: VMBinding> GCTriggerPolicy<VM> for MemoryPressureTrigger {
+    fn is_gc_required(
+        &self,
+        space_full: bool,
+        space: Option<SpaceStats<VM>>,
+        plan: &dyn Plan<VM = VM>,
+    ) -> bool {
+        // Check the memory usage of the container once in a while. Skip it if another thread is
+        // checking.
+        if let Ok(mut state) = self.state.try_lock() {
+            if state.last_refresh.elapsed() >= Self::REFRESH_INTERVAL {
+                let sample = self.source.read();
+                self.refresh(&mut state, plan.get_reserved_pages(), sample);
+            }
+        }
+        // Let the plan decide
+        plan.collection_required(space_full, space)
+    }
+
+    fn on_pending_allocation(&self, pages: usize) {
+        self.pending_pages.fetch_add(pages, Ordering::SeqCst);
+    }
+
+    fn on_gc_end(&self, mmtk: &'static MMTK<VM>) {
+        let plan = mmtk.get_plan();
+        let mut extra_reserve = plan.get_collection_reserved_pages();
+        if plan.generational().is_some() {
+            // Leave room for the min nursery so that the next GC is not forced to be a full heap GC.
+            extra_reserve += mmtk.gc_trigger.get_min_nursery_pages();
+        }
+        let live = plan.get_reserved_pages();
+        let sample = self.source.read();
+        let mut state = self.state.lock().unwrap();
+        self.update_target(&mut state, live, extra_reserve, sample);
+        self.refresh(&mut state, live, sample);
+        // Clear pending allocation pages at the end of GC, no matter we used it or not.
+        self.pending_pages.store(0, Ordering::SeqCst);
+    }
+
+    fn is_heap_full(&self, plan: &dyn Plan<VM = VM>) -> bool {
+        // If reserved pages is larger than the current heap size, the heap is full.
+        plan.get_reserved_pages() > self.current_heap_pages.load(Ordering::Relaxed)
+    }
+
+    fn get_current_heap_size_in_pages(&self) -> usize {
+        self.current_heap_pages.load(Ordering::Relaxed)
+    }
+
+    fn get_max_heap_size_in_pages(&self) -> usize {
+        self.bounds.max()
+    }
+
+    fn can_heap_size_grow(&self) -> bool {
+        self.current_heap_pages.load(Ordering::Relaxed) < self.bounds.max()
+    }
+
+    fn set_heap_size_bounds(&self, min_pages: usize, max_pages: usize) -> bool {
+        let mut state = self.state.lock().unwrap();
+        self.bounds.set(min_pages, max_pages);
+        state.target_heap_pages = state.target_heap_pages.clamp(min_pages, max_pages);
+        // Do not wait for the next refresh to apply the new bounds.
+        let current = self.current_heap_pages.load(Ordering::Relaxed);
+        self.current_heap_pages
+            .store(self.bounds.clamp(current), Ordering::Relaxed);
+        true
+    }
+}
+
+impl MemoryPressureTrigger {
+    /// How often allocations read the memory usage of the container between GCs.
+    const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+    /// The fraction of the memory limit that we keep free for the memory outside the heap to grow.
+    const HEADROOM: f64 = 0.1;
+    /// The memory pressure (in percentage of stalled time) above which the heap shrinks.
+    const PRESSURE_THRESHOLD: f64 = 10.0;
+
+    fn new(min_heap_pages: usize, max_heap_pages: usize, source: MemoryPressureSource) -> Self {
+        let trigger = Self {
+            bounds: HeapSizeBounds::new(min_heap_pages, max_heap_pages),
+            current_heap_pages: AtomicUsize::new(max_heap_pages),
+            pending_pages: AtomicUsize::new(0),
+            source,
+            state: std::sync::Mutex::new(MemoryPressureState {
+                last_refresh: Instant::now(),
+                // start with max heap, and let the container limit it.
+                target_heap_pages: max_heap_pages,
+                floor_heap_pages: min_heap_pages,
+            }),
+        };
+        let sample = trigger.source.read();
+        if sample.limit.is_none() {
+            info!(
+                "MemoryPressure: no memory limit in {:?}. The heap is only limited by the max heap.",
+                trigger.source.cgroup_path
+            );
+        }
+        trigger.refresh(&mut trigger.state.lock().unwrap(), 0, sample);
+        trigger
+    }
+
+    /// Shrink the target heap size under memory pressure, or grow it back otherwise. This is
+    /// called at the end of a GC with the live pages.
+    fn update_target(
+        &self,
+        state: &mut MemoryPressureState,
+        live: usize,
+        extra_reserve: usize,
+        sample: MemoryPressureSample,
+    ) {
+        let pending_pages = self.pending_pages.load(Ordering::SeqCst);
+        state.floor_heap_pages = live + extra_reserve + pending_pages;
+        let max = self.bounds.max();
+        let floor = state.floor_heap_pages.min(max);
+        let target = state.target_heap_pages.clamp(floor, max);
+        state.target_heap_pages = match sample.pressure {
+            Some(pressure) if pressure > Self::PRESSURE_THRESHOLD => {
+                // Halve the free heap.
+                floor + (target - floor) / 2
+            }
+            _ => {
+                // Recover a quarter of the way to the max heap.
+                target + (max - target) / 4
+            }
+        };
+    }
+
+    /// Compute the heap limit from the memory limit and usage of the container.
+    fn refresh(
+        &self,
+        state: &mut MemoryPressureState,
+        reserved_pages: usize,
+        sample: MemoryPressureSample,
+    ) {
+        state.last_refresh = Instant::now();
+        let mut heap = state.target_heap_pages;
+        if let (Some(limit), Some(current)) = (sample.limit, sample.current) {
+            // The memory that the process and others in the container use outside the heap.
+            let other = current.saturating_sub(conversions::pages_to_bytes(reserved_pages));
+            let available = ((limit as f64) * (1f64 - Self::HEADROOM)) as usize;
+            let container_heap_pages = available.saturating_sub(other) >> LOG_BYTES_IN_PAGE;
+            heap = heap.min(container_heap_pages);
+        }
+        let new_heap = self.bounds.clamp(heap.max(state.floor_heap_pages));
+        trace!(
+            "MemoryPressure: {:?}, new heap limit = {} pages (target = {}, floor = {})",
+            sample,
+            new_heap,
+            state.target_heap_pages,
+            state.floor_heap_pages
         );
         self.current_heap_pages.store(new_heap, Ordering::Relaxed);
     }
 }
+
+/// Statistics about the heap that MMTk passes to a [`DelegatedGCTrigger`].
+// Like `SpaceStats`, this type exists so we do not need to expose the `Plan` trait to the bindings.
+pub struct HeapStats<'a, VM: VMBinding> {
+    plan: &'a dyn Plan<VM = VM>,
+    allocated_pages: usize,
+    pending_pages: usize,
+}
+
+impl<VM: VMBinding> HeapStats<'_, VM> {
+    /// Get the number of reserved pages in the heap, including the pages reserved for copying.
+    pub fn reserved_pages(&self) -> usize {
+        self.plan.get_reserved_pages()
+    }
+
+    /// Get the number of pages that are used by the spaces.
+    pub fn used_pages(&self) -> usize {
+        self.plan.get_used_pages()
+    }
+
+    /// Get the current heap size in pages.
+    pub fn total_pages(&self) -> usize {
+        self.plan.get_total_pages()
+    }
+
+    /// Get the number of pages reserved since the end of the last GC. This is zero before the first
+    /// GC ends if nothing has been allocated.
+    pub fn allocated_pages_since_last_gc(&self) -> usize {
+        self.allocated_pages
+    }
+
+    /// Get the number of pages that allocation requests failed to get since the end of the last
+    /// GC. Those requests will be retried after the next GC, so the heap needs room for them.
+    pub fn pending_pages(&self) -> usize {
+        self.pending_pages
+    }
+
+    /// Visit the statistics of each space.
+    pub fn for_each_space(&self, f: &mut dyn FnMut(SpaceStats<VM>)) {
+        self.plan
+            .for_each_space(&mut |space| f(SpaceStats::new(space)));
+    }
+}
+
+/// A GC trigger implemented by the binding, used if the option `gc_trigger` is set to
+/// [`crate::util::options::GCTriggerSelector::Delegated`]. The binding creates it in
+/// [`crate::vm::Collection::create_gc_trigger`].
+///
+/// MMTk keeps the heap size, and accounts for allocation and pending allocation. The binding is
+/// asked whether a GC is required each time an allocation polls the trigger, and is told when
+/// a GC starts and ends. The binding can change the heap size at any time with
+/// [`crate::memory_manager::set_heap_size`]. The plan may still trigger a GC if the heap is full
+/// or if it needs one for its own reasons.
+pub trait DelegatedGCTrigger<VM: VMBinding>: Sync + Send {
+    /// The heap size in bytes when MMTk starts.
+    fn initial_heap_size(&self) -> usize;
+    /// The max heap size in bytes. The heap size cannot be set larger than this unless the bounds
+    /// are changed by [`crate::memory_manager::set_heap_size_bounds`].
+    fn max_heap_size(&self) -> usize;
+    /// Is a GC required now? This is called each time an allocation polls the trigger, before the
+    /// plan is asked. Return true to trigger a GC.
+    ///
+    /// Arguments:
+    /// * `space_full`: Is any space full?
+    /// * `space`: The space that triggered the poll. This could be `None` if the poll is not
+    ///   triggered by a space.
+    /// * `heap`: The statistics of the heap.
+    fn is_gc_required(
+        &self,
+        _space_full: bool,
+        _space: Option<SpaceStats<VM>>,
+        _heap: &HeapStats<VM>,
+    ) -> bool {
+        false
+    }
+    /// Inform the binding that a GC starts.
+    fn on_gc_start(&self, _heap: &HeapStats<VM>) {}
+    /// Inform the binding that a GC ends, before mutators resume. The statistics show the heap
+    /// after the GC, and the allocation and pending allocation before it.
+    fn on_gc_end(&self, _heap: &HeapStats<VM>) {}
+}
+
+/// A GC trigger policy that delegates the decisions to a [`DelegatedGCTrigger`] from the binding.
+pub struct DelegatedTrigger<VM: VMBinding> {
+    /// The trigger from the binding
+    delegate: Box<dyn DelegatedGCTrigger<VM>>,
+    /// The min and max heap size. There is no min heap size unless it is set at run time.
+    bounds: HeapSizeBounds,
+    /// The current heap size
+    current_heap_pages: AtomicUsize,
+    /// The number of pending allocation pages. The allocation requests for them have failed, and a GC is triggered.
+    pending_pages: AtomicUsize,
+    /// The reserved pages at the end of the last GC
+    last_gc_reserved_pages: AtomicUsize,
+}
+
+impl<VM: VMBinding> DelegatedTrigger<VM> {
+    fn new(delegate: Box<dyn DelegatedGCTrigger<VM>>) -> Self {
+        let max_heap_pages = conversions::bytes_to_pages_up(delegate.max_heap_size());
+        let initial_heap_pages = conversions::bytes_to_pages_up(delegate.initial_heap_size());
+        assert!(
+            initial_heap_pages <= max_heap_pages,
+            "The initial heap size ({} pages) is larger than the max heap size ({} pages)",
+            initial_heap_pages,
+            max_heap_pages
+        );
+        Self {
+            delegate,
+            bounds: HeapSizeBounds::new(0, max_heap_pages),
+            current_heap_pages: AtomicUsize::new(initial_heap_pages),
+            pending_pages: AtomicUsize::new(0),
+            last_gc_reserved_pages: AtomicUsize::new(0),
+        }
+    }
+
+    fn heap_stats<'a>(&self, plan: &'a dyn Plan<VM = VM>) -> HeapStats<'a, VM> {
+        HeapStats {
+            plan,
+            allocated_pages: plan
+                .get_reserved_pages()
+                .saturating_sub(self.last_gc_reserved_pages.load(Ordering::Relaxed)),
+            pending_pages: self.pending_pages.load(Ordering::SeqCst),
+        }
+    }
+}
+
+impl<VM: VMBinding> GCTriggerPolicy<VM> for DelegatedTrigger<VM> {
+    fn is_gc_required(
+        &self,
+        space_full: bool,
+        space: Option<SpaceStats<VM>>,
+        plan: &dyn Plan<VM = VM>,
+    ) -> bool {
+        // Ask the binding first, and let the plan decide if the binding does not need a GC.
+        let space_stats = space.as_ref().map(|s| SpaceStats::new(s.0));
+        self.delegate
+            .is_gc_required(space_full, space_stats, &self.heap_stats(plan))
+            || plan.collection_required(space_full, space)
+    }
+
+    fn on_pending_allocation(&self, pages: usize) {
+        self.pending_pages.fetch_add(pages, Ordering::SeqCst);
+    }
+
+    fn on_gc_start(&self, mmtk: &'static MMTK<VM>) {
+        self.delegate.on_gc_start(&self.heap_stats(mmtk.get_plan()));
+    }
+
+    fn on_gc_end(&self, mmtk: &'static MMTK<VM>) {
+        let plan = mmtk.get_plan();
+        self.delegate.on_gc_end(&self.heap_stats(plan));
+        self.last_gc_reserved_pages
+            .store(plan.get_reserved_pages(), Ordering::Relaxed);
+        // Clear pending allocation pages at the end of GC, no matter we used it or not.
+        self.pending_pages.store(0, Ordering::SeqCst);
+    }
+
+    fn is_heap_full(&self, plan: &dyn Plan<VM = VM>) -> bool {
+        // If reserved pages is larger than the current heap size, the heap is full.
+        plan.get_reserved_pages() > self.current_heap_pages.load(Ordering::Relaxed)
+    }
+
+    fn get_current_heap_size_in_pages(&self) -> usize {
+        self.current_heap_pages.load(Ordering::Relaxed)
+    }
+
+    fn get_max_heap_size_in_pages(&self) -> usize {
+        self.bounds.max()
+    }
+
+    fn can_heap_size_grow(&self) -> bool {
+        self.current_heap_pages.load(Ordering::Relaxed) < self.bounds.max()
+    }
+
+    fn set_current_heap_size_in_pages(&self, pages: usize) -> bool {
+        if pages < self.bounds.min() || pages > self.bounds.max() {
+            return false;
+        }
+        self.current_heap_pages.store(pages, Ordering::Relaxed);
+        true
+    }
+
+    fn set_heap_size_bounds(&self, min_pages: usize, max_pages: usize) -> bool {
+        self.bounds.set(min_pages, max_pages);
+        let current = self.current_heap_pages.load(Ordering::Relaxed);
+        self.current_heap_pages
+            .store(self.bounds.clamp(current), Ordering::Relaxed);
+        true
+    }
+}
+
+/// Decides whether a GC should happen while mutators are idle, as reported by
+/// [`crate::memory_manager::notify_idle`]. It records how long GCs take, and how fast mutators
+/// allocate between GCs. A GC in idle time is worth it if the mutators have used a good part of the
+/// free heap since the last GC, or are expected to fill the heap soon at their allocation rate. It
+/// is only triggered if its first pause is expected to end before the deadline. For most plans, the
+/// first pause is the whole GC. A concurrent plan continues the GC with concurrent work (or
+/// incremental pauses) and a final pause after the deadline, like any GC it starts.
+pub(crate) struct IdleGCTrigger {
+    stats: std::sync::Mutex<IdleGCStats>,
+}
+
+struct IdleGCStats {
+    /// When the current or the last pause started
+    pause_start_time: Instant,
+    /// Is a GC in progress between pauses, i.e. did the last pause leave concurrent work?
+    gc_in_progress: bool,
+    /// When the last GC ended
+    gc_end_time: Instant,
+    /// The reserved pages at the end of the last GC
+    gc_end_reserved_pages: usize,
+    /// The heap size at the end of the last GC
+    gc_end_total_pages: usize,
+    /// The smoothed duration of the first pause of GCs. `None` before the first pause ends.
+    first_pause_time: Option<std::time::Duration>,
+}
+
+impl IdleGCTrigger {
+    /// Collect if this fraction of the free heap after the last GC has been used.
+    const MIN_PROGRESS: f64 = 0.5;
+    /// Collect if the heap is expected to be full within this time at the current allocation rate.
+    const HORIZON: std::time::Duration = std::time::Duration::from_secs(1);
+
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            stats: std::sync::Mutex::new(IdleGCStats {
+                pause_start_time: now,
+                gc_in_progress: false,
+                gc_end_time: now,
+                gc_end_reserved_pages: 0,
+                gc_end_total_pages: 0,
+                first_pause_time: None,
+            }),
+        }
+    }
+
+    pub(crate) fn on_gc_start(&self) {
+        self.stats.lock().unwrap().pause_start_time = Instant::now();
+    }
+
+    /// A GC pause ends. `gc_in_progress` is true if the GC continues after the pause, i.e. the
+    /// pause leaves concurrent work for a concurrent plan.
+    pub(crate) fn on_gc_end(
+        &self,
+        reserved_pages: usize,
+        total_pages: usize,
+        gc_in_progress: bool,
+    ) {
+        const SMOOTH_FACTOR: f64 = 0.5;
+        let mut stats = self.stats.lock().unwrap();
+        let now = Instant::now();
+        if !stats.gc_in_progress {
+            // This is the first pause of a GC.
+            let pause_time = now - stats.pause_start_time;
+            stats.first_pause_time = Some(
+                stats
+                    .first_pause_time
+                    .map(|prev| {
+                        prev.mul_f64(SMOOTH_FACTOR) + pause_time.mul_f64(1f64 - SMOOTH_FACTOR)
+                    })
+                    .unwrap_or(pause_time),
+            );
+        }
+        stats.gc_in_progress = gc_in_progress;
+        if !gc_in_progress {
+            stats.gc_end_time = now;
+            stats.gc_end_reserved_pages = reserved_pages;
+            stats.gc_end_total_pages = total_pages;
+        }
+    }
+
+    /// Should we collect now, if mutators are idle until the deadline?
+    fn should_collect(
+        &self,
+        now: Instant,
+        deadline: Instant,
+        reserved_pages: usize,
+        total_pages: usize,
+    ) -> bool {
+        let stats = self.stats.lock().unwrap();
+        // We cannot tell how long a pause takes before the first one.
+        let Some(pause_time) = stats.first_pause_time else {
+            return false;
+        };
+        if now + pause_time > deadline {
+            trace!(
+                "Idle GC: the first pause of a GC ({:?}) does not fit before the deadline",
+                pause_time
+            );
+            return false;
+        }
+
+        let allocated = reserved_pages.saturating_sub(stats.gc_end_reserved_pages);
+        let free_after_gc = stats
+            .gc_end_total_pages
+            .saturating_sub(stats.gc_end_reserved_pages);
+        let progress = allocated as f64 / free_after_gc.max(1) as f64;
+        let mutator_time = now
+            .saturating_duration_since(stats.gc_end_time)
+            .as_secs_f64();
+        let allocation_rate = if mutator_time > 0f64 {
+            allocated as f64 / mutator_time
+        } else {
+            0f64
+        };
+        let free = total_pages.saturating_sub(reserved_pages);
+        let time_to_full = free as f64 / allocation_rate;
+        trace!(
+            "Idle GC: progress = {:.2}, allocation rate = {:.1} pages/s, full in {:.3}s",
+            progress,
+            allocation_rate,
+            time_to_full
+        );
+        progress >= Self::MIN_PROGRESS || time_to_full <= Self::HORIZON.as_secs_f64()
+    }
+}
+
+/// The steps that MMTk takes when the heap usage exceeds the soft heap limit (the option
+/// `soft_heap_limit`). Each step is only taken if the usage is still above the limit after the
+/// previous step.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SoftHeapLimitStage {
+    /// A nursery GC. This step is skipped for non-generational plans.
+    NurseryGC,
+    /// A full heap GC.
+    FullHeapGC,
+    /// An emergency GC that collects as much as it can, including objects only reachable from soft
+    /// references.
+    ClearSoftReferences,
+    /// The allocation that exceeds the limit fails with [`crate::util::alloc::AllocationError::HeapOutOfMemory`].
+    OutOfMemory,
+}
+
+/// Tracks the steps taken for the soft heap limit. The next step is taken when a mutator polls
+/// with the heap usage above the limit and the GC of the current step has finished. The steps start
+/// again from the first one once the usage drops below the limit, or an out of memory error is
+/// thrown.
+pub(crate) struct SoftHeapLimit {
+    /// The soft heap limit in pages. 0 means there is no limit.
+    limit_pages: usize,
+    state: std::sync::Mutex<SoftHeapLimitState>,
+}
+
+struct SoftHeapLimitState {
+    /// The current step. `None` if the usage was below the limit at the end of the last GC.
+    stage: Option<SoftHeapLimitStage>,
+    /// Has the GC for the current step been requested and not finished yet?
+    gc_requested: bool,
+}
+
+impl SoftHeapLimit {
+    fn new(limit_pages: usize) -> Self {
+        Self {
+            limit_pages,
+            state: std::sync::Mutex::new(SoftHeapLimitState {
+                stage: None,
+                gc_requested: false,
+            }),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.limit_pages != 0
+    }
+
+    /// Move on to the next step if the usage is above the limit. Return the new step, or `None` if
+    /// there is no new step.
+    fn escalate(&self, used_pages: usize, generational: bool) -> Option<SoftHeapLimitStage> {
+        if used_pages <= self.limit_pages {
+            return None;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.gc_requested {
+            return None;
+        }
+        let next = match state.stage {
+            None if generational => SoftHeapLimitStage::NurseryGC,
+            None | Some(SoftHeapLimitStage::NurseryGC) => SoftHeapLimitStage::FullHeapGC,
+            Some(SoftHeapLimitStage::FullHeapGC) => SoftHeapLimitStage::ClearSoftReferences,
+            Some(SoftHeapLimitStage::ClearSoftReferences) => SoftHeapLimitStage::OutOfMemory,
+            // Wait until an allocation throws the error.
+            Some(SoftHeapLimitStage::OutOfMemory) => return None,
+        };
+        state.stage = Some(next);
+        state.gc_requested = next != SoftHeapLimitStage::OutOfMemory;
+        Some(next)
+    }
+
+    /// Should the current GC be an emergency collection to clear soft references?
+    pub(crate) fn is_clearing_soft_references(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.gc_requested && state.stage == Some(SoftHeapLimitStage::ClearSoftReferences)
+    }
+
+    /// Should allocations fail, as the usage is still above the limit after all the GCs?
+    pub(crate) fn is_out_of_memory(&self) -> bool {
+        self.is_enabled()
+            && self.state.lock().unwrap().stage == Some(SoftHeapLimitStage::OutOfMemory)
+    }
+
+    /// Return true if allocations should fail, and start again from the first step for later
+    /// allocations. The caller should throw an out of memory error.
+    pub(crate) fn take_out_of_memory(&self) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.stage == Some(SoftHeapLimitStage::OutOfMemory) {
+            state.stage = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn on_gc_end(&self, used_pages: usize) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.gc_requested = false;
+        if used_pages <= self.limit_pages {
+            state.stage = None;
+        }
+    }
+}
+
+/// Resizes an Adaptive nursery (see [`crate::util::options::NurserySize::Adaptive`]). Generational
+/// plans report the start and the end of each nursery GC. From those, it measures the survival
+/// ratio, i.e. the pages that survived the nursery GC over the pages allocated into the nursery, and
+/// the pause time of the GC.
+struct AdaptiveNursery {
+    /// The current nursery size in bytes
+    size: AtomicUsize,
+    stats: std::sync::Mutex<AdaptiveNurseryStats>,
+}
+
+struct AdaptiveNurseryStats {
+    /// When the current nursery GC started
+    gc_start_time: Instant,
+    /// The pages allocated into the nursery before the current nursery GC
+    nursery_pages: usize,
+    /// The pages used by the plan before the current nursery GC
+    used_pages: usize,
+    /// The smoothed survival ratio. `None` before the first nursery GC ends.
+    survival: Option<f64>,
+}
+
+impl AdaptiveNursery {
+    /// Grow the nursery if more than this fraction of the nursery survives.
+    const HIGH_SURVIVAL: f64 = 0.1;
+    /// Shrink the nursery if less than this fraction of the nursery survives.
+    const LOW_SURVIVAL: f64 = 0.01;
+    const GROW_FACTOR: f64 = 1.5;
+    const SHRINK_FACTOR: f64 = 0.75;
+
+    fn new(size: usize) -> Self {
+        Self {
+            size: AtomicUsize::new(size),
+            stats: std::sync::Mutex::new(AdaptiveNurseryStats {
+                gc_start_time: Instant::now(),
+                nursery_pages: 0,
+                used_pages: 0,
+                survival: None,
+            }),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.size.load(Ordering::Relaxed)
+    }
+
+    fn on_gc_start(&self, nursery_pages: usize, used_pages: usize) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.gc_start_time = Instant::now();
+        stats.nursery_pages = nursery_pages;
+        stats.used_pages = used_pages;
+    }
+
+    fn on_gc_end(
+        &self,
+        used_pages: usize,
+        pause_goal: std::time::Duration,
+        min: usize,
+        max: usize,
+    ) {
+        const SMOOTH_FACTOR: f64 = 0.5;
+        let mut stats = self.stats.lock().unwrap();
+        let pause = stats.gc_start_time.elapsed();
+        // Objects outside the nursery are not collected in a nursery GC, so the used pages only
+        // drop by the nursery pages that did not survive.
+        let mature_pages = stats.used_pages.saturating_sub(stats.nursery_pages);
+        let survived_pages = used_pages.saturating_sub(mature_pages);
+        let survival =
+            (survived_pages as f64 / stats.nursery_pages.max(1) as f64).clamp(0f64, 1f64);
+        let survival = stats
+            .survival
+            .map(|prev| prev * SMOOTH_FACTOR + survival * (1f64 - SMOOTH_FACTOR))
+            .unwrap_or(survival);
+        stats.survival = Some(survival);
+
+        let size = Self::next_size(self.size(), survival, pause, pause_goal, min, max);
+        debug!(
+            "Adaptive nursery: survival = {:.3}, pause = {:?}, size = {} -> {}",
+            survival,
+            pause,
+            self.size(),
+            size
+        );
+        self.size.store(size, Ordering::Relaxed);
+    }
+
+    /// Compute the nursery size for the next GC from the smoothed survival ratio and the pause
+    /// time of the last nursery GC.
+    fn next_size(
+        size: usize,
+        survival: f64,
+        pause: std::time::Duration,
+        pause_goal: std::time::Duration,
+        min: usize,
+        max: usize,
+    ) -> usize {
+        let pause_ratio = pause_goal.as_secs_f64() / pause.as_secs_f64();
+        let factor = if pause > pause_goal {
+            // The pause time is mostly spent on the survivors, whose number is roughly proportional
+            // to the nursery size. Shrink the nursery to meet the goal, but not too fast.
+            pause_ratio.max(0.5)
+        } else if survival > Self::HIGH_SURVIVAL {
+            // Give objects more time to die, as long as the longer pause still meets the goal.
+            Self::GROW_FACTOR.min(pause_ratio)
+        } else if survival < Self::LOW_SURVIVAL {
+            // Objects die well before the nursery is full. Leave the memory to the mature space.
+            Self::SHRINK_FACTOR
+        } else {
+            1f64
+        };
+        let size = conversions::raw_align_up((size as f64 * factor) as usize, BYTES_IN_PAGE);
+        size.max(min).min(max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mem_balancer_extra_pages() {
+        // sqrt(L * g / (c * s)) = sqrt(1000 * 400 / (0.1 * 100)) = 200
+        let e = MemBalancerTrigger::optimal_extra_pages(1000, 400f64, 100f64, 0.1).unwrap();
+        assert!((e - 200f64).abs() < 1e-9);
+        // A faster allocation rate or a slower collection speed gives a larger heap.
+        assert!(MemBalancerTrigger::optimal_extra_pages(1000, 800f64, 100f64, 0.1).unwrap() > e);
+        assert!(MemBalancerTrigger::optimal_extra_pages(1000, 400f64, 50f64, 0.1).unwrap() > e);
+        // Unusable rates.
+        assert!(MemBalancerTrigger::optimal_extra_pages(1000, 0f64, 100f64, 0.1).is_none());
+        assert!(MemBalancerTrigger::optimal_extra_pages(1000, 400f64, f64::NAN, 0.1).is_none());
+    }
+
+    #[test]
+    fn heap_size_bounds() {
+        let bounds = HeapSizeBounds::new(100, 1000);
+        assert_eq!(bounds.clamp(50), 100);
+        assert_eq!(bounds.clamp(500), 500);
+        assert_eq!(bounds.clamp(5000), 1000);
+        bounds.set(10, 200);
+        assert_eq!(bounds.clamp(5), 10);
+        assert_eq!(bounds.clamp(500), 200);
+        // A reader may see a new max with an old min while the bounds are being changed. It does
+        // not panic, and the max wins.
+        bounds.max_pages.store(5, Ordering::Relaxed);
+        assert_eq!(bounds.clamp(500), 5);
+    }
+
+    #[test]
+    fn idle_gc() {
+        use std::time::Duration;
+        let trigger = IdleGCTrigger::new();
+        let now = Instant::now();
+        let ms = Duration::from_millis;
+        // We do not know how long a GC takes before the first one.
+        assert!(!trigger.should_collect(now, now + ms(1000), 1000, 1100));
+
+        let set_last_gc = |gc_end_time| {
+            let mut stats = trigger.stats.lock().unwrap();
+            stats.first_pause_time = Some(ms(10));
+            stats.gc_end_time = gc_end_time;
+            stats.gc_end_reserved_pages = 100;
+            stats.gc_end_total_pages = 1100;
+        };
+        set_last_gc(now - ms(1000));
+        // More than half of the free heap is used, but a GC does not fit before the deadline.
+        assert!(!trigger.should_collect(now, now + ms(5), 700, 1100));
+        assert!(trigger.should_collect(now, now + ms(50), 700, 1100));
+        // 100 pages per second fills the remaining 900 pages in 9 seconds.
+        assert!(!trigger.should_collect(now, now + ms(50), 200, 1100));
+        // 2000 pages per second fills the remaining 800 pages in 0.4 seconds.
+        set_last_gc(now - ms(100));
+        assert!(trigger.should_collect(now, now + ms(50), 300, 1100));
+
+        // A concurrent GC: a short first pause leaves concurrent work, and a later pause finishes
+        // the GC. Only the first pause counts, and the heap usage is recorded when the GC ends.
+        let trigger = IdleGCTrigger::new();
+        trigger.on_gc_start();
+        trigger.on_gc_end(200, 1100, true);
+        std::thread::sleep(ms(20));
+        trigger.on_gc_start();
+        std::thread::sleep(ms(20));
+        trigger.on_gc_end(100, 1100, false);
+        let stats = trigger.stats.lock().unwrap();
+        assert!(stats.first_pause_time.unwrap() < ms(20));
+        assert_eq!(stats.gc_end_reserved_pages, 100);
+        drop(stats);
+        let now = Instant::now();
+        assert!(trigger.should_collect(now, now + ms(20), 700, 1100));
+    }
+
+    #[test]
+    fn adaptive_nursery_size() {
+        use std::time::Duration;
+        const MB: usize = 1 << 20;
+        let ms = Duration::from_millis;
+        let next_size = |survival, pause| {
+            AdaptiveNursery::next_size(8 * MB, survival, ms(pause), ms(10), MB, 16 * MB)
+        };
+        // Many objects survive, and the pause is short. Grow.
+        assert_eq!(next_size(0.5, 1), 12 * MB);
+        // The growth is limited by the pause goal.
+        assert_eq!(next_size(0.5, 8), 10 * MB);
+        // The pause is too long. Shrink, but at most by half.
+        assert_eq!(next_size(0.5, 16), 5 * MB);
+        assert_eq!(next_size(0.5, 100), 4 * MB);
+        // Almost nothing survives. Shrink.
+        assert_eq!(next_size(0.001, 1), 6 * MB);
+        // Otherwise, keep the size.
+        assert_eq!(next_size(0.05, 1), 8 * MB);
+        // The size stays within the bounds.
+        assert_eq!(
+            AdaptiveNursery::next_size(12 * MB, 0.5, ms(1), ms(10), MB, 16 * MB),
+            16 * MB
+        );
+        assert_eq!(
+            AdaptiveNursery::next_size(MB, 0.001, ms(1), ms(10), MB, 16 * MB),
+            MB
+        );
+
+        // The survival ratio is measured from the used pages before and after a nursery GC.
+        let nursery = AdaptiveNursery::new(8 * MB);
+        nursery.on_gc_start(100, 1000);
+        nursery.on_gc_end(950, Duration::from_secs(1000), MB, 16 * MB);
+        assert_eq!(nursery.stats.lock().unwrap().survival, Some(0.5));
+        assert_eq!(nursery.size(), 12 * MB);
+    }
+
+    #[test]
+    fn soft_heap_limit() {
+        use SoftHeapLimitStage::*;
+        let limit = SoftHeapLimit::new(100);
+        // Below the limit.
+        assert_eq!(limit.escalate(100, true), None);
+        // Each step waits for the GC of the previous step.
+        assert_eq!(limit.escalate(101, true), Some(NurseryGC));
+        assert_eq!(limit.escalate(101, true), None);
+        limit.on_gc_end(150);
+        assert_eq!(limit.escalate(150, true), Some(FullHeapGC));
+        limit.on_gc_end(150);
+        assert_eq!(limit.escalate(150, true), Some(ClearSoftReferences));
+        assert!(limit.is_clearing_soft_references());
+        limit.on_gc_end(150);
+        assert!(!limit.is_clearing_soft_references());
+        assert_eq!(limit.escalate(150, true), Some(OutOfMemory));
+        assert_eq!(limit.escalate(150, true), None);
+        assert!(limit.is_out_of_memory());
+        // The error is thrown once, and the steps start again.
+        assert!(limit.take_out_of_memory());
+        assert!(!limit.take_out_of_memory());
+        // Non-generational plans skip the nursery GC. A GC that brings the usage below the limit
+        // resets the steps.
+        assert_eq!(limit.escalate(150, false), Some(FullHeapGC));
+        limit.on_gc_end(50);
+        assert_eq!(limit.escalate(150, false), Some(FullHeapGC));
+    }
+
+    #[test]
+    fn mem_balancer_heap_limit() {
+        let trigger = MemBalancerTrigger::new(100, 100_000, 0.1);
+        let heap_limit = |allocation_pages, collection_time| {
+            trigger.access_stats(|stats| {
+                stats.allocation_pages = allocation_pages;
+                stats.allocation_time = 1f64;
+                stats.collection_pages = 100f64;
+                stats.collection_time = collection_time;
+                trigger.compute_new_heap_limit(1000, 0, stats);
+            });
+            trigger.current_heap_pages.load(Ordering::Relaxed)
+        };
+
+        // live 1000 + sqrt(1000 * 400 / (0.1 * 100)) = 1200
+        assert_eq!(heap_limit(400f64, 1f64), 1200);
+        // Later measurements are smoothed with the earlier ones, so the heap grows, but not as
+        // much as the unsmoothed rates would suggest.
+        let smoothed = heap_limit(400f64, 4f64);
+        assert!(smoothed > 1200 && smoothed < 1400);
+        // The smoothed collection time keeps growing towards the measured one.
+        assert!(heap_limit(400f64, 4f64) > smoothed);
+        // The limit is clamped to the max heap.
+        assert_eq!(heap_limit(1e12, 4f64), 100_000);
+    }
+    #[test]
+    fn gc_time_ratio_heap_limit() {
+        // Aim to spend at most 1 / (1 + 9) = 10% of the time in GC.
+        let trigger = GCTimeRatioTrigger::new(1000, 100_000, 9);
+        let heap_limit = |gc_time| {
+            let mut stats = trigger.stats.borrow_mut();
+            trigger.compute_new_heap_limit(500, 0, gc_time, 1f64, &mut stats);
+            trigger.current_heap_pages.load(Ordering::Relaxed)
+        };
+
+        // GC takes 40% of the time. The free heap can at most double in one GC.
+        assert_eq!(heap_limit(0.4), 500 + 500 * 2);
+        // Once the smoothed fraction is within the goal, the heap size is stable.
+        for _ in 0..10 {
+            heap_limit(0.075);
+        }
+        let stable = heap_limit(0.075);
+        assert!(stable > 1500);
+        assert_eq!(heap_limit(0.075), stable);
+        // GC takes much less time than the goal. The heap shrinks, but not below the min heap.
+        assert!(heap_limit(0.001) < stable);
+        for _ in 0..20 {
+            heap_limit(0.001);
+        }
+        assert_eq!(heap_limit(0.001), 1000);
+        // The heap does not grow beyond the max heap.
+        for _ in 0..20 {
+            heap_limit(0.9);
+        }
+        assert_eq!(heap_limit(0.9), 100_000);
+    }
+    /// Fake cgroup and PSI files in a temporary directory.
+    struct FakeMemoryFiles(std::path::PathBuf);
+
+    impl FakeMemoryFiles {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("mmtk-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn source(&self) -> MemoryPressureSource {
+            MemoryPressureSource {
+                cgroup_path: self.0.clone(),
+                psi_path: self.0.join("pressure"),
+            }
+        }
+
+        fn write(&self, limit: &str, current: usize, pressure: f64) {
+            std::fs::write(self.0.join("memory.max"), format!("{}\n", limit)).unwrap();
+            std::fs::write(self.0.join("memory.current"), format!("{}\n", current)).unwrap();
+            std::fs::write(
+                self.0.join("pressure"),
+                format!(
+                    "some avg10={:.2} avg60=0.00 avg300=0.00 total=0\nfull avg10=0.00 avg60=0.00 avg300=0.00 total=0\n",
+                    pressure
+                ),
+            )
+            .unwrap();
+        }
+    }
+
+    impl Drop for FakeMemoryFiles {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn memory_pressure_parse() {
+        assert_eq!(
+            MemoryPressureSource::parse_memory_bytes("1048576\n"),
+            Some(1 << 20)
+        );
+        assert_eq!(MemoryPressureSource::parse_memory_bytes("max\n"), None);
+        assert_eq!(
+            MemoryPressureSource::parse_psi(
+                "some avg10=12.50 avg60=3.00 avg300=1.00 total=42\nfull avg10=1.00 avg60=0.00 avg300=0.00 total=7\n"
+            ),
+            Some(12.5)
+        );
+        assert_eq!(MemoryPressureSource::parse_psi(""), None);
+
+        // Missing files
+        let source = MemoryPressureSource {
+            cgroup_path: "/nonexistent/cgroup".into(),
+            psi_path: "/nonexistent/pressure".into(),
+        };
+        assert_eq!(source.read(), MemoryPressureSample::default());
+    }
+
+    #[test]
+    fn memory_pressure_heap_limit() {
+        const MB_PAGES: usize = (1 << 20) >> LOG_BYTES_IN_PAGE;
+        let files = FakeMemoryFiles::new("memory-pressure-heap-limit");
+        let heap_limit = |trigger: &MemoryPressureTrigger| {
+            trigger.current_heap_pages.load(Ordering::Relaxed) / MB_PAGES
+        };
+        let gc_end = |trigger: &MemoryPressureTrigger, live: usize| {
+            let sample = trigger.source.read();
+            let mut state = trigger.state.lock().unwrap();
+            trigger.update_target(&mut state, live * MB_PAGES, 0, sample);
+            trigger.refresh(&mut state, live * MB_PAGES, sample);
+        };
+
+        // Without a limit, the heap starts at the max heap.
+        files.write("max", 50 << 20, 0.0);
+        let trigger = MemoryPressureTrigger::new(4 * MB_PAGES, 1024 * MB_PAGES, files.source());
+        assert_eq!(heap_limit(&trigger), 1024);
+
+        // The container uses 50MB outside the heap, and 90% of its 100MB limit is available.
+        files.write(&(100 << 20).to_string(), 50 << 20, 0.0);
+        gc_end(&trigger, 0);
+        assert_eq!(heap_limit(&trigger), 40);
+        // The heap uses 30MB of the container's 80MB.
+        files.write(&(100 << 20).to_string(), 80 << 20, 0.0);
+        gc_end(&trigger, 30);
+        assert_eq!(heap_limit(&trigger), 40);
+        // The heap does not go below the live memory.
+        files.write(&(100 << 20).to_string(), 120 << 20, 0.0);
+        gc_end(&trigger, 30);
+        assert_eq!(heap_limit(&trigger), 30);
+
+        // Memory pressure shrinks the heap towards the live memory, even without a limit.
+        let trigger = MemoryPressureTrigger::new(4 * MB_PAGES, 1024 * MB_PAGES, files.source());
+        files.write("max", 0, 50.0);
+        gc_end(&trigger, 100);
+        assert_eq!(heap_limit(&trigger), 100 + (1024 - 100) / 2);
+        gc_end(&trigger, 100);
+        assert_eq!(heap_limit(&trigger), 100 + (1024 - 100) / 4);
+        // Without pressure, the heap grows back.
+        files.write("max", 0, 0.0);
+        gc_end(&trigger, 100);
+        assert!(heap_limit(&trigger) > 100 + (1024 - 100) / 4);
+    }
+}
@@ -10,6 +10,7 @@ use crate::util::alloc::embedded_meta_data::*;
 use crate::util::heap::layout::vm_layout::LOG_BYTES_IN_CHUNK;
 use crate::util::heap::pageresource::CommonPageResource;
 use crate::util::opaque_pointer::*;
+use crate::util::options::DecommitPolicy;
 
 use super::layout::VMMap;
 use super::pageresource::{PRAllocFail, PRAllocResult};
@@ -31,6 +32,8 @@ struct MonotonePageResourceSync {
     sentinel: Address,
     /** Base address of the current chunk of addresses */
     current_chunk: Address,
+    /** The highest address the cursor has reached since we last decommitted free pages. */
+    highwater: Address,
     conditional: MonotonePageResourceConditional,
 }
 
@@ -57,6 +60,24 @@ impl<VM: VMBinding> PageResource<VM> for MonotonePageResource<VM> {
         pages
     }
 
+    fn decommit_free_pages(&self, policy: DecommitPolicy) -> usize {
+        // A discontiguous space returns its free chunks to the VM map when it releases pages, and
+        // the chunks are no longer ours.
+        if !self.common.contiguous {
+            return 0;
+        }
+        let mut sync = self.sync.lock().unwrap();
+        // Pages between the cursor and the highwater mark have been used, and are now free.
+        let start = sync.cursor.align_up(BYTES_IN_PAGE);
+        let end = sync.highwater;
+        sync.highwater = start;
+        if start < end {
+            self.common.decommit(start, end - start, policy)
+        } else {
+            0
+        }
+    }
+
     fn get_available_physical_pages(&self) -> usize {
         let sync = self.sync.lock().unwrap();
         let mut rtn = bytes_to_pages_up(sync.sentinel - sync.cursor);
@@ -137,6 +158,9 @@ impl<VM: VMBinding> PageResource<VM> for MonotonePageResource<VM> {
             //debug!("tmp={:?} <= sync.sentinel={:?}", tmp, sync.sentinel);
             sync.cursor = tmp;
             debug!("update cursor = {}", tmp);
+            if tmp > sync.highwater {
+                sync.highwater = tmp;
+            }
 
             /* In a contiguous space we can bump along into the next chunk, so preserve the currentChunk invariant */
             if self.common().contiguous && chunk_align_down(sync.cursor) != sync.current_chunk {
@@ -168,6 +192,7 @@ impl<VM: VMBinding> MonotonePageResource<VM> {
             sync: Mutex::new(MonotonePageResourceSync {
                 cursor: start,
                 current_chunk: chunk_align_down(start),
+                highwater: start,
                 sentinel,
                 conditional: MonotonePageResourceConditional::Contiguous {
                     start,
@@ -185,6 +210,7 @@ impl<VM: VMBinding> MonotonePageResource<VM> {
             sync: Mutex::new(MonotonePageResourceSync {
                 cursor: unsafe { Address::zero() },
                 current_chunk: unsafe { Address::zero() },
+                highwater: unsafe { Address::zero() },
                 sentinel: unsafe { Address::zero() },
                 conditional: MonotonePageResourceConditional::Discontiguous,
             }),
@@ -1,10 +1,15 @@
+use crate::mmtk::MMAPPER;
 use crate::util::address::Address;
 use crate::util::conversions;
 use crate::util::freelist::FreeList;
+use crate::util::memory;
 use crate::util::opaque_pointer::*;
+use crate::util::options::DecommitPolicy;
 use crate::vm::ActivePlan;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
 
+use super::layout::vm_layout::BYTES_IN_CHUNK;
 use super::layout::VMMap;
 use crate::util::heap::space_descriptor::SpaceDescriptor;
 use crate::util::heap::PageAccounting;
@@ -108,6 +113,13 @@ pub trait PageResource<VM: VMBinding>: 'static {
         self.common().vm_map
     }
 
+    /// Return the free memory of this page resource to the OS as the policy specifies, and return
+    /// the number of pages returned. The memory stays mapped, and can be allocated again. This is
+    /// called at the end of a GC, before mutators resume. By default, this does nothing.
+    fn decommit_free_pages(&self, _policy: DecommitPolicy) -> usize {
+        0
+    }
+
     // Some page resources need to record the start address.
     // This method will be called after the start address of the discontigous region is determined.
     // `start` is the computed start address.  By default, this does nothing.
@@ -131,6 +143,8 @@ pub struct CommonPageResource {
 
     pub vm_map: &'static dyn VMMap,
     head_discontiguous_region: Mutex<Address>,
+    /// The number of pages that this page resource has returned to the OS in total.
+    decommitted: AtomicUsize,
 }
 
 impl CommonPageResource {
@@ -143,7 +157,41 @@ impl CommonPageResource {
             vm_map,
 
             head_discontiguous_region: Mutex::new(Address::ZERO),
+            decommitted: AtomicUsize::new(0),
+        }
+    }
+
+    /// Return the free memory in the given range to the OS as the policy specifies, and return the
+    /// number of pages returned. Chunks in the range that are not mapped are skipped.
+    pub fn decommit(&self, start: Address, bytes: usize, policy: DecommitPolicy) -> usize {
+        debug_assert!(conversions::is_page_aligned(start));
+        debug_assert!(conversions::is_page_aligned(Address::ZERO + bytes));
+        let end = start + bytes;
+        let mut cursor = start;
+        let mut pages = 0;
+        while cursor < end {
+            let next = (conversions::chunk_align_down(cursor) + BYTES_IN_CHUNK).min(end);
+            if MMAPPER.is_mapped_address(cursor) {
+                let size = next - cursor;
+                let result = match policy {
+                    DecommitPolicy::Never => Ok(()),
+                    DecommitPolicy::DontNeed => memory::madvise_dontneed(cursor, size),
+                    DecommitPolicy::Free => memory::madvise_free(cursor, size),
+                };
+                match result {
+                    Ok(()) => pages += conversions::bytes_to_pages_up(size),
+                    Err(e) => warn!("Failed to decommit {} bytes at {}: {}", size, cursor, e),
+                }
+            }
+            cursor = next;
         }
+        self.decommitted.fetch_add(pages, Ordering::Relaxed);
+        pages
+    }
+
+    /// Get the number of pages that this page resource has returned to the OS in total.
+    pub fn get_decommitted_pages(&self) -> usize {
+        self.decommitted.load(Ordering::Relaxed)
     }
 
     /// Extend the virtual memory associated with a particular discontiguous
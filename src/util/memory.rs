@@ -302,6 +302,53 @@ pub fn munmap(start: Address, size: usize) -> Result<()> {
     wrap_libc_call(&|| unsafe { libc::munmap(start.to_mut_ptr(), size) }, 0)
 }
 
+/// Return the physical memory of the given range (in page granularity) to the OS with
+/// `madvise(MADV_DONTNEED)`. The range stays mapped, and reads as zero when it is accessed again.
+pub fn madvise_dontneed(start: Address, size: usize) -> Result<()> {
+    wrap_libc_call(
+        &|| unsafe { libc::madvise(start.to_mut_ptr(), size, libc::MADV_DONTNEED) },
+        0,
+    )
+}
+
+/// Let the OS reclaim the physical memory of the given range (in page granularity) lazily with
+/// `madvise(MADV_FREE)`. The range stays mapped. When it is accessed again, it reads as either zero
+/// or its old content, depending on whether the OS has reclaimed the memory.
+pub fn madvise_free(start: Address, size: usize) -> Result<()> {
+    wrap_libc_call(
+        &|| unsafe { libc::madvise(start.to_mut_ptr(), size, libc::MADV_FREE) },
+        0,
+    )
+}
+
+/// Prefer memory on the given NUMA node for the given range (in page granularity) with
+/// `mbind(MPOL_PREFERRED)`. Pages of the range that are already in memory are moved to the node if
+/// possible.
+#[cfg(target_os = "linux")]
+pub fn bind_to_numa_node(start: Address, size: usize, node: usize) -> Result<()> {
+    // From <linux/mempolicy.h>
+    const MPOL_MF_MOVE: libc::c_uint = 1 << 1;
+    const BITS_PER_LONG: usize = libc::c_ulong::BITS as usize;
+
+    let mut nodemask = vec![0 as libc::c_ulong; node / BITS_PER_LONG + 1];
+    nodemask[node / BITS_PER_LONG] |= 1 << (node % BITS_PER_LONG);
+    wrap_libc_call(
+        &|| unsafe {
+            libc::syscall(
+                libc::SYS_mbind,
+                start.to_mut_ptr::<libc::c_void>(),
+                size,
+                libc::MPOL_PREFERRED,
+                nodemask.as_ptr(),
+                // The kernel reads one bit fewer than `maxnode`.
+                nodemask.len() * BITS_PER_LONG + 1,
+                MPOL_MF_MOVE,
+            )
+        },
+        0,
+    )
+}
+
 /// Properly handle errors from a mmap Result, including invoking the binding code in the case of
 /// an OOM error.
 pub fn handle_mmap_error<VM: VMBinding>(
@@ -524,6 +571,94 @@ mod tests {
         })
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_madvise_dontneed() {
+        serial_test(|| {
+            with_cleanup(
+                || {
+                    let res = dzmmap_noreplace(
+                        START,
+                        BYTES_IN_PAGE,
+                        MmapStrategy::TEST,
+                        mmap_anno_test!(),
+                    );
+                    assert!(res.is_ok());
+                    unsafe { START.store(42usize) };
+                    assert!(madvise_dontneed(START, BYTES_IN_PAGE).is_ok());
+                    // The memory is still mapped, and reads as zero.
+                    assert_eq!(unsafe { START.load::<usize>() }, 0);
+                    // The memory is usable again.
+                    unsafe { START.store(42usize) };
+                    assert_eq!(unsafe { START.load::<usize>() }, 42);
+                },
+                || {
+                    assert!(munmap(START, BYTES_IN_PAGE).is_ok());
+                },
+            );
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_madvise_free() {
+        serial_test(|| {
+            with_cleanup(
+                || {
+                    let res = dzmmap_noreplace(
+                        START,
+                        BYTES_IN_PAGE,
+                        MmapStrategy::TEST,
+                        mmap_anno_test!(),
+                    );
+                    assert!(res.is_ok());
+                    unsafe { START.store(42usize) };
+                    assert!(madvise_free(START, BYTES_IN_PAGE).is_ok());
+                    // The memory reads as either its old content or zero.
+                    let value = unsafe { START.load::<usize>() };
+                    assert!(value == 42 || value == 0);
+                },
+                || {
+                    assert!(munmap(START, BYTES_IN_PAGE).is_ok());
+                },
+            );
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_bind_to_numa_node() {
+        serial_test(|| {
+            with_cleanup(
+                || {
+                    let res = dzmmap_noreplace(
+                        START,
+                        BYTES_IN_PAGE,
+                        MmapStrategy::TEST,
+                        mmap_anno_test!(),
+                    );
+                    assert!(res.is_ok());
+                    unsafe { START.store(42usize) };
+                    // Node 0 exists unless the kernel does not support NUMA, or `mbind` is not
+                    // permitted in the sandbox (e.g. by seccomp in containers).
+                    if let Err(e) = bind_to_numa_node(START, BYTES_IN_PAGE, 0) {
+                        let errno = e.raw_os_error();
+                        assert!(
+                            errno == Some(libc::ENOSYS) || errno == Some(libc::EPERM),
+                            "Unexpected error: {}",
+                            e
+                        );
+                    }
+                    // The content is not changed.
+                    assert_eq!(unsafe { START.load::<usize>() }, 42);
+                },
+                || {
+                    assert!(munmap(START, BYTES_IN_PAGE).is_ok());
+                },
+            );
+        });
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn test_mmap_noreplace() {
@@ -11,6 +11,11 @@ impl VMGlobalLogBitSpec {
         self.store_atomic::<VM, u8>(object, 1, None, order)
     }
 
+    /// Mark the log bit as logged (0 means logged)
+    pub fn mark_as_logged<VM: VMBinding>(&self, object: ObjectReference, order: Ordering) {
+        self.store_atomic::<VM, u8>(object, 0, None, order)
+    }
+
     /// Mark the entire byte as unlogged if the log bit is in the side metadata. As it marks the entire byte,
     /// it may unlog adjacent objects. This method should only be used
     /// when adjacent objects are also in the mature space, and there is no harm if we also unlog them.
@@ -60,6 +60,10 @@ define_side_metadata_specs!(
     MS_ACTIVE_CHUNK = (global: true, log_num_of_bits: 3, log_bytes_in_region: LOG_BYTES_IN_CHUNK),
     // Track the index in SFT map for a chunk (only used for SFT sparse chunk map)
     SFT_DENSE_CHUNK_MAP_INDEX   = (global: true, log_num_of_bits: 3, log_bytes_in_region: LOG_BYTES_IN_CHUNK),
+    // Reference counts of objects (only used by RCImmix)
+    RC_COUNT = (global: true, log_num_of_bits: 1, log_bytes_in_region: LOG_MIN_OBJECT_SIZE as usize),
+    // Unlog bits of the fields of objects, one for every 4 bytes so that compressed slots have their own bits (only used by RCImmix)
+    FIELD_UNLOG_BIT = (global: true, log_num_of_bits: 0, log_bytes_in_region: LOG_BYTES_IN_INT as usize),
 );
 
 // This defines all LOCAL side metadata used by mmtk-core.
@@ -96,6 +100,14 @@ define_side_metadata_specs!(
     MS_LOCAL_FREE   = (global: false, log_num_of_bits: LOG_BITS_IN_ADDRESS, log_bytes_in_region: crate::policy::marksweepspace::native_ms::Block::LOG_BYTES),
     // First cell of thread free list in block for native mimalloc
     MS_THREAD_FREE  = (global: false, log_num_of_bits: LOG_BITS_IN_ADDRESS, log_bytes_in_region: crate::policy::marksweepspace::native_ms::Block::LOG_BYTES),
+    // Record the state of regions in the region space
+    RS_REGION_STATE = (global: false, log_num_of_bits: 3, log_bytes_in_region: crate::policy::region::HeapRegion::LOG_BYTES),
+    // Record live bytes of regions in the region space
+    RS_REGION_LIVE_BYTES = (global: false, log_num_of_bits: LOG_BITS_IN_ADDRESS, log_bytes_in_region: crate::policy::region::HeapRegion::LOG_BYTES),
+    // Record where the first live object of each forwarding block is moved to by mark compact
+    MC_FORWARDING_TABLE = (global: false, log_num_of_bits: LOG_BITS_IN_ADDRESS, log_bytes_in_region: crate::policy::markcompactspace::LOG_BYTES_IN_FORWARDING_BLOCK),
+    // Mark objects whose fields have been forwarded by mark compact
+    MC_FORWARDED_BIT = (global: false, log_num_of_bits: 0, log_bytes_in_region: LOG_MIN_OBJECT_SIZE as usize),
 );
 
 #[cfg(test)]
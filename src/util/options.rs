@@ -1,4 +1,4 @@
-use crate::scheduler::affinity::{get_total_num_cpus, CoreId};
+use crate::scheduler::affinity::{get_numa_nodes, get_total_num_cpus, CoreId};
 use crate::util::constants::LOG_BYTES_IN_MBYTE;
 use crate::util::Address;
 use std::default::Default;
@@ -24,6 +24,19 @@ pub enum NurseryZeroingOptions {
     Adaptive,
 }
 
+/// How MMTk returns free memory in its spaces to the OS after a GC that shrinks the heap.
+#[derive(Copy, Clone, EnumString, Debug, PartialEq, Eq)]
+pub enum DecommitPolicy {
+    /// Keep free memory committed.
+    Never,
+    /// Return free memory with `madvise(MADV_DONTNEED)`. The resident memory drops immediately,
+    /// and the memory is zeroed by the OS when it is used again.
+    DontNeed,
+    /// Return free memory with `madvise(MADV_FREE)`. The OS reclaims the memory lazily when it is
+    /// short of memory, which is cheaper if the memory is used again soon.
+    Free,
+}
+
 /// Select a GC plan for MMTk.
 #[derive(Copy, Clone, EnumString, Debug, PartialEq, Eq)]
 pub enum PlanSelector {
@@ -37,6 +50,8 @@ pub enum PlanSelector {
     GenCopy,
     /// A generational collector that uses a copying nursery, and Immix as its mature space.
     GenImmix,
+    /// A generational collector that uses a copying nursery, and mark-sweep as its mature space.
+    GenMarkSweep,
     /// A mark-sweep collector, which marks live objects and sweeps dead objects during GC.
     MarkSweep,
     /// A debugging collector that allocates memory at page granularity, and protects pages for dead objects
@@ -48,6 +63,24 @@ pub enum PlanSelector {
     MarkCompact,
     /// An Immix collector that uses a sticky mark bit to allow generational behaviors without a copying nursery.
     StickyImmix,
+    /// A mark-sweep collector that uses a sticky mark bit to allow generational behaviors without a copying nursery.
+    StickyMarkSweep,
+    /// An Immix collector that marks objects concurrently with mutators, using a
+    /// snapshot-at-the-beginning (SATB) barrier.
+    ConcurrentImmix,
+    /// A G1-style region-based collector that evacuates a collection set of regions in each GC,
+    /// and sizes the collection set to meet a pause time goal.
+    Regional,
+    /// An Immix collector that reclaims objects with deferred and coalesced reference counting,
+    /// and uses occasional backup traces to collect cyclic garbage. Objects in the large object
+    /// space are not reference counted, so they are only reclaimed by backup traces, unless they
+    /// die before the first GC after their allocation.
+    RCImmix,
+    /// A semispace collector that switches to Immix at runtime once the live data grows past
+    /// `semi_immix_threshold`, and never switches back. Mutators switch from a bump pointer
+    /// allocator to an Immix allocator for the default semantics at the switch, so a binding
+    /// should not cache the allocator selector of a mutator across GCs with this plan.
+    SemiImmix,
 }
 
 /// MMTk option for perf events
@@ -241,6 +274,8 @@ macro_rules! options {
             }
 
             /// Set an option and run its validator for its value.
+            // Parsing a `String` option never fails.
+            #[allow(irrefutable_let_patterns)]
             fn set_inner(&mut self, s: &str, val: &str) -> bool {
                 match s {
                     // Parse the given value from str (by env vars or by calling process()) to the right type
@@ -313,6 +348,21 @@ pub enum AffinityKind {
     /// core.
     // XXX: Maybe using a u128 bitvector with each bit representing a core is more performant?
     RoundRobin(Vec<CoreId>),
+    /// Assign threads to NUMA nodes in a round robin fashion, and bind each thread to all the cores
+    /// of its node. Threads prefer stealing work packets from threads on the same node, and copy
+    /// objects into memory on their own node. The nodes are discovered from
+    /// `/sys/devices/system/node` when the option is parsed, and only include the nodes with cores
+    /// allocated to the program.
+    Numa(Vec<NumaNode>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A NUMA node with cores allocated to the program.
+pub struct NumaNode {
+    /// The node id, as in `/sys/devices/system/node/node<id>`.
+    pub id: usize,
+    /// The cores on the node.
+    pub cores: Vec<CoreId>,
 }
 
 impl AffinityKind {
@@ -322,12 +372,22 @@ impl AffinityKind {
     /// should be used for pinning threads. Performs de-duplication of specified cores. Note that
     /// the core list is sorted as a side-effect whenever a new core is added to the set.
     fn parse_cpulist(cpulist: &str) -> Result<AffinityKind, String> {
-        let mut cpuset = vec![];
-
         if cpulist.is_empty() {
             return Ok(AffinityKind::OsDefault);
         }
 
+        if cpulist == "Numa" {
+            return get_numa_nodes().map(AffinityKind::Numa);
+        }
+
+        Self::parse_core_list(cpulist).map(AffinityKind::RoundRobin)
+    }
+
+    /// Parse a non-empty list of cores in the format described in `parse_cpulist`. This is also the
+    /// format of the `cpulist` files in `/sys/devices/system/node`.
+    pub(crate) fn parse_core_list(cpulist: &str) -> Result<Vec<CoreId>, String> {
+        let mut cpuset = vec![];
+
         // Split on ',' first and then split on '-' if there is a range
         for split in cpulist.split(',') {
             if !split.contains('-') {
@@ -367,7 +427,7 @@ impl AffinityKind {
             return Err("Core ids have been incorrectly specified".to_string());
         }
 
-        Ok(AffinityKind::RoundRobin(cpuset))
+        Ok(cpuset)
     }
 
     /// Return true if the affinity is either OsDefault or the cores in the list do not exceed the
@@ -376,15 +436,13 @@ impl AffinityKind {
     pub fn validate(&self) -> bool {
         let num_cpu = get_total_num_cpus();
 
-        if let AffinityKind::RoundRobin(cpuset) = self {
-            for cpu in cpuset {
-                if cpu >= &num_cpu {
-                    return false;
-                }
+        match self {
+            AffinityKind::OsDefault => true,
+            AffinityKind::RoundRobin(cpuset) => cpuset.iter().all(|cpu| *cpu < num_cpu),
+            AffinityKind::Numa(nodes) => {
+                !nodes.is_empty() && nodes.iter().all(|node| !node.cores.is_empty())
             }
         }
-
-        true
     }
 }
 
@@ -419,6 +477,18 @@ pub enum NurserySize {
     /// lower bounds. Note that this is considered less performant than a Bounded nursery since a
     /// Fixed nursery size can be too restrictive and cause more GCs.
     Fixed(usize),
+    /// An Adaptive nursery starts at the lower bound, and is resized after each nursery GC between
+    /// the bounds based on the survival ratio and the pause time of nursery GCs. It grows when many
+    /// objects survive and the pause time is within [`Options::pause_time_goal`], so objects have more
+    /// time to die, and shrinks when the pause time exceeds the goal, or when almost no objects
+    /// survive. The size adapts in GenCopy, GenImmix and StickyImmix. Other plans keep the lower
+    /// bound.
+    Adaptive {
+        /// The lower bound of the nursery size in bytes. Default to [`DEFAULT_MIN_NURSERY`].
+        min: usize,
+        /// The upper bound of the nursery size in bytes. Default to [`DEFAULT_MAX_NURSERY`].
+        max: usize,
+    },
 }
 
 impl NurserySize {
@@ -430,6 +500,7 @@ impl NurserySize {
                 0.0f64 < min && min <= max && max <= 1.0f64
             }
             NurserySize::Fixed(_) => true,
+            NurserySize::Adaptive { min, max } => 0 < min && min <= max,
         }
     }
 }
@@ -484,6 +555,15 @@ impl FromStr for NurserySize {
                     Err("Fixed requires one value".to_string())
                 }
             }
+            "Adaptive" => {
+                if values.len() == 2 {
+                    let min = default_or_parse(values[0], DEFAULT_MIN_NURSERY)?;
+                    let max = default_or_parse(values[1], DEFAULT_MAX_NURSERY)?;
+                    Ok(NurserySize::Adaptive { min, max })
+                } else {
+                    Err("Adaptive requires two values".to_string())
+                }
+            }
             _ => Err("Unknown variant".to_string()),
         }
     }
@@ -572,6 +652,35 @@ mod nursery_size_parsing_tests {
             panic!("Failed: {:?}", result);
         }
     }
+
+    #[test]
+    fn test_adaptive() {
+        // Simple case
+        let result = "Adaptive:4096,8192".parse::<NurserySize>().unwrap();
+        if let NurserySize::Adaptive { min, max } = result {
+            assert_eq!(min, 4096);
+            assert_eq!(max, 8192);
+        } else {
+            panic!("Failed: {:?}", result);
+        }
+
+        // Default both
+        let result = "Adaptive:_,_".parse::<NurserySize>().unwrap();
+        if let NurserySize::Adaptive { min, max } = result {
+            assert_eq!(min, DEFAULT_MIN_NURSERY);
+            assert_eq!(max, DEFAULT_MAX_NURSERY);
+        } else {
+            panic!("Failed: {:?}", result);
+        }
+
+        // Invalid
+        assert!("Adaptive:4096".parse::<NurserySize>().is_err());
+        assert!(!"Adaptive:8192,4096"
+            .parse::<NurserySize>()
+            .unwrap()
+            .validate());
+        assert!(!"Adaptive:0,_".parse::<NurserySize>().unwrap().validate());
+    }
 }
 
 /// Select a GC trigger for MMTk.
@@ -582,7 +691,20 @@ pub enum GCTriggerSelector {
     /// GC is triggered by internal herusticis, and the heap size is varying between the two given values.
     /// The two values are the lower and the upper bound of the heap size.
     DynamicHeapSize(usize, usize),
-    /// Delegate the GC triggering to the binding. This is not supported at the moment.
+    /// GC is triggered by internal heuristics, and the heap size is varying between the first two given
+    /// values, so that the time spent in GC is at most `1 / (1 + ratio)` of the total time, where
+    /// `ratio` is the third value. This is similar to the `GCTimeRatio` option of HotSpot. For
+    /// example, 'GCTimeRatio:64m,1g,19' aims to spend at most 5% of the time in GC with a heap
+    /// between 64MB and 1GB.
+    GCTimeRatio(usize, usize, usize),
+    /// GC is triggered by internal heuristics, and the heap size is varying between the two given values.
+    /// The heap is also limited to what fits in the memory limit of the cgroup (v2) of the process,
+    /// and shrinks when the memory pressure reported by Linux PSI is high. The files are read from
+    /// the options `cgroup_memory_path` and `memory_pressure_path`. For example,
+    /// 'MemoryPressure:64m,4g' sizes the heap between 64MB and 4GB.
+    MemoryPressure(usize, usize),
+    /// Delegate the GC triggering to the binding. The binding provides a
+    /// [`crate::util::heap::DelegatedGCTrigger`] in [`crate::vm::Collection::create_gc_trigger`].
     Delegated,
 }
 
@@ -597,6 +719,8 @@ impl GCTriggerSelector {
         match self {
             Self::FixedHeapSize(s) => *s,
             Self::DynamicHeapSize(_, s) => *s,
+            Self::GCTimeRatio(_, s, _) => *s,
+            Self::MemoryPressure(_, s) => *s,
             _ => unreachable!("Cannot get max heap size"),
         }
     }
@@ -641,6 +765,8 @@ impl GCTriggerSelector {
         match self {
             Self::FixedHeapSize(size) => *size > 0,
             Self::DynamicHeapSize(min, max) => min <= max,
+            Self::GCTimeRatio(min, max, ratio) => min <= max && *ratio > 0,
+            Self::MemoryPressure(min, max) => min <= max,
             Self::Delegated => true,
         }
     }
@@ -657,6 +783,13 @@ impl FromStr for GCTriggerSelector {
             static ref DYNAMIC_HEAP_REGEX: Regex =
                 Regex::new(r"^DynamicHeapSize:(?P<min>\d+[kKmMgGtT]?),(?P<max>\d+[kKmMgGtT]?)$")
                     .unwrap();
+            static ref GC_TIME_RATIO_REGEX: Regex = Regex::new(
+                r"^GCTimeRatio:(?P<min>\d+[kKmMgGtT]?),(?P<max>\d+[kKmMgGtT]?),(?P<ratio>\d+)$"
+            )
+            .unwrap();
+            static ref MEMORY_PRESSURE_REGEX: Regex =
+                Regex::new(r"^MemoryPressure:(?P<min>\d+[kKmMgGtT]?),(?P<max>\d+[kKmMgGtT]?)$")
+                    .unwrap();
         }
 
         if s.is_empty() {
@@ -669,6 +802,17 @@ impl FromStr for GCTriggerSelector {
             let min = Self::parse_size(&captures["min"])?;
             let max = Self::parse_size(&captures["max"])?;
             return Ok(Self::DynamicHeapSize(min, max));
+        } else if let Some(captures) = GC_TIME_RATIO_REGEX.captures(s) {
+            let min = Self::parse_size(&captures["min"])?;
+            let max = Self::parse_size(&captures["max"])?;
+            let ratio = captures["ratio"]
+                .parse::<usize>()
+                .map_err(|e| e.to_string())?;
+            return Ok(Self::GCTimeRatio(min, max, ratio));
+        } else if let Some(captures) = MEMORY_PRESSURE_REGEX.captures(s) {
+            let min = Self::parse_size(&captures["min"])?;
+            let max = Self::parse_size(&captures["max"])?;
+            return Ok(Self::MemoryPressure(min, max));
         } else if s.starts_with("Delegated") {
             return Ok(Self::Delegated);
         }
@@ -772,6 +916,40 @@ mod gc_trigger_tests {
         assert!(GCTriggerSelector::from_str("DynamicHeapSize:1024,1024,").is_err());
     }
 
+    #[test]
+    fn test_parse_gc_time_ratio() {
+        assert_eq!(
+            GCTriggerSelector::from_str("GCTimeRatio:1024,2048,19"),
+            Ok(GCTriggerSelector::GCTimeRatio(1024, 2048, 19))
+        );
+        assert_eq!(
+            GCTriggerSelector::from_str("GCTimeRatio:1m,2m,99"),
+            Ok(GCTriggerSelector::GCTimeRatio(
+                1024 * 1024,
+                2 * 1024 * 1024,
+                99
+            ))
+        );
+
+        // incorrect
+        assert!(GCTriggerSelector::from_str("GCTimeRatio:1024,2048").is_err());
+        assert!(GCTriggerSelector::from_str("GCTimeRatio:1024,2048,1k").is_err());
+    }
+
+    #[test]
+    fn test_parse_memory_pressure() {
+        assert_eq!(
+            GCTriggerSelector::from_str("MemoryPressure:1m,2g"),
+            Ok(GCTriggerSelector::MemoryPressure(
+                1024 * 1024,
+                2 * 1024 * 1024 * 1024
+            ))
+        );
+
+        // incorrect
+        assert!(GCTriggerSelector::from_str("MemoryPressure:1m").is_err());
+    }
+
     #[test]
     fn test_validate() {
         assert!(GCTriggerSelector::FixedHeapSize(1024).validate());
@@ -780,6 +958,228 @@ mod gc_trigger_tests {
 
         assert!(!GCTriggerSelector::FixedHeapSize(0).validate());
         assert!(!GCTriggerSelector::DynamicHeapSize(2048, 1024).validate());
+        assert!(GCTriggerSelector::GCTimeRatio(1024, 2048, 19).validate());
+        assert!(!GCTriggerSelector::GCTimeRatio(2048, 1024, 19).validate());
+        assert!(!GCTriggerSelector::GCTimeRatio(1024, 2048, 0).validate());
+        assert!(GCTriggerSelector::MemoryPressure(1024, 2048).validate());
+        assert!(!GCTriggerSelector::MemoryPressure(2048, 1024).validate());
+    }
+}
+
+/// How long an incremental pause may last.
+///
+/// Plans that do part of their work concurrently with mutators (such as ConcurrentImmix) can do
+/// that work incrementally instead.  In that case, GC workers only execute concurrent work packets
+/// in short stop-the-world pauses, and each pause ends when its budget runs out.  Between two
+/// incremental pauses, mutators run for at least as long as the previous pause took.  Other plans
+/// ignore this option.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PauseBudget {
+    /// Do concurrent work concurrently with mutators.  Pauses are not bounded.
+    Unbounded,
+    /// Each incremental pause stops executing work packets after the given number of
+    /// microseconds.
+    Time(u64),
+    /// Each incremental pause executes at most the given number of work packets.
+    WorkPackets(usize),
+}
+
+impl PauseBudget {
+    /// Return true if concurrent work is done in incremental pauses.
+    pub fn is_incremental(&self) -> bool {
+        !matches!(self, Self::Unbounded)
+    }
+
+    /// Return true if the budget is valid.
+    fn validate(&self) -> bool {
+        match *self {
+            Self::Unbounded => true,
+            Self::Time(micros) => micros > 0,
+            Self::WorkPackets(packets) => packets > 0,
+        }
+    }
+}
+
+impl FromStr for PauseBudget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "Unbounded" {
+            return Ok(Self::Unbounded);
+        }
+
+        let Some((variant, value)) = s.split_once(':') else {
+            return Err(format!("Failed to parse the pause budget: {:?}", s));
+        };
+        match variant {
+            "Time" => value.parse::<u64>().map(Self::Time),
+            "WorkPackets" => value.parse::<usize>().map(Self::WorkPackets),
+            _ => return Err(format!("Unknown pause budget: {:?}", variant)),
+        }
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod pause_budget_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            PauseBudget::from_str("Unbounded"),
+            Ok(PauseBudget::Unbounded)
+        );
+        assert_eq!(
+            PauseBudget::from_str("Time:2000"),
+            Ok(PauseBudget::Time(2000))
+        );
+        assert_eq!(
+            PauseBudget::from_str("WorkPackets:64"),
+            Ok(PauseBudget::WorkPackets(64))
+        );
+
+        // incorrect
+        assert!(PauseBudget::from_str("").is_err());
+        assert!(PauseBudget::from_str("Time").is_err());
+        assert!(PauseBudget::from_str("Time:").is_err());
+        assert!(PauseBudget::from_str("Time:-1").is_err());
+        assert!(PauseBudget::from_str("Bytes:1024").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(PauseBudget::Unbounded.validate());
+        assert!(PauseBudget::Time(1).validate());
+        assert!(PauseBudget::WorkPackets(1).validate());
+
+        assert!(!PauseBudget::Time(0).validate());
+        assert!(!PauseBudget::WorkPackets(0).validate());
+    }
+}
+
+/// What MMTk does when an allocation makes a space exceed its budget. See [`SpaceBudgets`].
+#[derive(Copy, Clone, EnumString, Debug, PartialEq, Eq)]
+pub enum SpaceBudgetAction {
+    /// Trigger a GC. If the space still exceeds its budget after the GC, allocations into the
+    /// space fail with [`crate::util::alloc::AllocationError::SpaceBudgetExceeded`] until a later
+    /// GC brings the space under its budget.
+    GC,
+    /// Fail the allocation with [`crate::util::alloc::AllocationError::SpaceBudgetExceeded`].
+    Error,
+}
+
+/// The budget of one space.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpaceBudget {
+    /// The name of the space, such as 'los', 'immortal' or 'nonmoving'.
+    pub space: String,
+    /// The maximum number of bytes that the space can use, including its side metadata.
+    pub bytes: usize,
+    /// What to do when the space exceeds the budget.
+    pub action: SpaceBudgetAction,
+}
+
+/// Caps on the memory used by individual spaces, in addition to the heap size. Each budget is
+/// written as 'name:size' or 'name:size:action', where the size is in bytes or with a suffix of
+/// K, M or G, and the action is one of [`SpaceBudgetAction`] (default to 'GC'). Budgets are
+/// separated by commas. For example, 'los:64m,immortal:16m:Error'.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpaceBudgets(pub Vec<SpaceBudget>);
+
+impl SpaceBudgets {
+    /// Get the budget of the space with the given name.
+    pub fn get(&self, space: &str) -> Option<&SpaceBudget> {
+        self.0.iter().find(|budget| budget.space == space)
+    }
+
+    /// Return true if the budgets are valid.
+    fn validate(&self) -> bool {
+        self.0.iter().enumerate().all(|(i, budget)| {
+            budget.bytes > 0 && self.0[..i].iter().all(|b| b.space != budget.space)
+        })
+    }
+}
+
+impl FromStr for SpaceBudgets {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+        s.split(',')
+            .map(|budget| {
+                let parts: Vec<&str> = budget.split(':').collect();
+                let (space, size, action) = match parts[..] {
+                    [space, size] => (space, size, SpaceBudgetAction::GC),
+                    [space, size, action] => (
+                        space,
+                        size,
+                        SpaceBudgetAction::from_str(action)
+                            .map_err(|_| format!("Unknown space budget action: {:?}", action))?,
+                    ),
+                    _ => return Err(format!("Failed to parse the space budget: {:?}", budget)),
+                };
+                if space.is_empty() {
+                    return Err(format!("No space name in the space budget: {:?}", budget));
+                }
+                Ok(SpaceBudget {
+                    space: space.to_string(),
+                    bytes: GCTriggerSelector::parse_size(size)?,
+                    action,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+}
+
+#[cfg(test)]
+mod space_budget_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(SpaceBudgets::from_str(""), Ok(SpaceBudgets::default()));
+        let budgets = SpaceBudgets::from_str("los:64m,immortal:4096:Error").unwrap();
+        assert_eq!(
+            budgets.get("los"),
+            Some(&SpaceBudget {
+                space: "los".to_string(),
+                bytes: 64 * 1024 * 1024,
+                action: SpaceBudgetAction::GC,
+            })
+        );
+        assert_eq!(
+            budgets.get("immortal"),
+            Some(&SpaceBudget {
+                space: "immortal".to_string(),
+                bytes: 4096,
+                action: SpaceBudgetAction::Error,
+            })
+        );
+        assert_eq!(budgets.get("nonmoving"), None);
+
+        // incorrect
+        assert!(SpaceBudgets::from_str("los").is_err());
+        assert!(SpaceBudgets::from_str(":64m").is_err());
+        assert!(SpaceBudgets::from_str("los:64x").is_err());
+        assert!(SpaceBudgets::from_str("los:64m:Panic").is_err());
+        assert!(SpaceBudgets::from_str("los:64m,").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(SpaceBudgets::default().validate());
+        assert!(SpaceBudgets::from_str("los:64m,immortal:16m")
+            .unwrap()
+            .validate());
+
+        assert!(!SpaceBudgets::from_str("los:0").unwrap().validate());
+        assert!(!SpaceBudgets::from_str("los:64m,los:16m:Error")
+            .unwrap()
+            .validate());
     }
 }
 
@@ -790,6 +1190,13 @@ options! {
     plan:                  PlanSelector         [env_var: true, command_line: true] [always_valid] = PlanSelector::GenImmix,
     /// Number of GC worker threads.
     threads:               usize                [env_var: true, command_line: true] [|v: &usize| *v > 0]    = num_cpus::get(),
+    /// The expected bytes of GC work for each GC worker thread. If it is not zero, each GC only
+    /// wakes up enough GC threads for the work it is expected to do, and no more than the CPUs
+    /// available to the process. The other GC threads stay parked. A generational plan expects the
+    /// work of a nursery GC to be the nursery size. Other plans expect the bytes used after the
+    /// last GC. Plans with concurrent work always use all GC threads. 0 means all GC threads take
+    /// part in every GC.
+    work_per_gc_thread:    usize                [env_var: true, command_line: true]  [always_valid] = 0,
     /// Enable an optimization that only scans the part of the stack that has changed since the last GC (not supported)
     use_short_stack_scans: bool                 [env_var: true, command_line: true]  [always_valid] = false,
     /// Enable a return barrier (not supported)
@@ -798,14 +1205,22 @@ options! {
     eager_complete_sweep:  bool                 [env_var: true, command_line: true]  [always_valid] = false,
     /// Should we ignore GCs requested by the user (e.g. java.lang.System.gc)?
     ignore_system_gc:      bool                 [env_var: true, command_line: true]  [always_valid] = false,
-    /// The nursery size for generational plans. It can be one of Bounded, ProportionalBounded or Fixed.
+    /// The nursery size for generational plans. It can be one of Bounded, ProportionalBounded, Fixed or Adaptive.
     /// The nursery size can be set like 'Fixed:8192', for example,
     /// to have a Fixed nursery size of 8192 bytes, or 'ProportionalBounded:0.2,1.0' to have a nursery size
     /// between 20% and 100% of the heap size. You can omit lower bound and upper bound to use the default
     /// value for bounded nursery by using '_'. For example, 'ProportionalBounded:0.1,_' sets the min nursery
-    /// to 10% of the heap size while using the default value for max nursery.
+    /// to 10% of the heap size while using the default value for max nursery. 'Adaptive:_,67108864' resizes the
+    /// nursery between the default min nursery and 64MB based on how many objects survive nursery GCs.
     nursery:               NurserySize          [env_var: true, command_line: true]  [|v: &NurserySize| v.validate()]
         = NurserySize::ProportionalBounded { min: DEFAULT_PROPORTIONAL_MIN_NURSERY, max: DEFAULT_PROPORTIONAL_MAX_NURSERY },
+    /// The pause time goal in milliseconds. The Regional plan chooses how many old regions to
+    /// evacuate in a GC so that the predicted pause time stays within this goal. An Adaptive nursery
+    /// shrinks if nursery GCs take longer than this goal.
+    pause_time_goal:       usize                [env_var: true, command_line: true]  [|v: &usize| *v > 0] = 200,
+    /// The live bytes after a copying GC above which the SemiImmix plan switches from semispace
+    /// copying to Immix.
+    semi_immix_threshold:  usize                [env_var: true, command_line: true]  [|v: &usize| *v > 0] = 64 << 20,
     /// Should a major GC be performed when a system GC is required?
     full_heap_system_gc:   bool                 [env_var: true, command_line: true]  [always_valid] = false,
     /// Should finalization be disabled?
@@ -857,16 +1272,56 @@ options! {
     /// be used to pin threads even though we specified the core ids "0,1,2,3,4".
     /// `MMTK_THREAD_AFFINITY="12" taskset -c 6-12 <program>` will not work, on the other hand, as
     /// there is no core with (perceived) id 12.
+    /// 'Numa' binds each thread to the cores of one NUMA node, and spreads threads over the nodes in
+    /// a round-robin fashion (see [`AffinityKind::Numa`]).
     // XXX: This option is currently only supported on Linux.
     thread_affinity:        AffinityKind         [env_var: true, command_line: true] [|v: &AffinityKind| v.validate()] = AffinityKind::OsDefault,
     /// Set the GC trigger. This defines the heap size and how MMTk triggers a GC.
     /// Default to a fixed heap size of 0.5x physical memory.
     gc_trigger:             GCTriggerSelector    [env_var: true, command_line: true] [|v: &GCTriggerSelector| v.validate()] = GCTriggerSelector::FixedHeapSize((crate::util::memory::get_system_total_memory() as f64 * 0.5f64) as usize),
+    /// The tuning factor of MemBalancer, which the 'DynamicHeapSize' GC trigger uses to size the heap.
+    /// The heap limit is the live memory plus an extra heap that is inversely proportional to the
+    /// square root of this factor.  A smaller factor gives a larger heap and less time in GC.
+    mem_balancer_tuning_factor: f64             [env_var: true, command_line: true] [|v: &f64| v.is_finite() && *v > 0f64] = 0.2,
+    /// The cgroup (v2) directory whose 'memory.max' and 'memory.current' the 'MemoryPressure' GC
+    /// trigger reads. In a container, this is usually the cgroup of the container.
+    cgroup_memory_path:     String               [env_var: true, command_line: true] [always_valid] = "/sys/fs/cgroup".to_string(),
+    /// The PSI (pressure stall information) file of memory that the 'MemoryPressure' GC trigger reads.
+    memory_pressure_path:   String               [env_var: true, command_line: true] [always_valid] = "/proc/pressure/memory".to_string(),
+    /// How to return free memory in spaces to the OS. Free memory is only returned after a GC that
+    /// lowers the heap size, which only happens with GC triggers that vary the heap size.
+    decommit_policy:        DecommitPolicy       [env_var: true, command_line: true] [always_valid] = DecommitPolicy::Never,
+    /// Allow GCs when the binding reports idle time with `memory_manager::notify_idle`. Without
+    /// this, `notify_idle` does nothing.
+    idle_gc:                bool                 [env_var: true, command_line: true] [always_valid] = false,
+    /// A soft limit of the heap usage in bytes. 0 means no soft limit. When the used heap exceeds
+    /// the limit, MMTk escalates step by step until the usage is below the limit again: a nursery GC
+    /// (for generational plans), a full heap GC, a GC that clears soft references, and finally an
+    /// out of memory error. The binding is told about each step in `Collection::soft_heap_limit_exceeded`.
+    soft_heap_limit:        usize                [env_var: true, command_line: true] [always_valid] = 0,
+    /// Caps on the memory used by individual spaces, keyed by the space names, such as
+    /// 'los:64m,immortal:16m:Error'. Exceeding a cap either triggers a GC or fails the allocation.
+    /// See [`SpaceBudgets`] for the format.
+    space_budgets:          SpaceBudgets         [env_var: true, command_line: true] [|v: &SpaceBudgets| v.validate()] = SpaceBudgets::default(),
     /// Enable transparent hugepage support for MMTk spaces via madvise (only Linux is supported)
     /// This only affects the memory for MMTk spaces.
     transparent_hugepages: bool                  [env_var: true, command_line: true]  [|v: &bool| !v || cfg!(target_os = "linux")] = false,
     /// Count live bytes for objects in each space during a GC.
-    count_live_bytes_in_gc: bool                 [env_var: true, command_line: true] [always_valid] = false
+    count_live_bytes_in_gc: bool                 [env_var: true, command_line: true] [always_valid] = false,
+    /// The file into which the timeline of recent GC events is written in the Chrome trace event
+    /// format at `harness_end`. An empty string disables writing the file.
+    // TODO: Ideally this option should only be included when the feature 'timeline' is enabled. The current macro does not allow us to do this.
+    timeline_file:          String               [env_var: true, command_line: true] [|v: &String| v.is_empty() || cfg!(feature = "timeline")] = String::new(),
+    /// The number of the most recent GC events that the timeline keeps. The ring buffer of events
+    /// takes about 56 bytes per event, and is only allocated when the feature 'timeline' is enabled.
+    timeline_capacity:      usize                [env_var: true, command_line: true] [|v: &usize| *v > 0] = 1 << 14,
+    /// The budget of incremental pauses.  If it is not 'Unbounded', plans that support concurrent
+    /// work do it in stop-the-world pauses bounded by this budget instead of concurrently with
+    /// mutators.  It can be set like 'Time:2000' for pauses of 2000 microseconds, or
+    /// 'WorkPackets:64' for pauses that execute 64 work packets.  Only ConcurrentImmix does
+    /// concurrent work (marking) at the moment, and only its incremental pauses are bounded.  Its
+    /// initial-mark and final-mark pauses, and the pauses of other plans, are not bounded.
+    incremental_pause_budget: PauseBudget        [env_var: true, command_line: true] [|v: &PauseBudget| v.validate()] = PauseBudget::Unbounded
 }
 
 #[cfg(test)]
@@ -1140,6 +1595,22 @@ mod tests {
         })
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_thread_affinity_numa() {
+        serial_test(|| {
+            // Every Linux system has at least one node if it exposes the NUMA topology.
+            if std::path::Path::new("/sys/devices/system/node/node0").exists() {
+                let Ok(AffinityKind::Numa(nodes)) = "Numa".parse::<AffinityKind>() else {
+                    panic!("Failed to discover NUMA nodes");
+                };
+                assert!(AffinityKind::Numa(nodes.clone()).validate());
+                let cores = nodes.iter().map(|node| node.cores.len()).sum::<usize>();
+                assert_eq!(cores, get_total_num_cpus() as usize);
+            }
+        })
+    }
+
     #[test]
     fn test_thread_affinity_complex_core_list() {
         serial_test(|| {
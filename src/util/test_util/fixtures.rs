@@ -280,3 +280,39 @@ impl FixtureContent for TwoObjects {
         }
     }
 }
+
+/// A MockVM whose `block_for_gc` panics with "block_for_gc is called".  A test can check that a GC
+/// is triggered with `#[should_panic(expected = "block_for_gc is called")]`.  Other methods can be
+/// mocked with `MockVM { ..., ..mock_vm_panicking_in_block_for_gc() }`.
+pub fn mock_vm_panicking_in_block_for_gc() -> MockVM {
+    MockVM {
+        block_for_gc: crate::util::test_util::mock_method::MockMethod::new_fixed(Box::new(|_| {
+            panic!("block_for_gc is called")
+        })),
+        ..MockVM::default()
+    }
+}
+
+/// Allocate `bytes` in objects of 1KB with the default semantics.  All the allocations must
+/// succeed.
+pub fn alloc_bytes(mutator: &mut Mutator<MockVM>, bytes: usize) {
+    let allocated = try_alloc_bytes(mutator, bytes, 1024, AllocationSemantics::Default);
+    assert_eq!(allocated, bytes);
+}
+
+/// Allocate `bytes` in objects of `object_size`.  Return the number of bytes allocated before an
+/// allocation fails.
+pub fn try_alloc_bytes(
+    mutator: &mut Mutator<MockVM>,
+    bytes: usize,
+    object_size: usize,
+    semantics: AllocationSemantics,
+) -> usize {
+    for i in 0..bytes / object_size {
+        let addr = memory_manager::alloc(mutator, object_size, 8, 0, semantics);
+        if addr.is_zero() {
+            return i * object_size;
+        }
+    }
+    bytes
+}
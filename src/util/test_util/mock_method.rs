@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::sync::Arc;
 
 /// `MockAny` hides any type information. It is useful when we want to create
 /// a mock method for methods with generic type parameters.
@@ -37,22 +38,26 @@ pub enum MockImpl<I, R> {
 /// The function pointer for the mock closure.
 pub type MockClosureSignature<I, R> = Box<dyn Fn(I) -> R + Send + Sync>;
 
+/// A shared reference to a mock closure, which can be called after the lock of the MockVM is
+/// released.  See [`MockMethod::prepare_call`].
+pub type SharedMockClosure<I, R> = Arc<dyn Fn(I) -> R + Send + Sync>;
+
 /// The function pointer for the closure, and some metadata.
 pub struct MockClosure<I, R> {
-    closure: MockClosureSignature<I, R>,
+    closure: SharedMockClosure<I, R>,
     call_count: usize,
 }
 
 impl<I, R> MockClosure<I, R> {
     fn new(closure: MockClosureSignature<I, R>) -> Self {
         Self {
-            closure,
+            closure: closure.into(),
             call_count: 0,
         }
     }
-    fn call(&mut self, args: I) -> R {
+    fn prepare_call(&mut self) -> SharedMockClosure<I, R> {
         self.call_count += 1;
-        (self.closure)(args)
+        self.closure.clone()
     }
 }
 
@@ -96,14 +101,21 @@ impl<I, R> MockMethod<I, R> {
 
     /// Call the mock method.
     pub fn call(&mut self, args: I) -> R {
+        (self.prepare_call())(args)
+    }
+
+    /// Count a call of the mock method, and return the closure to call.  Unlike [`Self::call`],
+    /// the caller does not need to hold `self` while calling the closure, so the closure may call
+    /// other mock methods of the MockVM.
+    pub fn prepare_call(&mut self) -> SharedMockClosure<I, R> {
         let cur_call = self.call_count();
 
         match &mut self.imp {
             MockImpl::Sequence(closures) => {
                 let len = closures.len();
-                closures[cur_call % len].call(args)
+                closures[cur_call % len].prepare_call()
             }
-            MockImpl::Fixed(closure) => closure.call(args),
+            MockImpl::Fixed(closure) => closure.prepare_call(),
         }
     }
 
@@ -170,4 +182,18 @@ mod tests {
         assert_eq!(mock.call(()), 1);
         assert_eq!(mock.call_count(), 4);
     }
+
+    #[test]
+    fn mock_prepare_call() {
+        let mut mock = MockMethod::new_sequence(vec![
+            Box::new(|a: usize| -> usize { a }),
+            Box::new(|a: usize| -> usize { a * 2 }),
+        ]);
+        let first = mock.prepare_call();
+        let second = mock.prepare_call();
+        assert_eq!(mock.call_count(), 2);
+        assert_eq!(second(3), 6);
+        assert_eq!(first(3), 3);
+        assert_eq!(mock.call_count(), 2);
+    }
 }
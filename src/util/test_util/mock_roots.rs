@@ -0,0 +1,268 @@
+//! A MockVM setup for mock tests that run real GCs of a plan.
+//!
+//! The mocked VM has one mutator and [`NUM_ROOTS`] root slots.  Every object has a header word
+//! followed by [`NUM_FIELDS`] reference fields, and objects can be copied and slid.  The setup
+//! counts the pauses, so tests of concurrent plans can use it as well.  Tests that use
+//! this setup usually live with the plans instead of `crate::vm::tests::mock_tests`, because the
+//! mocked root scanning needs to name the work contexts of a plan, which are private to the plan.
+//!
+//! A test calls [`with_mockvm`] with [`setup`] (or a function that overrides some methods of
+//! [`setup`]), and calls [`create_mmtk`] to create the MMTk instance and the mutator.
+
+// Not all the tests that use this module are enabled with every set of features.
+#![allow(dead_code)]
+
+use std::any::Any;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use crate::memory_manager;
+use crate::plan::Mutator;
+use crate::scheduler::gc_work::ProcessEdgesWorkRootsWorkFactory;
+use crate::scheduler::GCWorkContext;
+use crate::util::constants::BYTES_IN_ADDRESS;
+use crate::util::options::{GCTriggerSelector, PlanSelector};
+use crate::util::test_util::mock_method::*;
+use crate::util::test_util::mock_vm::*;
+use crate::util::{
+    Address, ObjectReference, OpaquePointer, VMMutatorThread, VMThread, VMWorkerThread,
+};
+use crate::vm::slot::Slot;
+use crate::vm::{GCThreadContext, RootsWorkFactory};
+use crate::{AllocationSemantics, MMTKBuilder, MMTK};
+
+/// The number of reference fields in each object.
+pub const NUM_FIELDS: usize = 2;
+/// Each object has a header word followed by the reference fields.
+pub const OBJECT_SIZE: usize = DEFAULT_OBJECT_REF_OFFSET + (NUM_FIELDS + 1) * BYTES_IN_ADDRESS;
+/// The number of root slots.
+pub const NUM_ROOTS: usize = 2;
+
+/// How long a test waits for the GC before it fails.  Don't block the CI.
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+type RootsFactory<C> = ProcessEdgesWorkRootsWorkFactory<
+    MockVM,
+    <C as GCWorkContext>::DefaultProcessEdges,
+    <C as GCWorkContext>::PinningProcessEdges,
+>;
+
+/// Root slots.  They hold the raw addresses of objects.
+static ROOTS: [AtomicUsize; NUM_ROOTS] = [AtomicUsize::new(0), AtomicUsize::new(0)];
+/// The only mutator.
+static MUTATOR: AtomicUsize = AtomicUsize::new(0);
+
+/// How many pauses have finished, i.e. how many times the mutators have been resumed.  A GC of a
+/// stop-the-world plan has one pause.
+static PAUSES: Mutex<usize> = Mutex::new(0);
+static PAUSE_FINISHED: Condvar = Condvar::new();
+/// Whether the mutators are stopped.
+static MUTATORS_STOPPED: AtomicBool = AtomicBool::new(false);
+
+pub fn the_mutator() -> &'static mut Mutator<MockVM> {
+    unsafe { &mut *(MUTATOR.load(Ordering::SeqCst) as *mut Mutator<MockVM>) }
+}
+
+/// The address of the reference field `index` of `object`.
+pub fn field(object: ObjectReference, index: usize) -> Address {
+    object.to_raw_address() + (index + 1) * BYTES_IN_ADDRESS
+}
+
+/// The address of the root slot `index`.
+pub fn root_slot(index: usize) -> Address {
+    Address::from_ref(&ROOTS[index])
+}
+
+/// The object in the root slot `index`, which must not be empty.
+pub fn root(index: usize) -> ObjectReference {
+    Slot::load(&root_slot(index)).unwrap()
+}
+
+/// Allocate an object with the default semantics.  Its fields are null.
+pub fn alloc_object(mutator: &mut Mutator<MockVM>) -> ObjectReference {
+    let semantics = AllocationSemantics::Default;
+    let addr = memory_manager::alloc(mutator, OBJECT_SIZE, BYTES_IN_ADDRESS, 0, semantics);
+    assert!(!addr.is_zero());
+    let object = MockVM::object_start_to_ref(addr);
+    // Initialize the header and the fields, as a VM would.  The memory may hold a dead object.
+    for i in 0..=NUM_FIELDS {
+        unsafe { (object.to_raw_address() + i * BYTES_IN_ADDRESS).store(Address::ZERO) };
+    }
+    memory_manager::post_alloc(mutator, object, OBJECT_SIZE, semantics);
+    object
+}
+
+/// Request a GC, and wait until it finishes.
+pub fn collect(mmtk: &'static MMTK<MockVM>) {
+    wait_for_gc(|| mmtk.gc_requester.request());
+}
+
+/// Call `request`, which should trigger a GC of a stop-the-world plan, and wait until the GC
+/// finishes.
+pub fn wait_for_gc(request: impl FnOnce()) {
+    let pauses = PAUSES.lock().unwrap();
+    let expected = *pauses + 1;
+    request();
+    let (_pauses, timeout_result) = PAUSE_FINISHED
+        .wait_timeout_while(pauses, TIMEOUT, |pauses| *pauses < expected)
+        .unwrap();
+    assert!(!timeout_result.timed_out());
+}
+
+/// How many pauses have finished since [`setup`].
+pub fn pauses() -> usize {
+    *PAUSES.lock().unwrap()
+}
+
+/// Wait until `pauses` pauses have finished since [`setup`].
+pub fn wait_for_pauses(pauses: usize) {
+    let (_pauses, timeout_result) = PAUSE_FINISHED
+        .wait_timeout_while(PAUSES.lock().unwrap(), TIMEOUT, |finished| {
+            *finished < pauses
+        })
+        .unwrap();
+    assert!(!timeout_result.timed_out());
+}
+
+/// Whether the mutators are stopped for a pause.
+pub fn mutators_stopped() -> bool {
+    MUTATORS_STOPPED.load(Ordering::SeqCst)
+}
+
+/// Report the root slots to whichever of the work contexts `C1` and `C2` the current trace uses.
+/// A plan with only one work context uses it as both.
+pub struct ScanVMSpecificRoots<C1, C2 = C1>(PhantomData<fn() -> (C1, C2)>);
+
+impl<C1, C2> Default for ScanVMSpecificRoots<C1, C2> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<C1, C2> ScanVMSpecificRoots<C1, C2> {
+    fn create_roots_work<F: RootsWorkFactory<Address>>(mut factory: F) {
+        // One packet for each root, so that objects reachable from different roots are traced in
+        // different work packets.
+        for i in 0..NUM_ROOTS {
+            factory.create_process_roots_work(vec![root_slot(i)]);
+        }
+    }
+}
+
+impl<C1, C2> MockAny for ScanVMSpecificRoots<C1, C2>
+where
+    C1: GCWorkContext<VM = MockVM>,
+    C2: GCWorkContext<VM = MockVM>,
+{
+    fn call_any(&mut self, args: Box<dyn Any>) -> Box<dyn Any> {
+        match args.downcast::<(VMWorkerThread, Box<RootsFactory<C1>>)>() {
+            Ok(args) => Self::create_roots_work(*args.1),
+            Err(args) => {
+                let args = args
+                    .downcast::<(VMWorkerThread, Box<RootsFactory<C2>>)>()
+                    .unwrap();
+                Self::create_roots_work(*args.1)
+            }
+        }
+        Box::new(())
+    }
+}
+
+/// Return the same value for all the work contexts.
+pub struct ReturnAny<R: Clone + 'static>(pub R);
+
+impl<R: Clone + 'static> MockAny for ReturnAny<R> {
+    fn call_any(&mut self, _args: Box<dyn Any>) -> Box<dyn Any> {
+        Box::new(self.0.clone())
+    }
+}
+
+/// Create the MockVM for a plan whose traces use the work contexts `C1` and `C2`.  The root slots
+/// and the pause count are cleared.
+pub fn setup<C1, C2>() -> MockVM
+where
+    C1: GCWorkContext<VM = MockVM>,
+    C2: GCWorkContext<VM = MockVM>,
+{
+    for root in ROOTS.iter() {
+        root.store(0, Ordering::SeqCst);
+    }
+    *PAUSES.lock().unwrap() = 0;
+
+    MockVM {
+        number_of_mutators: MockMethod::new_fixed(Box::new(|_| 1)),
+        mutator: MockMethod::new_fixed(Box::new(|_| the_mutator())),
+        mutators: MockMethod::new_fixed(Box::new(|_| Box::new(std::iter::once(the_mutator())))),
+        stop_all_mutators: MockMethod::new_fixed(Box::new(|(_, mut visitor)| {
+            MUTATORS_STOPPED.store(true, Ordering::SeqCst);
+            visitor(the_mutator())
+        })),
+        resume_mutators: MockMethod::new_fixed(Box::new(|_| {
+            MUTATORS_STOPPED.store(false, Ordering::SeqCst);
+            *PAUSES.lock().unwrap() += 1;
+            PAUSE_FINISHED.notify_all();
+        })),
+        block_for_gc: MockMethod::new_default(),
+        spawn_gc_thread: MockMethod::new_fixed(Box::new(|(_, context)| {
+            let GCThreadContext::Worker(worker) = context;
+            std::thread::spawn(move || {
+                // Any non-null pointer will do.
+                let tls = VMWorkerThread(VMThread(OpaquePointer::from_address(Address::from_ref(
+                    worker.as_ref(),
+                ))));
+                memory_manager::start_worker(worker.mmtk, tls, worker);
+            });
+        })),
+        get_object_size: MockMethod::new_fixed(Box::new(|_| OBJECT_SIZE)),
+        get_object_size_when_copied: MockMethod::new_fixed(Box::new(|_| OBJECT_SIZE)),
+        get_object_reference_when_copied_to: MockMethod::new_fixed(Box::new(|(_, to)| {
+            MockVM::object_start_to_ref(to)
+        })),
+        copy_object_to: MockMethod::new_fixed(Box::new(|(from, to, _)| {
+            let from_start = from.to_raw_address() - DEFAULT_OBJECT_REF_OFFSET;
+            let to_start = to.to_raw_address() - DEFAULT_OBJECT_REF_OFFSET;
+            unsafe {
+                std::ptr::copy::<u8>(from_start.to_ptr(), to_start.to_mut_ptr(), OBJECT_SIZE);
+            }
+            to_start + OBJECT_SIZE
+        })),
+        scan_object: MockMethod::new_fixed(Box::new(|(_, object, slot_visitor)| {
+            for i in 0..NUM_FIELDS {
+                slot_visitor.visit_slot(field(object, i));
+            }
+        })),
+        scan_roots_in_mutator_thread: Box::new(ReturnAny(())),
+        scan_vm_specific_roots: Box::new(ScanVMSpecificRoots::<C1, C2>::default()),
+        notify_initial_thread_scan_complete: MockMethod::new_default(),
+        prepare_for_roots_re_scanning: MockMethod::new_default(),
+        process_weak_refs: Box::new(ReturnAny(false)),
+        forward_weak_refs: Box::new(ReturnAny(())),
+        ..MockVM::default()
+    }
+}
+
+/// Create an MMTk instance of `plan` with a 64MB heap and two GC workers, initialize collection,
+/// and bind the only mutator.  `configure` may change the other options.
+pub fn create_mmtk(
+    plan: PlanSelector,
+    configure: impl FnOnce(&mut MMTKBuilder),
+) -> (&'static MMTK<MockVM>, &'static mut Mutator<MockVM>) {
+    let mut builder = MMTKBuilder::new();
+    builder.options.plan.set(plan);
+    builder
+        .options
+        .gc_trigger
+        .set(GCTriggerSelector::FixedHeapSize(64 * 1024 * 1024));
+    builder.options.threads.set(2);
+    configure(&mut builder);
+    let mmtk: &'static MMTK<MockVM> = Box::leak(Box::new(builder.build::<MockVM>()));
+    mmtk.initialize_collection(VMThread::UNINITIALIZED);
+    let mutator = Box::leak(memory_manager::bind_mutator(
+        mmtk,
+        VMMutatorThread(VMThread::UNINITIALIZED),
+    ));
+    MUTATOR.store(mutator as *mut Mutator<MockVM> as usize, Ordering::SeqCst);
+    (mmtk, mutator)
+}
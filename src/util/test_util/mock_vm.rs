@@ -8,7 +8,7 @@ use crate::scheduler::gc_work::SFTProcessEdges;
 use crate::scheduler::*;
 use crate::util::alloc::AllocationError;
 use crate::util::copy::*;
-use crate::util::heap::gc_trigger::GCTriggerPolicy;
+use crate::util::heap::gc_trigger::{DelegatedGCTrigger, SoftHeapLimitStage};
 use crate::util::opaque_pointer::*;
 use crate::util::{Address, ObjectReference};
 use crate::vm::object_model::specs::*;
@@ -59,6 +59,13 @@ macro_rules! mock {
         write_mockvm(|mock| mock.$fn.call(($($arg),*)))
     };
 }
+/// Call `MockMethod` after releasing the lock of the MockVM, so that the mock method may call
+/// other mock methods.
+macro_rules! mock_reentrant {
+    ($fn: ident($($arg:expr),*)) => {
+        write_mockvm(|mock| mock.$fn.prepare_call())(($($arg),*))
+    };
+}
 /// Call `MockAny`.
 macro_rules! mock_any {
     ($fn: ident($($arg:expr),*)) => {
@@ -210,13 +217,16 @@ pub struct MockVM {
     pub post_forwarding: MockMethod<VMWorkerThread, ()>,
     pub vm_live_bytes: MockMethod<(), usize>,
     pub is_collection_enabled: MockMethod<(), bool>,
-    pub create_gc_trigger: MockMethod<(), Box<dyn GCTriggerPolicy<MockVM>>>,
+    pub create_gc_trigger: MockMethod<(), Box<dyn DelegatedGCTrigger<MockVM>>>,
+    pub soft_heap_limit_exceeded: MockMethod<SoftHeapLimitStage, ()>,
     // object model
+    /// It is called without holding the lock of the MockVM.  The default copies the object with
+    /// [`MockVM::copy_object_default`].
     pub copy_object: MockMethod<
         (
             ObjectReference,
             CopySemantics,
-            &'static GCWorkerCopyContext<MockVM>,
+            &'static mut GCWorkerCopyContext<MockVM>,
         ),
         ObjectReference,
     >,
@@ -246,6 +256,8 @@ pub struct MockVM {
         ),
         (),
     >,
+    pub reference_array_slice:
+        MockMethod<(VMWorkerThread, ObjectReference), Option<<MockVM as VMBinding>::VMMemorySlice>>,
     pub scan_object_and_trace_edges: MockMethod<
         (
             VMWorkerThread,
@@ -286,8 +298,11 @@ impl Default for MockVM {
             vm_live_bytes: MockMethod::new_default(),
             is_collection_enabled: MockMethod::new_fixed(Box::new(|_| true)),
             create_gc_trigger: MockMethod::new_unimplemented(),
+            soft_heap_limit_exceeded: MockMethod::new_default(),
 
-            copy_object: MockMethod::new_unimplemented(),
+            copy_object: MockMethod::new_fixed(Box::new(|(from, semantics, copy_context)| {
+                MockVM::copy_object_default(from, semantics, copy_context)
+            })),
             copy_object_to: MockMethod::new_unimplemented(),
             get_object_size: MockMethod::new_unimplemented(),
             get_object_size_when_copied: MockMethod::new_unimplemented(),
@@ -310,6 +325,7 @@ impl Default for MockVM {
 
             support_slot_enqueuing: MockMethod::new_fixed(Box::new(|_| true)),
             scan_object: MockMethod::new_unimplemented(),
+            reference_array_slice: MockMethod::new_fixed(Box::new(|_| None)),
             scan_object_and_trace_edges: MockMethod::new_unimplemented(),
             // We instantiate a `MockMethod` with the arguments as ProcessEdgesWorkRootsWorkFactory<..., SFTProcessEdges<MockVM>, ...>,
             // thus the mock method expects the actual call arguments to match the type.
@@ -457,9 +473,13 @@ impl crate::vm::Collection<MockVM> for MockVM {
         mock!(vm_live_bytes())
     }
 
-    fn create_gc_trigger() -> Box<dyn GCTriggerPolicy<MockVM>> {
+    fn create_gc_trigger() -> Box<dyn DelegatedGCTrigger<MockVM>> {
         mock!(create_gc_trigger())
     }
+
+    fn soft_heap_limit_exceeded(stage: SoftHeapLimitStage) {
+        mock!(soft_heap_limit_exceeded(stage))
+    }
 }
 
 impl crate::vm::ObjectModel<MockVM> for MockVM {
@@ -468,7 +488,11 @@ impl crate::vm::ObjectModel<MockVM> for MockVM {
         VMLocalForwardingPointerSpec::in_header(0);
     const LOCAL_FORWARDING_BITS_SPEC: VMLocalForwardingBitsSpec =
         VMLocalForwardingBitsSpec::in_header(0);
+    #[cfg(not(feature = "mock_test_side_mark_bit"))]
     const LOCAL_MARK_BIT_SPEC: VMLocalMarkBitSpec = VMLocalMarkBitSpec::in_header(0);
+    // ImmixSpace only supports side mark bits.
+    #[cfg(feature = "mock_test_side_mark_bit")]
+    const LOCAL_MARK_BIT_SPEC: VMLocalMarkBitSpec = VMLocalMarkBitSpec::side_first();
     const LOCAL_LOS_MARK_NURSERY_SPEC: VMLocalLOSMarkNurserySpec =
         VMLocalLOSMarkNurserySpec::in_header(0);
 
@@ -482,7 +506,8 @@ impl crate::vm::ObjectModel<MockVM> for MockVM {
         semantics: CopySemantics,
         copy_context: &mut GCWorkerCopyContext<MockVM>,
     ) -> ObjectReference {
-        mock!(copy_object(from, semantics, lifetime!(copy_context)))
+        // Allocating with the copy context may call other mock methods.
+        mock_reentrant!(copy_object(from, semantics, lifetime!(copy_context)))
     }
 
     fn copy_to(from: ObjectReference, to: ObjectReference, region: Address) -> Address {
@@ -560,6 +585,12 @@ impl crate::vm::Scanning<MockVM> for MockVM {
             lifetime!(slot_visitor as &mut dyn SlotVisitor<<MockVM as VMBinding>::VMSlot>)
         ))
     }
+    fn reference_array_slice(
+        tls: VMWorkerThread,
+        object: ObjectReference,
+    ) -> Option<<MockVM as VMBinding>::VMMemorySlice> {
+        mock!(reference_array_slice(tls, object))
+    }
     fn scan_object_and_trace_edges<OT: ObjectTracer>(
         tls: VMWorkerThread,
         object: ObjectReference,
@@ -614,6 +645,29 @@ impl crate::vm::Scanning<MockVM> for MockVM {
 }
 
 impl MockVM {
+    /// The default implementation of `copy_object`.  It allocates with the copy context, and
+    /// copies the bytes from the object start.
+    pub fn copy_object_default(
+        from: ObjectReference,
+        semantics: CopySemantics,
+        copy_context: &mut GCWorkerCopyContext<MockVM>,
+    ) -> ObjectReference {
+        use crate::vm::ObjectModel;
+        let bytes = Self::get_size_when_copied(from);
+        let align = Self::get_align_when_copied(from);
+        let offset = Self::get_align_offset_when_copied(from);
+        let from_start = Self::ref_to_object_start(from);
+        let to_start = copy_context.alloc_copy(from, bytes, align, offset, semantics);
+        debug_assert!(!to_start.is_zero());
+        unsafe {
+            std::ptr::copy_nonoverlapping::<u8>(from_start.to_ptr(), to_start.to_mut_ptr(), bytes)
+        };
+        let to = ObjectReference::from_raw_address(to_start + (from.to_raw_address() - from_start))
+            .unwrap();
+        copy_context.post_copy(to, bytes, semantics);
+        to
+    }
+
     pub fn object_start_to_ref(start: Address) -> ObjectReference {
         ObjectReference::from_raw_address(start + DEFAULT_OBJECT_REF_OFFSET).unwrap()
     }
@@ -1,5 +1,5 @@
 use crate::util::alloc::AllocationError;
-use crate::util::heap::gc_trigger::GCTriggerPolicy;
+use crate::util::heap::gc_trigger::{DelegatedGCTrigger, SoftHeapLimitStage};
 use crate::util::opaque_pointer::*;
 use crate::vm::VMBinding;
 use crate::{scheduler::*, Mutator};
@@ -19,6 +19,10 @@ pub trait Collection<VM: VMBinding> {
     /// MMTk provides a callback function and expects the binding to use the callback for each mutator when it
     /// is ready for stack scanning. Usually a stack can be scanned as soon as the thread stops in the yieldpoint.
     ///
+    /// If concurrent work is done in incremental pauses (see [`crate::util::options::PauseBudget`]), MMTk also
+    /// calls this method to start an incremental pause, which is not a GC.  In that case, MMTk does not scan the
+    /// stacks of the mutators passed to `mutator_visitor`.
+    ///
     /// Arguments:
     /// * `tls`: The thread pointer for the GC worker.
     /// * `mutator_visitor`: A callback.  Call it with a mutator as argument to notify MMTk that the mutator is ready to be scanned.
@@ -26,7 +30,8 @@ pub trait Collection<VM: VMBinding> {
     where
         F: FnMut(&'static mut Mutator<VM>);
 
-    /// Resume all the mutator threads, the opposite of the above. When a GC is finished, MMTk calls this method.
+    /// Resume all the mutator threads, the opposite of the above. When a GC or an incremental pause is finished,
+    /// MMTk calls this method.
     ///
     /// This method may not be called by the same GC thread that called `stop_all_mutators`.
     ///
@@ -59,12 +64,15 @@ pub trait Collection<VM: VMBinding> {
     fn spawn_gc_thread(tls: VMThread, ctx: GCThreadContext<VM>);
 
     /// Inform the VM of an out-of-memory error. The binding should hook into the VM's error
-    /// routine for OOM. Note that there are two different categories of OOM:
+    /// routine for OOM. Note that there are three different categories of OOM:
     ///  * Critical OOM: This is the case where the OS is unable to mmap or acquire more memory.
     ///    MMTk expects the VM to abort immediately if such an error is thrown.
     ///  * Heap OOM: This is the case where the specified heap size is insufficient to execute the
     ///    application. MMTk expects the binding to notify the VM about this OOM. MMTk makes no
     ///    assumptions about whether the VM will continue executing or abort immediately.
+    ///  * Space budget: This is the case where a space exceeds its budget set by the option
+    ///    [`space_budgets`](crate::util::options::Options::space_budgets). Like heap OOM, MMTk
+    ///    makes no assumptions about whether the VM will continue executing.
     ///
     /// See [`AllocationError`] for more information.
     ///
@@ -75,6 +83,16 @@ pub trait Collection<VM: VMBinding> {
         panic!("Out of memory with {:?}!", err_kind);
     }
 
+    /// Inform the VM that the heap usage exceeds the soft heap limit (the option
+    /// [`soft_heap_limit`](crate::util::options::Options::soft_heap_limit)), and MMTk is taking the
+    /// given step to bring the usage below the limit. This is called before the GC of the step is
+    /// requested, so the VM can release caches and other memory it can recreate. MMTk calls this
+    /// in a mutator thread that polls for GC.
+    ///
+    /// Arguments:
+    /// * `stage`: The step that MMTk is taking.
+    fn soft_heap_limit_exceeded(_stage: SoftHeapLimitStage) {}
+
     /// Inform the VM to schedule finalization threads.
     ///
     /// Arguments:
@@ -157,9 +175,10 @@ pub trait Collection<VM: VMBinding> {
         true
     }
 
-    /// Ask the binding to create a [`GCTriggerPolicy`] if the option `gc_trigger` is set to
-    /// `crate::util::options::GCTriggerSelector::Delegated`.
-    fn create_gc_trigger() -> Box<dyn GCTriggerPolicy<VM>> {
+    /// Ask the binding to create a [`DelegatedGCTrigger`] if the option `gc_trigger` is set to
+    /// `crate::util::options::GCTriggerSelector::Delegated`. MMTk calls this once when it creates
+    /// the GC trigger.
+    fn create_gc_trigger() -> Box<dyn DelegatedGCTrigger<VM>> {
         unimplemented!()
     }
 }
@@ -196,6 +196,29 @@ pub trait Scanning<VM: VMBinding> {
         slot_visitor: &mut SV,
     );
 
+    /// Return the reference fields of an object as a memory slice, if the object is an array whose
+    /// reference fields can be scanned in parallel.
+    ///
+    /// This is called for objects that support slot enqueuing before [`Scanning::scan_object`].
+    /// If this returns `Some`, MMTk core visits the slots of the returned slice instead of calling
+    /// `scan_object` on the object.  The slice must therefore contain all the reference fields of
+    /// the object.  For large slices, MMTk core creates separate work packets that split the slice
+    /// with [`MemorySlice::split_off`](crate::vm::slot::MemorySlice::split_off) and scan its parts on different GC workers.
+    ///
+    /// The default implementation returns `None`.  VMs with large reference arrays should return
+    /// their elements here.  Like [`Scanning::support_slot_enqueuing`], this method is called for
+    /// every object to be scanned, so it must be fast.
+    ///
+    /// Arguments:
+    /// * `tls`: The VM-specific thread-local storage for the current worker.
+    /// * `object`: The object to be scanned.
+    fn reference_array_slice(
+        _tls: VMWorkerThread,
+        _object: ObjectReference,
+    ) -> Option<VM::VMMemorySlice> {
+        None
+    }
+
     /// Delegated scanning of a object, visiting each reference field encountered, and tracing the
     /// objects pointed by each field.
     ///
@@ -86,6 +86,12 @@ pub trait Slot: Copy + Send + Debug + PartialEq + Eq + Hash {
     fn prefetch_store(&self) {
         // no-op by default
     }
+
+    /// Return the address of the slot in memory.
+    ///
+    /// Plans that use a field logging barrier (currently RCImmix) keep an unlog bit for every slot
+    /// of an object in the side metadata, indexed by the address of the slot.
+    fn to_address(&self) -> Address;
 }
 
 /// A simple slot implementation that represents a word-sized slot which holds the raw address of
@@ -128,6 +134,10 @@ impl Slot for SimpleSlot {
     fn store(&self, object: ObjectReference) {
         unsafe { (*self.slot_addr).store(object.to_raw_address(), atomic::Ordering::Relaxed) }
     }
+
+    fn to_address(&self) -> Address {
+        self.as_address()
+    }
 }
 
 /// For backword compatibility, we let `Address` implement `Slot` with the same semantics as
@@ -149,6 +159,10 @@ impl Slot for Address {
     fn store(&self, object: ObjectReference) {
         unsafe { Address::store(*self, object) }
     }
+
+    fn to_address(&self) -> Address {
+        *self
+    }
 }
 
 #[test]
@@ -178,6 +192,14 @@ pub trait MemorySlice: Send + Debug + PartialEq + Eq + Clone + Hash {
     fn bytes(&self) -> usize;
     /// Memory copy support
     fn copy(src: &Self, tgt: &Self);
+    /// Split the slice at roughly `offset` bytes from its start, keeping the first part in `self`
+    /// and returning the rest.  Implementations may move the split point to a slot boundary.
+    /// Return `None` if the slice cannot be split there, which is the default.
+    ///
+    /// MMTk uses this to scan a large slice in parallel.
+    fn split_off(&mut self, _offset: usize) -> Option<Self> {
+        None
+    }
 }
 
 /// Iterate slots within `Range<Address>`.
@@ -238,6 +260,16 @@ impl MemorySlice for Range<Address> {
             std::ptr::copy(src, tgt, words)
         }
     }
+
+    fn split_off(&mut self, offset: usize) -> Option<Self> {
+        let mid = self.start + crate::util::conversions::raw_align_down(offset, BYTES_IN_ADDRESS);
+        if mid <= self.start || mid >= self.end {
+            return None;
+        }
+        let rest = mid..self.end;
+        self.end = mid;
+        Some(rest)
+    }
 }
 
 /// Memory slice type with empty implementations.
@@ -304,4 +336,22 @@ mod tests {
         MemorySlice::copy(&src_slice, &dst_slice);
         assert_eq!(dst.iter().sum::<u8>(), src.len() as u8);
     }
+
+    #[test]
+    fn split_address_ranges() {
+        let start = unsafe { Address::from_usize(0x1000) };
+        let mut slice = start..start + 8 * BYTES_IN_ADDRESS;
+        // The split point is aligned down to a slot.
+        let rest = slice.split_off(3 * BYTES_IN_ADDRESS + 1).unwrap();
+        assert_eq!(slice, start..start + 3 * BYTES_IN_ADDRESS);
+        assert_eq!(
+            rest,
+            start + 3 * BYTES_IN_ADDRESS..start + 8 * BYTES_IN_ADDRESS
+        );
+        // Both parts must not be empty.
+        assert_eq!(slice.split_off(0), None);
+        assert_eq!(slice.split_off(BYTES_IN_ADDRESS - 1), None);
+        assert_eq!(slice.split_off(3 * BYTES_IN_ADDRESS), None);
+        assert_eq!(slice, start..start + 3 * BYTES_IN_ADDRESS);
+    }
 }
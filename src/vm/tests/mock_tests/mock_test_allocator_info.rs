@@ -27,8 +27,13 @@ pub fn test_allocator_info() {
                 | PlanSelector::SemiSpace
                 | PlanSelector::GenCopy
                 | PlanSelector::GenImmix
+                | PlanSelector::GenMarkSweep
                 | PlanSelector::MarkCompact
-                | PlanSelector::StickyImmix => {
+                | PlanSelector::StickyImmix
+                | PlanSelector::ConcurrentImmix
+                | PlanSelector::Regional
+                | PlanSelector::RCImmix
+                | PlanSelector::SemiImmix => {
                     // These plans all use bump pointer allocator.
                     let AllocatorInfo::BumpPointer {
                         bump_pointer_offset,
@@ -51,6 +56,10 @@ pub fn test_allocator_info() {
                         assert!(matches!(allocator_info, AllocatorInfo::Unimplemented))
                     }
                 }
+                PlanSelector::StickyMarkSweep => {
+                    // StickyMarkSweep always uses a free list allocator, which we haven't implemented.
+                    assert!(matches!(allocator_info, AllocatorInfo::Unimplemented))
+                }
                 // We provide no info for a large object allocator
                 PlanSelector::PageProtect => assert!(matches!(allocator_info, AllocatorInfo::None)),
             }
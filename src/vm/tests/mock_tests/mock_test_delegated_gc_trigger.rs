@@ -0,0 +1,82 @@
+// GITHUB-CI: MMTK_PLAN=all
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use super::mock_test_prelude::*;
+use crate::util::heap::{DelegatedGCTrigger, HeapStats, SpaceStats};
+use crate::util::options::GCTriggerSelector;
+
+const MB: usize = 1024 * 1024;
+
+/// The largest number of pages allocated since the last GC that the trigger has seen.
+static ALLOCATED_PAGES: AtomicUsize = AtomicUsize::new(0);
+/// The names of the spaces that the trigger has seen.
+static SPACES: Mutex<Vec<&'static str>> = Mutex::new(vec![]);
+/// The trigger requests a GC once this many pages are allocated.
+static GC_AFTER_PAGES: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// A trigger that records what it sees, and requests a GC once `GC_AFTER_PAGES` pages are allocated.
+struct TestTrigger;
+
+impl DelegatedGCTrigger<MockVM> for TestTrigger {
+    fn initial_heap_size(&self) -> usize {
+        MB
+    }
+
+    fn max_heap_size(&self) -> usize {
+        4 * MB
+    }
+
+    fn is_gc_required(
+        &self,
+        _space_full: bool,
+        _space: Option<SpaceStats<MockVM>>,
+        heap: &HeapStats<MockVM>,
+    ) -> bool {
+        let allocated = heap.allocated_pages_since_last_gc();
+        ALLOCATED_PAGES.fetch_max(allocated, Ordering::SeqCst);
+        let mut spaces = SPACES.lock().unwrap();
+        heap.for_each_space(&mut |space| {
+            if !spaces.contains(&space.name()) {
+                spaces.push(space.name());
+            }
+        });
+        allocated >= GC_AFTER_PAGES.load(Ordering::SeqCst)
+    }
+}
+
+#[test]
+#[should_panic(expected = "block_for_gc is called")]
+pub fn delegated_gc_trigger() {
+    with_mockvm(
+        || -> MockVM {
+            MockVM {
+                create_gc_trigger: MockMethod::new_fixed(Box::new(|_| Box::new(TestTrigger))),
+                ..mock_vm_panicking_in_block_for_gc()
+            }
+        },
+        || {
+            let mut fixture = MutatorFixture::create_with_builder(|builder| {
+                builder.options.gc_trigger.set(GCTriggerSelector::Delegated);
+            });
+            assert_eq!(memory_manager::total_bytes(fixture.mmtk()), MB);
+
+            // The trigger sees the allocation and the spaces.
+            alloc_bytes(&mut fixture.mutator, MB >> 2);
+            assert!(ALLOCATED_PAGES.load(Ordering::SeqCst) > 0);
+            assert!(!SPACES.lock().unwrap().is_empty());
+
+            // The binding can change the heap size up to the max heap size.
+            assert!(memory_manager::set_heap_size(fixture.mmtk(), 2 * MB));
+            assert_eq!(memory_manager::total_bytes(fixture.mmtk()), 2 * MB);
+            assert!(!memory_manager::set_heap_size(fixture.mmtk(), 8 * MB));
+            assert_eq!(memory_manager::total_bytes(fixture.mmtk()), 2 * MB);
+
+            // The heap is not full, but the trigger asks for a GC.
+            GC_AFTER_PAGES.store(ALLOCATED_PAGES.load(Ordering::SeqCst) + 1, Ordering::SeqCst);
+            alloc_bytes(&mut fixture.mutator, MB >> 2);
+        },
+        no_cleanup,
+    )
+}
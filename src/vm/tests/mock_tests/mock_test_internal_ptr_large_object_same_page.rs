@@ -1,4 +1,4 @@
-// GITHUB-CI: MMTK_PLAN=Immix,GenImmix,StickyImmix,MarkSweep,MarkCompact
+// GITHUB-CI: MMTK_PLAN=Immix,GenImmix,GenMarkSweep,StickyImmix,StickyMarkSweep,MarkSweep,MarkCompact,Regional
 // GITHUB-CI: FEATURES=is_mmtk_object
 
 // Only test this with plans that use LOS. NoGC does not use large object space.
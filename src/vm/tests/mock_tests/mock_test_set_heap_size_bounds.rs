@@ -0,0 +1,34 @@
+// GITHUB-CI: MMTK_PLAN=all
+
+use super::mock_test_prelude::*;
+
+const MB: usize = 1024 * 1024;
+
+#[test]
+#[should_panic(expected = "block_for_gc is called")]
+pub fn set_heap_size_bounds() {
+    with_mockvm(
+        mock_vm_panicking_in_block_for_gc,
+        || {
+            let mut fixture = MutatorFixture::create_with_heapsize(MB);
+            let mmtk = fixture.mmtk();
+
+            // Invalid bounds are rejected, and the heap size does not change.
+            assert!(memory_manager::set_heap_size_bounds(mmtk, 2 * MB, MB).is_err());
+            assert!(memory_manager::set_heap_size_bounds(mmtk, 0, 0).is_err());
+            assert!(memory_manager::set_heap_size_bounds(mmtk, MB, usize::MAX).is_err());
+            assert_eq!(memory_manager::total_bytes(mmtk), MB);
+
+            // The fixed heap grows to the new max heap size.
+            assert!(memory_manager::set_heap_size_bounds(mmtk, MB, 4 * MB).is_ok());
+            assert_eq!(memory_manager::total_bytes(mmtk), 4 * MB);
+            alloc_bytes(&mut fixture.mutator, MB >> 1);
+
+            // Shrink the heap below the memory in use. The next poll needs a GC.
+            assert!(memory_manager::set_heap_size_bounds(mmtk, MB >> 3, MB >> 2).is_ok());
+            assert_eq!(memory_manager::total_bytes(mmtk), MB >> 2);
+            memory_manager::gc_poll(mmtk, fixture.mutator.mutator_tls);
+        },
+        no_cleanup,
+    )
+}
@@ -92,6 +92,10 @@ mod compressed_oop {
             let compressed = (expanded >> 3) as u32;
             unsafe { (*self.slot_addr).store(compressed, atomic::Ordering::Relaxed) }
         }
+
+        fn to_address(&self) -> Address {
+            self.as_address()
+        }
     }
 
     // Two 35-bit addresses aligned to 8 bytes (3 zeros in the lowest bits).
@@ -185,6 +189,10 @@ mod offset_slot {
             let middle = begin + self.offset;
             unsafe { (*self.slot_addr).store(middle, atomic::Ordering::Relaxed) }
         }
+
+        fn to_address(&self) -> Address {
+            self.slot_address()
+        }
     }
 
     pub const OFFSET: usize = 48;
@@ -268,6 +276,10 @@ mod tagged_slot {
             let new_tagged = new_untagged | (old_tagged & Self::TAG_BITS_MASK);
             unsafe { (*self.slot_addr).store(new_tagged, atomic::Ordering::Relaxed) }
         }
+
+        fn to_address(&self) -> Address {
+            Address::from_mut_ptr(self.slot_addr)
+        }
     }
 
     pub const TAG1: usize = 0b01;
@@ -380,6 +392,16 @@ mod mixed {
                 DummyVMSlot::Tagged(e) => e.store(object),
             }
         }
+
+        fn to_address(&self) -> Address {
+            match self {
+                DummyVMSlot::Simple(e) => e.to_address(),
+                #[cfg(target_pointer_width = "64")]
+                DummyVMSlot::Compressed(e) => e.to_address(),
+                DummyVMSlot::Offset(e) => e.to_address(),
+                DummyVMSlot::Tagged(e) => e.to_address(),
+            }
+        }
     }
 
     #[test]
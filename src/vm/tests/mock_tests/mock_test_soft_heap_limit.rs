@@ -0,0 +1,57 @@
+// GITHUB-CI: MMTK_PLAN=all
+
+use std::sync::Mutex;
+
+use super::mock_test_prelude::*;
+use crate::util::heap::SoftHeapLimitStage;
+use crate::util::options::GCTriggerSelector;
+
+const MB: usize = 1024 * 1024;
+
+/// The steps that the binding is told about.
+static STAGES: Mutex<Vec<SoftHeapLimitStage>> = Mutex::new(vec![]);
+/// The steps that we expect before the first GC.
+static EXPECTED_STAGES: Mutex<Vec<SoftHeapLimitStage>> = Mutex::new(vec![]);
+
+#[test]
+#[should_panic(expected = "block_for_gc is called")]
+pub fn soft_heap_limit() {
+    with_mockvm(
+        || -> MockVM {
+            MockVM {
+                soft_heap_limit_exceeded: MockMethod::new_fixed(Box::new(|stage| {
+                    STAGES.lock().unwrap().push(stage)
+                })),
+                block_for_gc: MockMethod::new_fixed(Box::new(|_| {
+                    // The first GC is for the soft heap limit, long before the heap is full.
+                    assert_eq!(*STAGES.lock().unwrap(), *EXPECTED_STAGES.lock().unwrap());
+                    panic!("block_for_gc is called")
+                })),
+                ..MockVM::default()
+            }
+        },
+        || {
+            let mut fixture = MutatorFixture::create_with_builder(|builder| {
+                builder
+                    .options
+                    .gc_trigger
+                    .set(GCTriggerSelector::FixedHeapSize(8 * MB));
+                builder.options.soft_heap_limit.set(MB);
+            });
+            let plan = fixture.mmtk().get_plan();
+            // A plan without GC cannot do anything for the limit, and only polls when the heap is full.
+            if plan.constraints().collects_garbage {
+                EXPECTED_STAGES
+                    .lock()
+                    .unwrap()
+                    .push(if plan.generational().is_some() {
+                        SoftHeapLimitStage::NurseryGC
+                    } else {
+                        SoftHeapLimitStage::FullHeapGC
+                    });
+            }
+            alloc_bytes(&mut fixture.mutator, 16 * MB);
+        },
+        no_cleanup,
+    )
+}
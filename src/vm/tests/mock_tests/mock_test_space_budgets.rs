@@ -0,0 +1,60 @@
+// GITHUB-CI: MMTK_PLAN=SemiSpace,GenCopy,GenImmix,GenMarkSweep,MarkSweep,PageProtect,Immix,MarkCompact,StickyImmix,StickyMarkSweep,ConcurrentImmix,SemiImmix,RCImmix,Regional
+// NoGC allocates everything into one space unless the feature nogc_multi_space is enabled.
+
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use super::mock_test_prelude::*;
+use crate::util::options::{GCTriggerSelector, SpaceBudgets};
+use crate::AllocationSemantics;
+
+const MB: usize = 1024 * 1024;
+
+/// The allocation errors that the binding is told about.
+static ERRORS: Mutex<Vec<String>> = Mutex::new(vec![]);
+
+#[test]
+#[should_panic(expected = "block_for_gc is called")]
+pub fn space_budgets() {
+    with_mockvm(
+        || -> MockVM {
+            MockVM {
+                out_of_memory: MockMethod::new_fixed(Box::new(|(_, err)| {
+                    ERRORS.lock().unwrap().push(format!("{:?}", err))
+                })),
+                ..mock_vm_panicking_in_block_for_gc()
+            }
+        },
+        || {
+            let mut fixture = MutatorFixture::create_with_builder(|builder| {
+                builder
+                    .options
+                    .gc_trigger
+                    .set(GCTriggerSelector::FixedHeapSize(16 * MB));
+                assert!(builder
+                    .options
+                    .space_budgets
+                    .set(SpaceBudgets::from_str("immortal:1m:Error,los:1m").unwrap()));
+            });
+
+            // The immortal space fails allocations beyond its budget, well before the heap is full.
+            let allocated = try_alloc_bytes(
+                &mut fixture.mutator,
+                4 * MB,
+                1024,
+                AllocationSemantics::Immortal,
+            );
+            assert!(allocated <= MB);
+            assert_eq!(*ERRORS.lock().unwrap(), vec!["SpaceBudgetExceeded"]);
+
+            // The large object space triggers a GC when it exceeds its budget.
+            try_alloc_bytes(
+                &mut fixture.mutator,
+                4 * MB,
+                64 * 1024,
+                AllocationSemantics::Los,
+            );
+        },
+        no_cleanup,
+    )
+}